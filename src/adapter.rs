@@ -2,7 +2,15 @@
 
 use futures_core::Stream;
 
-use crate::{sys, AdapterEvent, AdvertisingDevice, ConnectionEvent, Device, DeviceId, Result, Uuid};
+use crate::advertiser::{Advertisement, AdvertisingHandle};
+use crate::error::ErrorKind;
+#[cfg(feature = "l2cap")]
+use crate::l2cap_channel::L2capListener;
+use crate::server::{Application, GattServer};
+use crate::{
+    sys, AdapterEvent, AdvertisingDevice, ConnectionEvent, Device, DeviceId, DeviceTracker, Error, Result, ScanOptions,
+    TrackerOptions, Uuid,
+};
 
 /// The system's Bluetooth adapter interface.
 ///
@@ -11,7 +19,7 @@ use crate::{sys, AdapterEvent, AdvertisingDevice, ConnectionEvent, Device, Devic
 pub struct Adapter(sys::adapter::AdapterImpl);
 
 /// Configuration options when creating the Bluetooth adapter interface.
-pub type AdapterConfig = sys::adapter::AdapterConfig;
+pub type AdapterConfig = crate::platform::adapter::AdapterConfig;
 
 impl Adapter {
     /// Creates an interface to a Bluetooth adapter using the provided config.
@@ -19,6 +27,14 @@ impl Adapter {
         sys::adapter::AdapterImpl::with_config(config).await.map(Adapter)
     }
 
+    /// Creates an interface to the simulated adapter described by `mock`.
+    ///
+    /// See the [`mock`][crate::mock] module for details.
+    #[cfg(feature = "mock")]
+    pub fn with_mock(mock: crate::mock::MockAdapter) -> Self {
+        Adapter(sys::adapter::AdapterImpl::with_mock(mock))
+    }
+
     /// Creates an interface to a Bluetooth adapter using the default config.
     #[inline]
     pub async fn default() -> Result<Self> {
@@ -27,6 +43,23 @@ impl Adapter {
             .map(Adapter)
     }
 
+    /// Uses sockets acquired from BlueZ with `AcquireNotify` and `AcquireWrite` for notifications and writes without
+    /// response to the devices opened through the returned adapter.
+    ///
+    /// This avoids a D-Bus round trip for every value, which improves throughput for characteristics that send or
+    /// receive many values. Once a socket has been acquired for a characteristic,
+    /// [`Characteristic::max_write_len`][crate::Characteristic::max_write_len] reports the MTU negotiated for it.
+    /// Indications, and characteristics for which BlueZ refuses to hand out a socket, still use D-Bus.
+    ///
+    /// # Platform specific
+    ///
+    /// Only available on Linux. Has no effect on simulated adapters.
+    #[cfg(target_os = "linux")]
+    pub fn with_acquire_io(mut self, acquire_io: bool) -> Self {
+        self.0.set_acquire_io(acquire_io);
+        self
+    }
+
     /// A stream of [`AdapterEvent`] which allows the application to identify when the adapter is enabled or disabled.
     #[inline]
     pub async fn events(&self) -> Result<impl Stream<Item = Result<AdapterEvent>> + Send + Unpin + '_> {
@@ -75,6 +108,13 @@ impl Adapter {
     ///
     /// If `services` is not empty, returns advertisements including at least one GATT service with a UUID in
     /// `services`. Otherwise returns all advertisements.
+    ///
+    /// # Platform specifics
+    ///
+    /// ## Linux
+    ///
+    /// BlueZ does not report individual advertising packets. An advertisement is returned when a device is first
+    /// discovered and again whenever its RSSI, manufacturer data, service data or transmit power changes.
     #[inline]
     pub async fn scan<'a>(
         &'a self,
@@ -83,6 +123,51 @@ impl Adapter {
         self.0.scan(services).await
     }
 
+    /// Starts scanning for Bluetooth advertising packets using the provided options.
+    ///
+    /// Returns a stream of [`AdvertisingDevice`] structs which contain the data from the advertising packet and the
+    /// [`Device`] which sent it. Scanning is automatically stopped when the stream is dropped. Only advertisements
+    /// satisfying every filter in `options` are returned. Unless [`ScanOptions::allow_duplicates`] is set, only the first
+    /// advertisement from each device is returned.
+    ///
+    /// # Platform specifics
+    ///
+    /// Returns a [`NotSupported`][crate::error::ErrorKind::NotSupported] error if [`ScanTransport::BrEdr`] is requested
+    /// on any platform other than Linux.
+    ///
+    /// ## Linux
+    ///
+    /// The filters are passed to BlueZ as a discovery filter. If another scan is already active on the same adapter,
+    /// the filters are only applied by Bluest.
+    ///
+    /// [`ScanTransport::BrEdr`]: crate::ScanTransport::BrEdr
+    #[inline]
+    pub async fn scan_with_options(
+        &self,
+        options: ScanOptions,
+    ) -> Result<impl Stream<Item = AdvertisingDevice> + Send + Unpin + '_> {
+        self.0.scan_with_options(options).await
+    }
+
+    /// Starts scanning and tracking the devices seen.
+    ///
+    /// Returns a [`DeviceTracker`] built on [`Adapter::scan_with_options`], which reports when devices appear, are
+    /// updated and are lost. Every advertisement is reported to the tracker, so that devices which keep advertising are
+    /// not lost. Scanning is automatically stopped when the tracker is dropped.
+    #[inline]
+    pub async fn track_devices<'a>(
+        &'a self,
+        services: &'a [Uuid],
+        options: TrackerOptions,
+    ) -> Result<DeviceTracker<'a>> {
+        let scan_options = ScanOptions {
+            services: services.to_vec(),
+            allow_duplicates: true,
+            ..Default::default()
+        };
+        Ok(DeviceTracker::new(self.scan_with_options(scan_options).await?, options))
+    }
+
     /// Finds Bluetooth devices providing any service in `services`.
     ///
     /// Returns a stream of [`Device`] structs with matching connected devices returned first. If the stream is not
@@ -122,7 +207,7 @@ impl Adapter {
     /// device. This connection will be maintained until [`disconnect_device`][Self::disconnect_device] is called.
     #[inline]
     pub async fn connect_device(&self, device: &Device) -> Result<()> {
-        self.0.connect_device(device).await
+        self.0.connect_device(&device.0).await
     }
 
     /// Disconnects from the [`Device`]
@@ -145,7 +230,7 @@ impl Adapter {
     /// This method disconnects the device from the system, even if other applications are using the device.
     #[inline]
     pub async fn disconnect_device(&self, device: &Device) -> Result<()> {
-        self.0.disconnect_device(device).await
+        self.0.disconnect_device(&device.0).await
     }
 
     /// Monitors a device for connection/disconnection events.
@@ -167,6 +252,66 @@ impl Adapter {
         &'a self,
         device: &'a Device,
     ) -> Result<impl Stream<Item = ConnectionEvent> + Send + Unpin + 'a> {
-        self.0.device_connection_events(device).await
+        self.0.device_connection_events(&device.0).await
+    }
+
+    /// Publishes the local GATT services described by `application` to remote devices (GATT Server role).
+    ///
+    /// The services stay published until the returned [`GattServer`] is dropped. See the [`server`][crate::server]
+    /// module for details.
+    ///
+    /// # Platform specifics
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS, Windows and Android.
+    #[inline]
+    pub async fn serve_gatt(&self, application: Application) -> Result<GattServer> {
+        self.0.serve_gatt(application).await
+    }
+
+    /// Starts sending `advertisement` (Peripheral or Broadcaster role).
+    ///
+    /// Advertising continues until the returned [`AdvertisingHandle`] is dropped. See the
+    /// [`advertiser`][crate::advertiser] module for details.
+    ///
+    /// Returns an [`InvalidParameter`][crate::error::ErrorKind::InvalidParameter] error if the minimum advertising
+    /// interval is greater than the maximum.
+    ///
+    /// # Platform specifics
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS, Windows and Android.
+    ///
+    /// ## Linux
+    ///
+    /// The number of simultaneous advertisements is limited by the controller. BlueZ returns an error once the limit
+    /// is reached.
+    #[inline]
+    pub async fn advertise(&self, advertisement: Advertisement) -> Result<AdvertisingHandle> {
+        if let (Some(min), Some(max)) = (advertisement.min_interval, advertisement.max_interval) {
+            if min > max {
+                return Err(Error::new(
+                    ErrorKind::InvalidParameter,
+                    None,
+                    "the minimum advertising interval is greater than the maximum",
+                ));
+            }
+        }
+        self.0.advertise(advertisement).await
+    }
+
+    /// Listens for L2CAP connection-oriented channels (CoC) opened by remote devices.
+    ///
+    /// Channels are accepted on `psm`, or on a PSM dynamically assigned by the platform if `psm` is `None`. The PSM is
+    /// available from [`L2capListener::psm`]. If `secure` is `true`, channels are only accepted over encrypted links.
+    ///
+    /// # Platform specifics
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS and Android.
+    ///
+    /// The `l2cap` feature is not available on Windows.
+    #[cfg(feature = "l2cap")]
+    #[inline]
+    pub async fn listen_l2cap(&self, psm: Option<u16>, secure: bool) -> Result<L2capListener> {
+        let listener = self.0.listen_l2cap(psm, secure).await?;
+        Ok(L2capListener(listener))
     }
 }
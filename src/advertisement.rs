@@ -0,0 +1,571 @@
+//! Parsing of raw advertising data (AD) and extended inquiry response (EIR) payloads
+//!
+//! Both payloads are a sequence of AD structures as described in the Bluetooth Core Specification, Vol 3, Part C, §11.
+//! The format of each AD type is defined in the Bluetooth Core Specification Supplement (CSS), Part A.
+//!
+//! ```
+//! use bluest::advertisement::{AdFlags, AdStructure};
+//!
+//! let raw = [0x02, 0x01, 0x06, 0x05, 0x09, b'T', b'e', b's', b't'];
+//! let structures = AdStructure::parse_all(&raw).unwrap();
+//! assert_eq!(structures[0], AdStructure::Flags(AdFlags::from_bits(0x06)));
+//! assert_eq!(structures[1], AdStructure::CompleteLocalName("Test".to_string()));
+//! ```
+
+use std::time::Duration;
+
+use crate::error::ErrorKind;
+use crate::{AdvertisementData, BluetoothUuidExt, Error, ManufacturerData, Result, Uuid};
+
+/// Assigned number identifying the type of an AD structure. See the Bluetooth Assigned Numbers document, §2.3.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AdType(u8);
+
+impl AdType {
+    /// Flags (CSS §A.1.3)
+    pub const FLAGS: AdType = AdType(0x01);
+    /// Incomplete list of 16-bit service UUIDs (CSS §A.1.1)
+    pub const INCOMPLETE_SERVICE_UUIDS_16: AdType = AdType(0x02);
+    /// Complete list of 16-bit service UUIDs (CSS §A.1.1)
+    pub const COMPLETE_SERVICE_UUIDS_16: AdType = AdType(0x03);
+    /// Incomplete list of 32-bit service UUIDs (CSS §A.1.1)
+    pub const INCOMPLETE_SERVICE_UUIDS_32: AdType = AdType(0x04);
+    /// Complete list of 32-bit service UUIDs (CSS §A.1.1)
+    pub const COMPLETE_SERVICE_UUIDS_32: AdType = AdType(0x05);
+    /// Incomplete list of 128-bit service UUIDs (CSS §A.1.1)
+    pub const INCOMPLETE_SERVICE_UUIDS_128: AdType = AdType(0x06);
+    /// Complete list of 128-bit service UUIDs (CSS §A.1.1)
+    pub const COMPLETE_SERVICE_UUIDS_128: AdType = AdType(0x07);
+    /// Shortened local name (CSS §A.1.2)
+    pub const SHORTENED_LOCAL_NAME: AdType = AdType(0x08);
+    /// Complete local name (CSS §A.1.2)
+    pub const COMPLETE_LOCAL_NAME: AdType = AdType(0x09);
+    /// Tx power level (CSS §A.1.5)
+    pub const TX_POWER_LEVEL: AdType = AdType(0x0a);
+    /// Class of device (CSS §A.1.6)
+    pub const CLASS_OF_DEVICE: AdType = AdType(0x0d);
+    /// Simple pairing hash C-192 (CSS §A.1.6)
+    pub const SIMPLE_PAIRING_HASH_C192: AdType = AdType(0x0e);
+    /// Simple pairing randomizer R-192 (CSS §A.1.6)
+    pub const SIMPLE_PAIRING_RANDOMIZER_R192: AdType = AdType(0x0f);
+    /// Security manager TK value (CSS §A.1.8), also used for the Device ID profile in EIR data
+    pub const SECURITY_MANAGER_TK_VALUE: AdType = AdType(0x10);
+    /// Security manager out of band flags (CSS §A.1.7)
+    pub const SECURITY_MANAGER_OOB_FLAGS: AdType = AdType(0x11);
+    /// Peripheral connection interval range (CSS §A.1.9)
+    pub const PERIPHERAL_CONNECTION_INTERVAL_RANGE: AdType = AdType(0x12);
+    /// List of 16-bit service solicitation UUIDs (CSS §A.1.10)
+    pub const SERVICE_SOLICITATION_16: AdType = AdType(0x14);
+    /// List of 128-bit service solicitation UUIDs (CSS §A.1.10)
+    pub const SERVICE_SOLICITATION_128: AdType = AdType(0x15);
+    /// Service data with a 16-bit UUID (CSS §A.1.11)
+    pub const SERVICE_DATA_16: AdType = AdType(0x16);
+    /// Public target address (CSS §A.1.13)
+    pub const PUBLIC_TARGET_ADDRESS: AdType = AdType(0x17);
+    /// Random target address (CSS §A.1.14)
+    pub const RANDOM_TARGET_ADDRESS: AdType = AdType(0x18);
+    /// Appearance (CSS §A.1.12)
+    pub const APPEARANCE: AdType = AdType(0x19);
+    /// Advertising interval (CSS §A.1.15)
+    pub const ADVERTISING_INTERVAL: AdType = AdType(0x1a);
+    /// LE Bluetooth device address (CSS §A.1.16)
+    pub const LE_BLUETOOTH_DEVICE_ADDRESS: AdType = AdType(0x1b);
+    /// LE role (CSS §A.1.17)
+    pub const LE_ROLE: AdType = AdType(0x1c);
+    /// Simple pairing hash C-256 (CSS §A.1.6)
+    pub const SIMPLE_PAIRING_HASH_C256: AdType = AdType(0x1d);
+    /// Simple pairing randomizer R-256 (CSS §A.1.6)
+    pub const SIMPLE_PAIRING_RANDOMIZER_R256: AdType = AdType(0x1e);
+    /// List of 32-bit service solicitation UUIDs (CSS §A.1.10)
+    pub const SERVICE_SOLICITATION_32: AdType = AdType(0x1f);
+    /// Service data with a 32-bit UUID (CSS §A.1.11)
+    pub const SERVICE_DATA_32: AdType = AdType(0x20);
+    /// Service data with a 128-bit UUID (CSS §A.1.11)
+    pub const SERVICE_DATA_128: AdType = AdType(0x21);
+    /// LE secure connections confirmation value (CSS §A.1.6)
+    pub const LE_SECURE_CONNECTIONS_CONFIRMATION_VALUE: AdType = AdType(0x22);
+    /// LE secure connections random value (CSS §A.1.6)
+    pub const LE_SECURE_CONNECTIONS_RANDOM_VALUE: AdType = AdType(0x23);
+    /// URI (CSS §A.1.18)
+    pub const URI: AdType = AdType(0x24);
+    /// Indoor positioning
+    pub const INDOOR_POSITIONING: AdType = AdType(0x25);
+    /// Transport discovery data
+    pub const TRANSPORT_DISCOVERY_DATA: AdType = AdType(0x26);
+    /// LE supported features (CSS §A.1.19)
+    pub const LE_SUPPORTED_FEATURES: AdType = AdType(0x27);
+    /// Channel map update indication (CSS §A.1.20)
+    pub const CHANNEL_MAP_UPDATE_INDICATION: AdType = AdType(0x28);
+    /// Mesh provisioning bearer
+    pub const PB_ADV: AdType = AdType(0x29);
+    /// Mesh message
+    pub const MESH_MESSAGE: AdType = AdType(0x2a);
+    /// Mesh beacon
+    pub const MESH_BEACON: AdType = AdType(0x2b);
+    /// BIGInfo (CSS §A.1.21)
+    pub const BIGINFO: AdType = AdType(0x2c);
+    /// Broadcast code (CSS §A.1.22)
+    pub const BROADCAST_CODE: AdType = AdType(0x2d);
+    /// Resolvable set identifier (CSS §A.1.23)
+    pub const RESOLVABLE_SET_IDENTIFIER: AdType = AdType(0x2e);
+    /// Advertising interval - long (CSS §A.1.15)
+    pub const ADVERTISING_INTERVAL_LONG: AdType = AdType(0x2f);
+    /// Broadcast name (CSS §A.1.24)
+    pub const BROADCAST_NAME: AdType = AdType(0x30);
+    /// Encrypted advertising data (CSS §A.1.25)
+    pub const ENCRYPTED_ADVERTISING_DATA: AdType = AdType(0x31);
+    /// Periodic advertising response timing information (CSS §A.1.26)
+    pub const PERIODIC_ADVERTISING_RESPONSE_TIMING_INFORMATION: AdType = AdType(0x32);
+    /// Electronic shelf label (CSS §A.1.27)
+    pub const ELECTRONIC_SHELF_LABEL: AdType = AdType(0x34);
+    /// 3D information data
+    pub const THREE_D_INFORMATION_DATA: AdType = AdType(0x3d);
+    /// Manufacturer specific data (CSS §A.1.4)
+    pub const MANUFACTURER_SPECIFIC_DATA: AdType = AdType(0xff);
+
+    /// Converts a [`u8`] value to an [`AdType`].
+    pub const fn from_u8(val: u8) -> Self {
+        AdType(val)
+    }
+
+    /// Converts an [`AdType`] to a [`u8`] value.
+    pub const fn as_u8(self) -> u8 {
+        self.0
+    }
+}
+
+impl From<u8> for AdType {
+    fn from(number: u8) -> Self {
+        AdType(number)
+    }
+}
+
+impl From<AdType> for u8 {
+    fn from(val: AdType) -> Self {
+        val.0
+    }
+}
+
+/// The flags AD type (CSS §A.1.3)
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AdFlags(u8);
+
+impl AdFlags {
+    /// LE limited discoverable mode
+    pub const LE_LIMITED_DISCOVERABLE: AdFlags = AdFlags(0x01);
+    /// LE general discoverable mode
+    pub const LE_GENERAL_DISCOVERABLE: AdFlags = AdFlags(0x02);
+    /// BR/EDR not supported
+    pub const BR_EDR_NOT_SUPPORTED: AdFlags = AdFlags(0x04);
+    /// Simultaneous LE and BR/EDR to same device capable (controller)
+    pub const SIMULTANEOUS_LE_BR_EDR: AdFlags = AdFlags(0x08);
+
+    /// Raw transmutation from [`u8`].
+    pub const fn from_bits(bits: u8) -> Self {
+        AdFlags(bits)
+    }
+
+    /// Raw transmutation to [`u8`].
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Returns `true` if all of the flags in `other` are set in `self`.
+    pub const fn contains(self, other: AdFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl std::ops::BitOr for AdFlags {
+    type Output = AdFlags;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        AdFlags(self.0 | rhs.0)
+    }
+}
+
+/// The LE role AD type (CSS §A.1.17)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LeRole {
+    /// Only the peripheral role is supported
+    PeripheralOnly,
+    /// Only the central role is supported
+    CentralOnly,
+    /// Both roles are supported, the peripheral role is preferred for connection establishment
+    PeripheralPreferred,
+    /// Both roles are supported, the central role is preferred for connection establishment
+    CentralPreferred,
+}
+
+/// The preferred connection interval range of a peripheral (CSS §A.1.9)
+///
+/// A bound of `None` means the peripheral has no specific preference for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ConnectionIntervalRange {
+    /// Minimum connection interval
+    pub min: Option<Duration>,
+    /// Maximum connection interval
+    pub max: Option<Duration>,
+}
+
+/// A Bluetooth device address in the LE Bluetooth device address AD type (CSS §A.1.16)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LeDeviceAddress {
+    /// The address, most significant octet first
+    pub address: [u8; 6],
+    /// Set to true for a random device address, false for a public device address
+    pub is_random: bool,
+}
+
+/// A single AD structure decoded from an advertising or extended inquiry response payload
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AdStructure {
+    /// Flags (CSS §A.1.3)
+    Flags(AdFlags),
+    /// Incomplete list of 16, 32 or 128-bit service UUIDs (CSS §A.1.1)
+    IncompleteServiceUuids(Vec<Uuid>),
+    /// Complete list of 16, 32 or 128-bit service UUIDs (CSS §A.1.1)
+    CompleteServiceUuids(Vec<Uuid>),
+    /// Shortened local name (CSS §A.1.2)
+    ShortenedLocalName(String),
+    /// Complete local name (CSS §A.1.2)
+    CompleteLocalName(String),
+    /// Tx power level in dBm (CSS §A.1.5)
+    TxPowerLevel(i8),
+    /// Class of device (CSS §A.1.6)
+    ClassOfDevice(u32),
+    /// Peripheral connection interval range (CSS §A.1.9)
+    ConnectionIntervalRange(ConnectionIntervalRange),
+    /// List of 16, 32 or 128-bit service solicitation UUIDs (CSS §A.1.10)
+    ServiceSolicitation(Vec<Uuid>),
+    /// Service data with a 16, 32 or 128-bit UUID (CSS §A.1.11)
+    ServiceData(Uuid, Vec<u8>),
+    /// Appearance (CSS §A.1.12)
+    Appearance(u16),
+    /// Public target addresses, most significant octet first (CSS §A.1.13)
+    PublicTargetAddress(Vec<[u8; 6]>),
+    /// Random target addresses, most significant octet first (CSS §A.1.14)
+    RandomTargetAddress(Vec<[u8; 6]>),
+    /// Advertising interval, including the long variant (CSS §A.1.15)
+    AdvertisingInterval(Duration),
+    /// LE Bluetooth device address (CSS §A.1.16)
+    LeDeviceAddress(LeDeviceAddress),
+    /// LE role (CSS §A.1.17)
+    LeRole(LeRole),
+    /// URI (CSS §A.1.18)
+    ///
+    /// The `http:` and `https:` scheme name string code points are expanded. URIs with other schemes are returned as
+    /// encoded.
+    Uri(String),
+    /// Manufacturer specific data (CSS §A.1.4)
+    ManufacturerData(ManufacturerData),
+    /// An AD type which is not decoded by this crate
+    Other(AdType, Vec<u8>),
+}
+
+impl AdStructure {
+    /// Decodes the data of a single AD structure of type `ad_type`.
+    ///
+    /// Returns an error with a kind of [`InvalidParameter`][ErrorKind::InvalidParameter] if `data` is not valid for
+    /// `ad_type`.
+    pub fn parse(ad_type: AdType, data: &[u8]) -> Result<Self> {
+        let structure = match ad_type {
+            AdType::FLAGS => AdStructure::Flags(AdFlags(data.first().copied().unwrap_or_default())),
+            AdType::INCOMPLETE_SERVICE_UUIDS_16 => AdStructure::IncompleteServiceUuids(uuid_list(data, 2)?),
+            AdType::COMPLETE_SERVICE_UUIDS_16 => AdStructure::CompleteServiceUuids(uuid_list(data, 2)?),
+            AdType::INCOMPLETE_SERVICE_UUIDS_32 => AdStructure::IncompleteServiceUuids(uuid_list(data, 4)?),
+            AdType::COMPLETE_SERVICE_UUIDS_32 => AdStructure::CompleteServiceUuids(uuid_list(data, 4)?),
+            AdType::INCOMPLETE_SERVICE_UUIDS_128 => AdStructure::IncompleteServiceUuids(uuid_list(data, 16)?),
+            AdType::COMPLETE_SERVICE_UUIDS_128 => AdStructure::CompleteServiceUuids(uuid_list(data, 16)?),
+            AdType::SHORTENED_LOCAL_NAME => AdStructure::ShortenedLocalName(utf8(data)?),
+            AdType::COMPLETE_LOCAL_NAME => AdStructure::CompleteLocalName(utf8(data)?),
+            AdType::TX_POWER_LEVEL => AdStructure::TxPowerLevel(i8::from_le_bytes(array(data)?)),
+            AdType::CLASS_OF_DEVICE => {
+                let [a, b, c] = array(data)?;
+                AdStructure::ClassOfDevice(u32::from_le_bytes([a, b, c, 0]))
+            }
+            AdType::PERIPHERAL_CONNECTION_INTERVAL_RANGE => {
+                let [a, b, c, d] = array(data)?;
+                let interval = |val: u16| (val != 0xffff).then(|| Duration::from_micros(u64::from(val) * 1250));
+                AdStructure::ConnectionIntervalRange(ConnectionIntervalRange {
+                    min: interval(u16::from_le_bytes([a, b])),
+                    max: interval(u16::from_le_bytes([c, d])),
+                })
+            }
+            AdType::SERVICE_SOLICITATION_16 => AdStructure::ServiceSolicitation(uuid_list(data, 2)?),
+            AdType::SERVICE_SOLICITATION_32 => AdStructure::ServiceSolicitation(uuid_list(data, 4)?),
+            AdType::SERVICE_SOLICITATION_128 => AdStructure::ServiceSolicitation(uuid_list(data, 16)?),
+            AdType::SERVICE_DATA_16 => service_data(data, 2)?,
+            AdType::SERVICE_DATA_32 => service_data(data, 4)?,
+            AdType::SERVICE_DATA_128 => service_data(data, 16)?,
+            AdType::APPEARANCE => AdStructure::Appearance(u16::from_le_bytes(array(data)?)),
+            AdType::PUBLIC_TARGET_ADDRESS => AdStructure::PublicTargetAddress(address_list(data)?),
+            AdType::RANDOM_TARGET_ADDRESS => AdStructure::RandomTargetAddress(address_list(data)?),
+            AdType::ADVERTISING_INTERVAL | AdType::ADVERTISING_INTERVAL_LONG => {
+                if !matches!(data.len(), 2..=4) {
+                    return Err(invalid_length(ad_type));
+                }
+                let mut bytes = [0u8; 4];
+                bytes[..data.len()].copy_from_slice(data);
+                AdStructure::AdvertisingInterval(Duration::from_micros(u64::from(u32::from_le_bytes(bytes)) * 625))
+            }
+            AdType::LE_BLUETOOTH_DEVICE_ADDRESS => {
+                let [a, b, c, d, e, f, kind] = array(data)?;
+                AdStructure::LeDeviceAddress(LeDeviceAddress {
+                    address: [f, e, d, c, b, a],
+                    is_random: kind & 0x01 != 0,
+                })
+            }
+            AdType::LE_ROLE => AdStructure::LeRole(match array(data)? {
+                [0x00] => LeRole::PeripheralOnly,
+                [0x01] => LeRole::CentralOnly,
+                [0x02] => LeRole::PeripheralPreferred,
+                [0x03] => LeRole::CentralPreferred,
+                _ => return Err(Error::new(ErrorKind::InvalidParameter, None, "invalid LE role")),
+            }),
+            AdType::URI => AdStructure::Uri(uri(data)?),
+            AdType::MANUFACTURER_SPECIFIC_DATA => {
+                if data.len() < 2 {
+                    return Err(invalid_length(ad_type));
+                }
+                AdStructure::ManufacturerData(ManufacturerData {
+                    company_id: u16::from_le_bytes([data[0], data[1]]),
+                    data: data[2..].to_vec(),
+                })
+            }
+            _ => AdStructure::Other(ad_type, data.to_vec()),
+        };
+
+        Ok(structure)
+    }
+
+    /// Decodes every AD structure in a raw advertising or extended inquiry response payload.
+    ///
+    /// Parsing stops at the first AD structure with a length of zero, which marks the start of the non-significant
+    /// part of the payload.
+    pub fn parse_all(raw: &[u8]) -> Result<Vec<Self>> {
+        AdStructures::new(raw)
+            .map(|res| res.and_then(|(ad_type, data)| AdStructure::parse(ad_type, data)))
+            .collect()
+    }
+}
+
+/// An iterator over the undecoded AD structures in a raw advertising or extended inquiry response payload
+///
+/// Yields an error with a kind of [`InvalidParameter`][ErrorKind::InvalidParameter] and stops if the payload is
+/// truncated.
+#[derive(Debug, Clone)]
+pub struct AdStructures<'a> {
+    raw: &'a [u8],
+}
+
+impl<'a> AdStructures<'a> {
+    /// Creates an iterator over the AD structures in `raw`.
+    pub fn new(raw: &'a [u8]) -> Self {
+        AdStructures { raw }
+    }
+}
+
+impl<'a> Iterator for AdStructures<'a> {
+    type Item = Result<(AdType, &'a [u8])>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (&len, rest) = self.raw.split_first()?;
+        let len = usize::from(len);
+        if len == 0 {
+            self.raw = &[];
+            return None;
+        }
+        if rest.len() < len {
+            self.raw = &[];
+            return Some(Err(Error::new(
+                ErrorKind::InvalidParameter,
+                None,
+                "AD structure extends past the end of the payload",
+            )));
+        }
+        let (structure, rest) = rest.split_at(len);
+        self.raw = rest;
+        Some(Ok((AdType(structure[0]), &structure[1..])))
+    }
+}
+
+impl AdvertisementData {
+    /// Decodes a raw advertising or extended inquiry response payload.
+    ///
+    /// AD structures with invalid contents are ignored, but an error with a kind of
+    /// [`InvalidParameter`][ErrorKind::InvalidParameter] is returned if the payload is truncated. Connectability is not
+    /// part of the payload so `is_connectable` is always `false`. The payload is retained in
+    /// [`raw`][AdvertisementData::raw].
+    pub fn from_raw(raw: &[u8]) -> Result<Self> {
+        let mut adv_data = AdvertisementData::default();
+        for res in AdStructures::new(raw) {
+            let (ad_type, data) = res?;
+            if let Ok(structure) = AdStructure::parse(ad_type, data) {
+                adv_data.merge(structure);
+            }
+        }
+        adv_data.raw = Some(raw.to_vec());
+        Ok(adv_data)
+    }
+
+    /// Adds the information in `structure` to `self`.
+    pub(crate) fn merge(&mut self, structure: AdStructure) {
+        match structure {
+            AdStructure::Flags(flags) => self.flags = Some(flags),
+            AdStructure::IncompleteServiceUuids(uuids) | AdStructure::CompleteServiceUuids(uuids) => {
+                extend_unique(&mut self.services, uuids)
+            }
+            AdStructure::ShortenedLocalName(name) => {
+                if self.local_name.is_none() {
+                    self.local_name = Some(name);
+                    self.is_local_name_shortened = true;
+                }
+            }
+            AdStructure::CompleteLocalName(name) => {
+                self.local_name = Some(name);
+                self.is_local_name_shortened = false;
+            }
+            AdStructure::TxPowerLevel(level) => self.tx_power_level = Some(level.into()),
+            AdStructure::ConnectionIntervalRange(range) => self.connection_interval_range = Some(range),
+            AdStructure::ServiceSolicitation(uuids) => extend_unique(&mut self.solicited_services, uuids),
+            AdStructure::ServiceData(uuid, data) => {
+                self.service_data.insert(uuid, data);
+            }
+            AdStructure::Appearance(appearance) => self.appearance = Some(appearance),
+            AdStructure::AdvertisingInterval(interval) => self.advertising_interval = Some(interval),
+            AdStructure::LeRole(role) => self.le_role = Some(role),
+            AdStructure::Uri(uri) => self.uri = Some(uri),
+            AdStructure::ManufacturerData(data) => {
+                if self.manufacturer_data.is_none() {
+                    self.manufacturer_data = Some(data.clone());
+                }
+                self.manufacturer_data_list.push(data);
+            }
+            AdStructure::ClassOfDevice(_)
+            | AdStructure::PublicTargetAddress(_)
+            | AdStructure::RandomTargetAddress(_)
+            | AdStructure::LeDeviceAddress(_)
+            | AdStructure::Other(..) => (),
+        }
+    }
+
+    /// Combines the data received in a later advertisement or scan response from the same device with `self`.
+    ///
+    /// Fields present in `other` replace those in `self`, lists are extended and a complete local name is never
+    /// replaced by a shortened one.
+    pub(crate) fn combine(&mut self, other: AdvertisementData) {
+        if let Some(name) = other.local_name {
+            if self.local_name.is_none() || self.is_local_name_shortened || !other.is_local_name_shortened {
+                self.local_name = Some(name);
+                self.is_local_name_shortened = other.is_local_name_shortened;
+            }
+        }
+        for data in other.manufacturer_data_list {
+            match self
+                .manufacturer_data_list
+                .iter_mut()
+                .find(|x| x.company_id == data.company_id)
+            {
+                Some(entry) => *entry = data,
+                None => self.manufacturer_data_list.push(data),
+            }
+        }
+        self.manufacturer_data = self.manufacturer_data_list.first().cloned();
+        extend_unique(&mut self.services, other.services);
+        self.service_data.extend(other.service_data);
+        self.tx_power_level = other.tx_power_level.or(self.tx_power_level);
+        self.is_connectable |= other.is_connectable;
+        self.flags = other.flags.or(self.flags);
+        self.appearance = other.appearance.or(self.appearance);
+        extend_unique(&mut self.solicited_services, other.solicited_services);
+        self.connection_interval_range = other.connection_interval_range.or(self.connection_interval_range);
+        self.uri = other.uri.or(self.uri.take());
+        self.le_role = other.le_role.or(self.le_role);
+        self.advertising_interval = other.advertising_interval.or(self.advertising_interval);
+        self.raw = other.raw.or(self.raw.take());
+    }
+}
+
+fn extend_unique(uuids: &mut Vec<Uuid>, new: Vec<Uuid>) {
+    for uuid in new {
+        if !uuids.contains(&uuid) {
+            uuids.push(uuid);
+        }
+    }
+}
+
+fn invalid_length(ad_type: AdType) -> Error {
+    Error::new(
+        ErrorKind::InvalidParameter,
+        None,
+        format!("invalid length for AD type 0x{:02x}", ad_type.0),
+    )
+}
+
+fn array<const N: usize>(data: &[u8]) -> Result<[u8; N]> {
+    data.try_into()
+        .map_err(|_| Error::new(ErrorKind::InvalidParameter, None, "invalid AD structure length"))
+}
+
+fn utf8(data: &[u8]) -> Result<String> {
+    String::from_utf8(data.to_vec())
+        .map_err(|err| Error::new(ErrorKind::InvalidParameter, Some(Box::new(err)), "invalid UTF-8 string"))
+}
+
+fn uuid(data: &[u8]) -> Uuid {
+    match data.len() {
+        2 => Uuid::from_u16(u16::from_le_bytes([data[0], data[1]])),
+        4 => Uuid::from_u32(u32::from_le_bytes([data[0], data[1], data[2], data[3]])),
+        _ => Uuid::from_u128(u128::from_le_bytes(data.try_into().unwrap())),
+    }
+}
+
+fn uuid_list(data: &[u8], uuid_len: usize) -> Result<Vec<Uuid>> {
+    let chunks = data.chunks_exact(uuid_len);
+    if !chunks.remainder().is_empty() {
+        return Err(Error::new(
+            ErrorKind::InvalidParameter,
+            None,
+            "invalid UUID list length",
+        ));
+    }
+    Ok(chunks.map(uuid).collect())
+}
+
+fn service_data(data: &[u8], uuid_len: usize) -> Result<AdStructure> {
+    if data.len() < uuid_len {
+        return Err(Error::new(
+            ErrorKind::InvalidParameter,
+            None,
+            "invalid service data length",
+        ));
+    }
+    let (id, value) = data.split_at(uuid_len);
+    Ok(AdStructure::ServiceData(uuid(id), value.to_vec()))
+}
+
+fn address_list(data: &[u8]) -> Result<Vec<[u8; 6]>> {
+    let chunks = data.chunks_exact(6);
+    if !chunks.remainder().is_empty() {
+        return Err(Error::new(
+            ErrorKind::InvalidParameter,
+            None,
+            "invalid address list length",
+        ));
+    }
+    Ok(chunks.map(|x| [x[5], x[4], x[3], x[2], x[1], x[0]]).collect())
+}
+
+fn uri(data: &[u8]) -> Result<String> {
+    let uri = utf8(data)?;
+    let mut chars = uri.chars();
+    let scheme = match chars.next() {
+        Some('\u{01}') => "",
+        Some('\u{16}') => "http:",
+        Some('\u{17}') => "https:",
+        _ => return Ok(uri),
+    };
+    Ok(format!("{}{}", scheme, chars.as_str()))
+}
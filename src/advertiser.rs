@@ -0,0 +1,103 @@
+//! Advertisements sent by the local adapter (Peripheral and Broadcaster roles)
+//!
+//! An [`Advertisement`] describes the data to advertise and how to advertise it. It is published with
+//! [`Adapter::advertise`][crate::Adapter::advertise], which returns an [`AdvertisingHandle`]. Advertising continues
+//! until the [`AdvertisingHandle`] is dropped.
+//!
+//! ```rust,no_run
+//!# use bluest::advertiser::Advertisement;
+//!# use bluest::btuuid::services;
+//!# use bluest::Adapter;
+//!# #[tokio::main]
+//!# async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!let adapter = Adapter::default().await?;
+//!
+//!let mut advertisement = Advertisement::default();
+//!advertisement.local_name = Some("Battery".to_string());
+//!advertisement.services.push(services::BATTERY);
+//!
+//!let _handle = adapter.advertise(advertisement).await?;
+//!#    Ok(())
+//!# }
+//! ```
+//!
+//! # Platform specific
+//!
+//! Only supported on Linux and by the `mock` backend. Returns [`NotSupported`][crate::error::ErrorKind::NotSupported]
+//! on MacOS/iOS, Windows and Android.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::{sys, ManufacturerData, Uuid};
+
+/// The data and parameters of an advertisement to send with [`Adapter::advertise`][crate::Adapter::advertise]
+///
+/// The default advertisement is connectable and general discoverable, and contains no data.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Advertisement {
+    /// The local name to advertise
+    ///
+    /// The platform may shorten the name if it does not fit in the advertising packet.
+    pub local_name: Option<String>,
+    /// The [`Uuid`]s of the services to advertise
+    pub services: Vec<Uuid>,
+    /// The service data to advertise, keyed by service [`Uuid`]
+    pub service_data: HashMap<Uuid, Vec<u8>>,
+    /// The manufacturer specific data to advertise
+    pub manufacturer_data: Vec<ManufacturerData>,
+    /// The transmit power to request in dBm
+    ///
+    /// When set, the transmit power level is included in the advertisement.
+    pub tx_power: Option<i16>,
+    /// Whether remote devices may connect in response to the advertisement
+    ///
+    /// Non-connectable advertisements are broadcast, so `is_discoverable` is ignored for them.
+    pub is_connectable: bool,
+    /// Whether to advertise as general discoverable
+    pub is_discoverable: bool,
+    /// The minimum advertising interval, or `None` to use the platform default
+    pub min_interval: Option<Duration>,
+    /// The maximum advertising interval, or `None` to use the platform default
+    pub max_interval: Option<Duration>,
+}
+
+impl Default for Advertisement {
+    fn default() -> Self {
+        Advertisement {
+            local_name: None,
+            services: Vec::new(),
+            service_data: HashMap::new(),
+            manufacturer_data: Vec::new(),
+            tx_power: None,
+            is_connectable: true,
+            is_discoverable: true,
+            min_interval: None,
+            max_interval: None,
+        }
+    }
+}
+
+/// An advertisement sent with [`Adapter::advertise`][crate::Adapter::advertise]
+///
+/// Advertising stops when this is dropped.
+#[derive(Debug)]
+pub struct AdvertisingHandle {
+    _inner: sys::advertiser::AdvertisingHandleImpl,
+}
+
+impl AdvertisingHandle {
+    #[cfg(target_os = "linux")]
+    pub(crate) fn from_platform(inner: crate::platform::advertiser::AdvertisingHandleImpl) -> Self {
+        #[cfg(feature = "mock")]
+        let inner = sys::advertiser::AdvertisingHandleImpl::Platform(inner);
+        AdvertisingHandle { _inner: inner }
+    }
+    #[cfg(feature = "mock")]
+    pub(crate) fn from_mock(inner: crate::mock::advertiser::AdvertisingHandleImpl) -> Self {
+        AdvertisingHandle {
+            _inner: sys::advertiser::AdvertisingHandleImpl::Mock(inner),
+        }
+    }
+}
@@ -1,9 +1,11 @@
 pub use android_ble::DeviceId;
 pub mod adapter;
+pub mod advertiser;
 pub mod characteristic;
 pub mod descriptor;
 pub mod device;
 pub mod l2cap_channel;
+pub mod server;
 pub mod service;
 
 impl From<android_ble::Error> for crate::Error {
@@ -4,8 +4,12 @@ use uuid::Uuid;
 
 use super::device::DeviceImpl;
 use super::DeviceId;
+use crate::advertiser::{Advertisement, AdvertisingHandle};
+use crate::error::ErrorKind;
+use crate::server::{Application, GattServer};
 use crate::{
     AdapterEvent, AdvertisementData, AdvertisingDevice, ConnectionEvent, Device, Error, ManufacturerData, Result,
+    ScanOptions, ScanTransport,
 };
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -43,7 +47,7 @@ impl AdapterImpl {
         self.0
             .open_device(id)
             .await
-            .map(|dev| Device(DeviceImpl(dev)))
+            .map(|dev| Device::from_platform(DeviceImpl(dev)))
             .map_err(Error::from)
     }
 
@@ -67,53 +71,86 @@ impl AdapterImpl {
         &'a self,
         services: &'a [Uuid],
     ) -> Result<impl Stream<Item = AdvertisingDevice> + Send + Unpin + 'a> {
-        Ok(self.0.scan(services).await?.map(|adv| AdvertisingDevice {
-            device: Device(DeviceImpl(adv.device)),
-            adv_data: AdvertisementData {
-                local_name: adv.adv_data.local_name,
-                manufacturer_data: adv.adv_data.manufacturer_data.map(|man| ManufacturerData {
-                    company_id: man.company_id,
-                    data: man.data,
-                }),
-                services: adv.adv_data.services,
-                service_data: adv.adv_data.service_data,
-                tx_power_level: adv.adv_data.tx_power_level,
-                is_connectable: adv.adv_data.is_connectable,
-            },
-            rssi: adv.rssi,
+        Ok(self.0.scan(services).await?.map(|adv| {
+            let manufacturer_data = adv.adv_data.manufacturer_data.map(|man| ManufacturerData {
+                company_id: man.company_id,
+                data: man.data,
+            });
+            AdvertisingDevice {
+                device: Device::from_platform(DeviceImpl(adv.device)),
+                adv_data: AdvertisementData {
+                    local_name: adv.adv_data.local_name,
+                    manufacturer_data_list: manufacturer_data.iter().cloned().collect(),
+                    manufacturer_data,
+                    services: adv.adv_data.services,
+                    service_data: adv.adv_data.service_data,
+                    tx_power_level: adv.adv_data.tx_power_level,
+                    is_connectable: adv.adv_data.is_connectable,
+                    ..Default::default()
+                },
+                rssi: adv.rssi,
+            }
         }))
     }
 
+    pub async fn scan_with_options(
+        &self,
+        options: ScanOptions,
+    ) -> Result<impl Stream<Item = AdvertisingDevice> + Send + Unpin + '_> {
+        if options.transport == ScanTransport::BrEdr {
+            return Err(Error::new(
+                ErrorKind::NotSupported,
+                None,
+                "scanning for BR/EDR devices is not supported",
+            ));
+        }
+        Ok(options.filter(self.scan(&[]).await?))
+    }
+
     pub async fn discover_devices<'a>(
         &'a self,
         services: &'a [Uuid],
     ) -> Result<impl Stream<Item = Result<Device>> + Send + Unpin + 'a> {
-        Ok(self
-            .0
-            .discover_devices(services)
-            .await?
-            .map(|dev| dev.map(|dev| Device(DeviceImpl(dev))).map_err(Error::from)))
+        Ok(self.0.discover_devices(services).await?.map(|dev| {
+            dev.map(|dev| Device::from_platform(DeviceImpl(dev)))
+                .map_err(Error::from)
+        }))
     }
 
-    pub async fn connect_device(&self, device: &Device) -> Result<()> {
-        self.0.connect_device(&device.0 .0).await.map_err(Error::from)
+    pub async fn connect_device(&self, device: &DeviceImpl) -> Result<()> {
+        self.0.connect_device(&device.0).await.map_err(Error::from)
     }
 
-    pub async fn disconnect_device(&self, device: &Device) -> Result<()> {
-        self.0.disconnect_device(&device.0 .0).await.map_err(Error::from)
+    pub async fn disconnect_device(&self, device: &DeviceImpl) -> Result<()> {
+        self.0.disconnect_device(&device.0).await.map_err(Error::from)
     }
 
     pub async fn device_connection_events<'a>(
         &'a self,
-        device: &'a Device,
+        device: &'a DeviceImpl,
     ) -> Result<impl Stream<Item = ConnectionEvent> + Send + Unpin + 'a> {
-        Ok(self.0.device_connection_events(&device.0 .0).await?.map(|e| match e {
+        Ok(self.0.device_connection_events(&device.0).await?.map(|e| match e {
             android_ble::ConnectionEvent::Connected => ConnectionEvent::Connected,
             android_ble::ConnectionEvent::Disconnected => ConnectionEvent::Disconnected,
         }))
     }
+
+    pub async fn serve_gatt(&self, _application: Application) -> Result<GattServer> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    pub async fn advertise(&self, _advertisement: Advertisement) -> Result<AdvertisingHandle> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    #[cfg(feature = "l2cap")]
+    pub async fn listen_l2cap(&self, _psm: Option<u16>, _secure: bool) -> Result<super::l2cap_channel::L2capListener> {
+        Err(ErrorKind::NotSupported.into())
+    }
 }
 
 fn convert_devices(src: Vec<android_ble::Device>) -> Vec<Device> {
-    src.into_iter().map(|dev| Device(DeviceImpl(dev))).collect()
+    src.into_iter()
+        .map(|dev| Device::from_platform(DeviceImpl(dev)))
+        .collect()
 }
@@ -1,9 +1,13 @@
+use std::time::Instant;
+
 use futures_core::Stream;
 use futures_lite::StreamExt;
 use uuid::Uuid;
 
+use super::DeviceId;
 use crate::android::descriptor::DescriptorImpl;
-use crate::{CharacteristicProperties, Descriptor, Error, Result};
+use crate::error::ErrorKind;
+use crate::{CharacteristicProperties, Descriptor, Error, Notification, Result, SubscriptionKind};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct CharacteristicImpl(pub(super) android_ble::Characteristic);
@@ -17,6 +21,15 @@ impl CharacteristicImpl {
         Ok(self.uuid())
     }
 
+    pub fn handle(&self) -> Result<u16> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    pub fn device_id(&self) -> Result<DeviceId> {
+        // android-ble does not expose the device of a characteristic
+        Err(ErrorKind::NotSupported.into())
+    }
+
     pub async fn properties(&self) -> Result<CharacteristicProperties> {
         self.0
             .properties()
@@ -44,6 +57,17 @@ impl CharacteristicImpl {
         self.0.read().await.map_err(Error::from)
     }
 
+    pub async fn read_with_offset(&self, offset: u16) -> Result<Vec<u8>> {
+        if offset != 0 {
+            return Err(Error::new(
+                ErrorKind::NotSupported,
+                None,
+                "reading at an offset is not supported on Android",
+            ));
+        }
+        self.read().await
+    }
+
     pub async fn write(&self, value: &[u8]) -> Result<()> {
         self.0.write(value).await.map_err(Error::from)
     }
@@ -52,6 +76,21 @@ impl CharacteristicImpl {
         self.0.write_without_response(value).await.map_err(Error::from)
     }
 
+    pub async fn write_long(&self, offset: u16, value: &[u8]) -> Result<()> {
+        if offset != 0 {
+            return Err(Error::new(
+                ErrorKind::NotSupported,
+                None,
+                "writing at an offset is not supported on Android",
+            ));
+        }
+        self.write(value).await
+    }
+
+    pub async fn execute_reliable_write(_writes: &[(&Self, &[u8])]) -> Result<()> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
     pub fn max_write_len(&self) -> Result<usize> {
         self.0.max_write_len().map_err(Error::from)
     }
@@ -64,6 +103,35 @@ impl CharacteristicImpl {
         Ok(self.0.notify().await?.map(|item| item.map_err(Error::from)))
     }
 
+    pub async fn subscribe(
+        &self,
+        kind: SubscriptionKind,
+    ) -> Result<impl Stream<Item = Result<Notification>> + Send + Unpin + '_> {
+        let properties = self.properties().await?;
+        kind.check_supported(&properties)?;
+        if kind == SubscriptionKind::Indicate && properties.notify {
+            return Err(Error::new(
+                ErrorKind::NotSupported,
+                None,
+                "notifications are enabled for characteristics supporting both notifications and indications",
+            ));
+        }
+
+        let is_indication = kind == SubscriptionKind::Indicate;
+        Ok(self.notify().await?.map(move |x| {
+            x.map(|value| Notification {
+                value,
+                is_indication,
+                received_at: Instant::now(),
+            })
+        }))
+    }
+
+    pub async fn unsubscribe(&self) -> Result<()> {
+        // Notifications are disabled once every stream returned by `notify` has been dropped
+        Ok(())
+    }
+
     pub async fn is_notifying(&self) -> Result<bool> {
         self.0.is_notifying().await.map_err(Error::from)
     }
@@ -76,7 +144,12 @@ impl CharacteristicImpl {
         self.0
             .descriptors()
             .await
-            .map(|descs| descs.into_iter().map(|desc| Descriptor(DescriptorImpl(desc))).collect())
+            .map(|descs| {
+                descs
+                    .into_iter()
+                    .map(|desc| Descriptor::from_platform(DescriptorImpl(desc)))
+                    .collect()
+            })
             .map_err(Error::from)
     }
 }
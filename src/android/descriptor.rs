@@ -1,3 +1,4 @@
+use crate::error::ErrorKind;
 use crate::{Error, Result, Uuid};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -12,6 +13,10 @@ impl DescriptorImpl {
         Ok(self.uuid())
     }
 
+    pub fn handle(&self) -> Result<u16> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
     pub async fn value(&self) -> Result<Vec<u8>> {
         self.0.value().await.map_err(Error::from)
     }
@@ -20,6 +25,17 @@ impl DescriptorImpl {
         self.0.read().await.map_err(Error::from)
     }
 
+    pub async fn read_with_offset(&self, offset: u16) -> Result<Vec<u8>> {
+        if offset != 0 {
+            return Err(Error::new(
+                ErrorKind::NotSupported,
+                None,
+                "reading at an offset is not supported on Android",
+            ));
+        }
+        self.read().await
+    }
+
     pub async fn write(&self, value: &[u8]) -> Result<()> {
         self.0.write(value).await.map_err(Error::from)
     }
@@ -2,10 +2,11 @@ use futures_core::Stream;
 use futures_lite::StreamExt;
 use uuid::Uuid;
 
+use super::DeviceId;
 use crate::android::service::ServiceImpl;
 use crate::error::ErrorKind;
 use crate::pairing::PairingAgent;
-use crate::{DeviceId, Error, Result, Service, ServicesChanged};
+use crate::{ConnectionPhy, ConnectionPriority, Error, Phy, Result, Service, ServicesChanged};
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct DeviceImpl(pub(super) android_ble::Device);
@@ -81,7 +82,7 @@ impl DeviceImpl {
         &self,
     ) -> Result<impl Stream<Item = Result<ServicesChanged>> + Send + Unpin + '_> {
         Ok(self.0.service_changed_indications().await?.map(|ch| {
-            ch.map(|ch| ServicesChanged(ServicesChangedImpl(ch)))
+            ch.map(|ch| ServicesChanged::from_platform(ServicesChangedImpl(ch)))
                 .map_err(Error::from)
         }))
     }
@@ -90,10 +91,57 @@ impl DeviceImpl {
         self.0.rssi().await.map_err(Error::from)
     }
 
+    pub async fn rssi_updates(&self) -> Result<impl Stream<Item = i16> + Send + Unpin + '_> {
+        Err::<futures_lite::stream::Empty<_>, _>(ErrorKind::NotSupported.into())
+    }
+
+    pub async fn mtu(&self) -> Result<u16> {
+        // android-ble only exposes the MTU through the write length of the characteristics of the device
+        for service in self.0.services().await? {
+            if let Some(characteristic) = service.characteristics().await?.first() {
+                // android-ble subtracts 5 bytes from the MTU, undo that to get the ATT MTU
+                let mtu = characteristic.max_write_len()? + 5;
+                return Ok(u16::try_from(mtu).unwrap_or(u16::MAX));
+            }
+        }
+        Err(Error::new(
+            ErrorKind::NotFound,
+            None,
+            "the device has no GATT characteristics to read the MTU from",
+        ))
+    }
+
+    /// android-ble requests the largest MTU when connecting, so this returns the current MTU.
+    pub async fn request_mtu(&self, _mtu: u16) -> Result<u16> {
+        self.mtu().await
+    }
+
+    pub async fn mtu_updates(&self) -> Result<impl Stream<Item = u16> + Send + Unpin + '_> {
+        Err::<futures_lite::stream::Empty<_>, _>(ErrorKind::NotSupported.into())
+    }
+
+    // android-ble does not expose the `BluetoothGatt` of the connection, which `requestConnectionPriority`,
+    // `readPhy` and `setPreferredPhy` are called on
+    pub async fn set_connection_priority(&self, _priority: ConnectionPriority) -> Result<()> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    pub async fn phy(&self) -> Result<ConnectionPhy> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    pub async fn set_preferred_phy(&self, _tx: Phy, _rx: Phy) -> Result<()> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
     #[cfg(feature = "l2cap")]
-    pub async fn open_l2cap_channel(&self, psm: u16, secure: bool) -> Result<super::l2cap_channel::L2capChannel> {
+    pub async fn open_l2cap_channel(
+        &self,
+        psm: u16,
+        options: &crate::L2capOptions,
+    ) -> Result<super::l2cap_channel::L2capChannel> {
         self.0
-            .open_l2cap_channel(psm, secure)
+            .open_l2cap_channel(psm, options.secure)
             .await
             .map(|ch| ch.split())
             .map(|(reader, writer)| super::l2cap_channel::L2capChannel { reader, writer })
@@ -105,11 +153,13 @@ impl DeviceImpl {
 pub struct ServicesChangedImpl(android_ble::ServicesChanged);
 
 impl ServicesChangedImpl {
-    pub fn was_invalidated(&self, service: &Service) -> bool {
-        self.0.was_invalidated(&service.0 .0)
+    pub fn was_invalidated(&self, service: &ServiceImpl) -> bool {
+        self.0.was_invalidated(&service.0)
     }
 }
 
 pub(super) fn convert_services(src: Vec<android_ble::Service>) -> Vec<Service> {
-    src.into_iter().map(|ser| Service(ServiceImpl(ser))).collect()
+    src.into_iter()
+        .map(|ser| Service::from_platform(ServiceImpl(ser)))
+        .collect()
 }
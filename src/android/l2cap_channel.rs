@@ -6,7 +6,9 @@ use std::task::{Context, Poll};
 pub use android_ble::{L2capChannelReader, L2capChannelWriter};
 use futures_lite::{AsyncRead, AsyncWrite};
 
+use crate::error::ErrorKind;
 use crate::l2cap_channel::{derive_async_read, derive_async_write};
+use crate::{Device, Result};
 
 pub struct L2capChannel {
     pub(super) reader: L2capChannelReader,
@@ -14,6 +16,18 @@ pub struct L2capChannel {
 }
 
 impl L2capChannel {
+    pub fn send_mtu(&self) -> Result<u16> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    pub fn recv_mtu(&self) -> Result<u16> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    pub fn is_encrypted(&self) -> Result<bool> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
     pub fn split(self) -> (L2capChannelReader, L2capChannelWriter) {
         (self.reader, self.writer)
     }
@@ -21,3 +35,15 @@ impl L2capChannel {
 
 derive_async_read!(L2capChannel, reader);
 derive_async_write!(L2capChannel, writer);
+
+pub enum L2capListener {}
+
+impl L2capListener {
+    pub fn psm(&self) -> u16 {
+        match *self {}
+    }
+
+    pub async fn accept(&self) -> Result<(Device, L2capChannel)> {
+        match *self {}
+    }
+}
@@ -0,0 +1,24 @@
+use crate::{Result, SubscriptionKind};
+
+pub enum NotifierImpl {}
+
+impl NotifierImpl {
+    pub fn kind(&self) -> SubscriptionKind {
+        match *self {}
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        match *self {}
+    }
+
+    pub async fn stopped(&mut self) {
+        match *self {}
+    }
+
+    pub async fn notify(&mut self, _value: &[u8]) -> Result<()> {
+        match *self {}
+    }
+}
+
+#[derive(Debug)]
+pub enum GattServerImpl {}
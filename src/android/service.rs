@@ -1,5 +1,6 @@
 use super::device::convert_services;
 use crate::android::characteristic::CharacteristicImpl;
+use crate::error::ErrorKind;
 use crate::{Characteristic, Error, Result, Service, Uuid};
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -14,6 +15,14 @@ impl ServiceImpl {
         Ok(self.uuid())
     }
 
+    pub fn handle(&self) -> Result<u16> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    pub fn end_handle(&self) -> Result<u16> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
     pub async fn is_primary(&self) -> Result<bool> {
         self.0.is_primary().await.map_err(Error::from)
     }
@@ -65,6 +74,6 @@ impl ServiceImpl {
 
 fn convert_chars(src: Vec<android_ble::Characteristic>) -> Vec<Characteristic> {
     src.into_iter()
-        .map(|ch| Characteristic(CharacteristicImpl(ch)))
+        .map(|ch| Characteristic::from_platform(CharacteristicImpl(ch)))
         .collect()
 }
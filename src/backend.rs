@@ -0,0 +1,61 @@
+//! Runtime selection between the platform backend and the [`mock`][crate::mock] backend.
+
+pub mod adapter;
+pub mod advertiser;
+pub mod characteristic;
+pub mod descriptor;
+pub mod device;
+#[cfg(feature = "l2cap")]
+pub use crate::platform::l2cap_channel;
+pub mod server;
+pub mod service;
+
+/// Forwards a method call to the platform or mock implementation wrapped by `self`.
+macro_rules! dispatch {
+    ($self:expr, $inner:ident => $body:expr) => {
+        match $self {
+            Self::Platform($inner) => $body,
+            Self::Mock($inner) => $body,
+        }
+    };
+}
+
+pub(crate) use dispatch;
+
+/// A platform-specific device identifier, or the identifier of a simulated peripheral.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeviceId(DeviceIdImpl);
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum DeviceIdImpl {
+    Platform(crate::platform::DeviceId),
+    /// The device address of a simulated peripheral
+    Mock([u8; 6]),
+}
+
+impl DeviceId {
+    /// The identifier of a simulated peripheral with the given device address.
+    pub(crate) fn mock(address: [u8; 6]) -> Self {
+        DeviceId(DeviceIdImpl::Mock(address))
+    }
+}
+
+impl From<crate::platform::DeviceId> for DeviceId {
+    fn from(id: crate::platform::DeviceId) -> Self {
+        DeviceId(DeviceIdImpl::Platform(id))
+    }
+}
+
+impl std::fmt::Display for DeviceId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.0 {
+            DeviceIdImpl::Platform(id) => std::fmt::Display::fmt(id, f),
+            DeviceIdImpl::Mock(address) => {
+                let [a, b, c, d, e, g] = address;
+                write!(f, "{a:02X}:{b:02X}:{c:02X}:{d:02X}:{e:02X}:{g:02X}")
+            }
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use std::pin::Pin;
+
+use futures_core::Stream;
+
+use super::device::DeviceImpl;
+use super::{dispatch, DeviceIdImpl};
+use crate::advertiser::{Advertisement, AdvertisingHandle};
+use crate::error::ErrorKind;
+use crate::server::{Application, GattServer};
+use crate::{
+    mock, platform, AdapterEvent, AdvertisingDevice, ConnectionEvent, Device, DeviceId, Error, Result, ScanOptions,
+    Uuid,
+};
+
+type BoxStream<'a, T> = Pin<Box<dyn Stream<Item = T> + Send + 'a>>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AdapterImpl {
+    Platform(platform::adapter::AdapterImpl),
+    Mock(mock::adapter::AdapterImpl),
+}
+
+fn foreign_device() -> Error {
+    Error::new(
+        ErrorKind::InvalidParameter,
+        None,
+        "the device does not belong to this adapter",
+    )
+}
+
+impl AdapterImpl {
+    pub async fn with_config(config: platform::adapter::AdapterConfig) -> Result<Self> {
+        platform::adapter::AdapterImpl::with_config(config)
+            .await
+            .map(AdapterImpl::Platform)
+    }
+
+    pub fn with_mock(adapter: mock::MockAdapter) -> Self {
+        AdapterImpl::Mock(mock::adapter::AdapterImpl::new(adapter))
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn set_acquire_io(&mut self, acquire_io: bool) {
+        // Simulated characteristics have no sockets to acquire
+        if let AdapterImpl::Platform(inner) = self {
+            inner.set_acquire_io(acquire_io);
+        }
+    }
+
+    pub async fn events(&self) -> Result<BoxStream<'_, Result<AdapterEvent>>> {
+        dispatch!(self, inner => Ok(Box::pin(inner.events().await?) as BoxStream<'_, _>))
+    }
+
+    pub async fn is_available(&self) -> Result<bool> {
+        dispatch!(self, inner => inner.is_available().await)
+    }
+
+    pub async fn wait_available(&self) -> Result<()> {
+        dispatch!(self, inner => inner.wait_available().await)
+    }
+
+    pub async fn open_device(&self, id: &DeviceId) -> Result<Device> {
+        match (self, &id.0) {
+            (Self::Platform(inner), DeviceIdImpl::Platform(id)) => inner.open_device(id).await,
+            (Self::Mock(inner), DeviceIdImpl::Mock(_)) => inner.open_device(id).await,
+            _ => Err(ErrorKind::NotFound.into()),
+        }
+    }
+
+    pub async fn connected_devices(&self) -> Result<Vec<Device>> {
+        dispatch!(self, inner => inner.connected_devices().await)
+    }
+
+    pub async fn connected_devices_with_services(&self, services: &[Uuid]) -> Result<Vec<Device>> {
+        dispatch!(self, inner => inner.connected_devices_with_services(services).await)
+    }
+
+    pub async fn scan<'a>(&'a self, services: &'a [Uuid]) -> Result<BoxStream<'a, AdvertisingDevice>> {
+        dispatch!(self, inner => Ok(Box::pin(inner.scan(services).await?) as BoxStream<'a, _>))
+    }
+
+    pub async fn scan_with_options(&self, options: ScanOptions) -> Result<BoxStream<'_, AdvertisingDevice>> {
+        dispatch!(self, inner => Ok(Box::pin(inner.scan_with_options(options).await?) as BoxStream<'_, _>))
+    }
+
+    pub async fn discover_devices<'a>(&'a self, services: &'a [Uuid]) -> Result<BoxStream<'a, Result<Device>>> {
+        dispatch!(self, inner => Ok(Box::pin(inner.discover_devices(services).await?) as BoxStream<'a, _>))
+    }
+
+    pub async fn connect_device(&self, device: &DeviceImpl) -> Result<()> {
+        match (self, device) {
+            (AdapterImpl::Platform(inner), DeviceImpl::Platform(device)) => inner.connect_device(device).await,
+            (AdapterImpl::Mock(inner), DeviceImpl::Mock(device)) => inner.connect_device(device).await,
+            _ => Err(foreign_device()),
+        }
+    }
+
+    pub async fn disconnect_device(&self, device: &DeviceImpl) -> Result<()> {
+        match (self, device) {
+            (AdapterImpl::Platform(inner), DeviceImpl::Platform(device)) => inner.disconnect_device(device).await,
+            (AdapterImpl::Mock(inner), DeviceImpl::Mock(device)) => inner.disconnect_device(device).await,
+            _ => Err(foreign_device()),
+        }
+    }
+
+    pub async fn device_connection_events<'a>(
+        &'a self,
+        device: &'a DeviceImpl,
+    ) -> Result<BoxStream<'a, ConnectionEvent>> {
+        match (self, device) {
+            (AdapterImpl::Platform(inner), DeviceImpl::Platform(device)) => {
+                Ok(Box::pin(inner.device_connection_events(device).await?))
+            }
+            (AdapterImpl::Mock(inner), DeviceImpl::Mock(device)) => {
+                Ok(Box::pin(inner.device_connection_events(device).await?))
+            }
+            _ => Err(foreign_device()),
+        }
+    }
+
+    pub async fn serve_gatt(&self, application: Application) -> Result<GattServer> {
+        dispatch!(self, inner => inner.serve_gatt(application).await)
+    }
+
+    pub async fn advertise(&self, advertisement: Advertisement) -> Result<AdvertisingHandle> {
+        dispatch!(self, inner => inner.advertise(advertisement).await)
+    }
+
+    #[cfg(feature = "l2cap")]
+    pub async fn listen_l2cap(&self, psm: Option<u16>, secure: bool) -> Result<super::l2cap_channel::L2capListener> {
+        dispatch!(self, inner => inner.listen_l2cap(psm, secure).await)
+    }
+}
@@ -0,0 +1,9 @@
+use crate::{mock, platform};
+
+/// An advertisement sent with [`Adapter::advertise`][crate::Adapter::advertise]
+#[derive(Debug)]
+#[allow(dead_code)] // Advertising stops when the inner value is dropped
+pub enum AdvertisingHandleImpl {
+    Platform(platform::advertiser::AdvertisingHandleImpl),
+    Mock(mock::advertiser::AdvertisingHandleImpl),
+}
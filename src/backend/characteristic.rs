@@ -0,0 +1,123 @@
+use std::pin::Pin;
+
+use futures_core::Stream;
+
+use super::dispatch;
+use crate::error::ErrorKind;
+use crate::{
+    mock, platform, CharacteristicProperties, Descriptor, DeviceId, Error, Notification, Result, SubscriptionKind, Uuid,
+};
+
+type BoxStream<'a, T> = Pin<Box<dyn Stream<Item = T> + Send + 'a>>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CharacteristicImpl {
+    Platform(platform::characteristic::CharacteristicImpl),
+    Mock(mock::characteristic::CharacteristicImpl),
+}
+
+impl CharacteristicImpl {
+    pub fn uuid(&self) -> Uuid {
+        dispatch!(self, inner => inner.uuid())
+    }
+
+    pub async fn uuid_async(&self) -> Result<Uuid> {
+        dispatch!(self, inner => inner.uuid_async().await)
+    }
+
+    pub fn handle(&self) -> Result<u16> {
+        dispatch!(self, inner => inner.handle())
+    }
+
+    pub fn device_id(&self) -> Result<DeviceId> {
+        match self {
+            Self::Platform(inner) => inner.device_id().map(Into::into),
+            Self::Mock(inner) => inner.device_id(),
+        }
+    }
+
+    pub async fn properties(&self) -> Result<CharacteristicProperties> {
+        dispatch!(self, inner => inner.properties().await)
+    }
+
+    pub async fn value(&self) -> Result<Vec<u8>> {
+        dispatch!(self, inner => inner.value().await)
+    }
+
+    pub async fn read(&self) -> Result<Vec<u8>> {
+        dispatch!(self, inner => inner.read().await)
+    }
+
+    pub async fn read_with_offset(&self, offset: u16) -> Result<Vec<u8>> {
+        dispatch!(self, inner => inner.read_with_offset(offset).await)
+    }
+
+    pub async fn write(&self, value: &[u8]) -> Result<()> {
+        dispatch!(self, inner => inner.write(value).await)
+    }
+
+    pub async fn write_without_response(&self, value: &[u8]) -> Result<()> {
+        dispatch!(self, inner => inner.write_without_response(value).await)
+    }
+
+    pub async fn write_long(&self, offset: u16, value: &[u8]) -> Result<()> {
+        dispatch!(self, inner => inner.write_long(offset, value).await)
+    }
+
+    pub async fn execute_reliable_write(writes: &[(&Self, &[u8])]) -> Result<()> {
+        let platform: Option<Vec<_>> = writes
+            .iter()
+            .map(|(characteristic, value)| match characteristic {
+                CharacteristicImpl::Platform(inner) => Some((inner, *value)),
+                CharacteristicImpl::Mock(_) => None,
+            })
+            .collect();
+        if let Some(writes) = platform {
+            return platform::characteristic::CharacteristicImpl::execute_reliable_write(&writes).await;
+        }
+
+        let mock: Option<Vec<_>> = writes
+            .iter()
+            .map(|(characteristic, value)| match characteristic {
+                CharacteristicImpl::Mock(inner) => Some((inner, *value)),
+                CharacteristicImpl::Platform(_) => None,
+            })
+            .collect();
+        match mock {
+            Some(writes) => mock::characteristic::CharacteristicImpl::execute_reliable_write(&writes).await,
+            None => Err(Error::new(
+                ErrorKind::InvalidParameter,
+                None,
+                "a reliable write cannot mix simulated and platform characteristics",
+            )),
+        }
+    }
+
+    pub fn max_write_len(&self) -> Result<usize> {
+        dispatch!(self, inner => inner.max_write_len())
+    }
+
+    pub async fn max_write_len_async(&self) -> Result<usize> {
+        dispatch!(self, inner => inner.max_write_len_async().await)
+    }
+
+    pub async fn subscribe(&self, kind: SubscriptionKind) -> Result<BoxStream<'_, Result<Notification>>> {
+        dispatch!(self, inner => Ok(Box::pin(inner.subscribe(kind).await?) as BoxStream<'_, _>))
+    }
+
+    pub async fn unsubscribe(&self) -> Result<()> {
+        dispatch!(self, inner => inner.unsubscribe().await)
+    }
+
+    pub async fn is_notifying(&self) -> Result<bool> {
+        dispatch!(self, inner => inner.is_notifying().await)
+    }
+
+    pub async fn discover_descriptors(&self) -> Result<Vec<Descriptor>> {
+        dispatch!(self, inner => inner.discover_descriptors().await)
+    }
+
+    pub async fn descriptors(&self) -> Result<Vec<Descriptor>> {
+        dispatch!(self, inner => inner.descriptors().await)
+    }
+}
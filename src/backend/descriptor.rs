@@ -0,0 +1,38 @@
+use super::dispatch;
+use crate::{mock, platform, Result, Uuid};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DescriptorImpl {
+    Platform(platform::descriptor::DescriptorImpl),
+    Mock(mock::descriptor::DescriptorImpl),
+}
+
+impl DescriptorImpl {
+    pub fn uuid(&self) -> Uuid {
+        dispatch!(self, inner => inner.uuid())
+    }
+
+    pub async fn uuid_async(&self) -> Result<Uuid> {
+        dispatch!(self, inner => inner.uuid_async().await)
+    }
+
+    pub fn handle(&self) -> Result<u16> {
+        dispatch!(self, inner => inner.handle())
+    }
+
+    pub async fn value(&self) -> Result<Vec<u8>> {
+        dispatch!(self, inner => inner.value().await)
+    }
+
+    pub async fn read(&self) -> Result<Vec<u8>> {
+        dispatch!(self, inner => inner.read().await)
+    }
+
+    pub async fn read_with_offset(&self, offset: u16) -> Result<Vec<u8>> {
+        dispatch!(self, inner => inner.read_with_offset(offset).await)
+    }
+
+    pub async fn write(&self, value: &[u8]) -> Result<()> {
+        dispatch!(self, inner => inner.write(value).await)
+    }
+}
@@ -0,0 +1,132 @@
+use std::pin::Pin;
+
+use futures_core::Stream;
+
+use super::dispatch;
+use super::service::ServiceImpl;
+use crate::pairing::PairingAgent;
+use crate::{mock, platform, ConnectionPhy, ConnectionPriority, DeviceId, Phy, Result, Service, ServicesChanged, Uuid};
+
+type BoxStream<'a, T> = Pin<Box<dyn Stream<Item = T> + Send + 'a>>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DeviceImpl {
+    Platform(platform::device::DeviceImpl),
+    Mock(mock::device::DeviceImpl),
+}
+
+impl std::fmt::Display for DeviceImpl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        dispatch!(self, inner => std::fmt::Display::fmt(inner, f))
+    }
+}
+
+impl DeviceImpl {
+    pub fn id(&self) -> DeviceId {
+        match self {
+            Self::Platform(inner) => inner.id().into(),
+            Self::Mock(inner) => inner.id(),
+        }
+    }
+
+    pub fn name(&self) -> Result<String> {
+        dispatch!(self, inner => inner.name())
+    }
+
+    pub async fn name_async(&self) -> Result<String> {
+        dispatch!(self, inner => inner.name_async().await)
+    }
+
+    pub async fn is_connected(&self) -> bool {
+        dispatch!(self, inner => inner.is_connected().await)
+    }
+
+    pub async fn is_paired(&self) -> Result<bool> {
+        dispatch!(self, inner => inner.is_paired().await)
+    }
+
+    pub async fn pair(&self) -> Result<()> {
+        dispatch!(self, inner => inner.pair().await)
+    }
+
+    pub async fn pair_with_agent<T: PairingAgent + 'static>(&self, agent: &T) -> Result<()> {
+        dispatch!(self, inner => inner.pair_with_agent(agent).await)
+    }
+
+    pub async fn unpair(&self) -> Result<()> {
+        dispatch!(self, inner => inner.unpair().await)
+    }
+
+    pub async fn discover_services(&self) -> Result<Vec<Service>> {
+        dispatch!(self, inner => inner.discover_services().await)
+    }
+
+    pub async fn discover_services_with_uuid(&self, uuid: Uuid) -> Result<Vec<Service>> {
+        dispatch!(self, inner => inner.discover_services_with_uuid(uuid).await)
+    }
+
+    pub async fn services(&self) -> Result<Vec<Service>> {
+        dispatch!(self, inner => inner.services().await)
+    }
+
+    pub async fn service_changed_indications(&self) -> Result<BoxStream<'_, Result<ServicesChanged>>> {
+        dispatch!(self, inner => Ok(Box::pin(inner.service_changed_indications().await?) as BoxStream<'_, _>))
+    }
+
+    pub async fn rssi(&self) -> Result<i16> {
+        dispatch!(self, inner => inner.rssi().await)
+    }
+
+    pub async fn rssi_updates(&self) -> Result<BoxStream<'_, i16>> {
+        dispatch!(self, inner => Ok(Box::pin(inner.rssi_updates().await?) as BoxStream<'_, _>))
+    }
+
+    pub async fn mtu(&self) -> Result<u16> {
+        dispatch!(self, inner => inner.mtu().await)
+    }
+
+    pub async fn request_mtu(&self, mtu: u16) -> Result<u16> {
+        dispatch!(self, inner => inner.request_mtu(mtu).await)
+    }
+
+    pub async fn mtu_updates(&self) -> Result<BoxStream<'_, u16>> {
+        dispatch!(self, inner => Ok(Box::pin(inner.mtu_updates().await?) as BoxStream<'_, _>))
+    }
+
+    pub async fn set_connection_priority(&self, priority: ConnectionPriority) -> Result<()> {
+        dispatch!(self, inner => inner.set_connection_priority(priority).await)
+    }
+
+    pub async fn phy(&self) -> Result<ConnectionPhy> {
+        dispatch!(self, inner => inner.phy().await)
+    }
+
+    pub async fn set_preferred_phy(&self, tx: Phy, rx: Phy) -> Result<()> {
+        dispatch!(self, inner => inner.set_preferred_phy(tx, rx).await)
+    }
+
+    #[cfg(feature = "l2cap")]
+    pub async fn open_l2cap_channel(
+        &self,
+        psm: u16,
+        options: &crate::L2capOptions,
+    ) -> Result<super::l2cap_channel::L2capChannel> {
+        dispatch!(self, inner => inner.open_l2cap_channel(psm, options).await)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ServicesChangedImpl {
+    Platform(platform::device::ServicesChangedImpl),
+    Mock(mock::device::ServicesChangedImpl),
+}
+
+impl ServicesChangedImpl {
+    pub fn was_invalidated(&self, service: &ServiceImpl) -> bool {
+        match (self, service) {
+            (ServicesChangedImpl::Platform(inner), ServiceImpl::Platform(service)) => inner.was_invalidated(service),
+            (ServicesChangedImpl::Mock(inner), ServiceImpl::Mock(service)) => inner.was_invalidated(service),
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use super::dispatch;
+use crate::{mock, platform, Result, SubscriptionKind};
+
+/// A subscription of a remote device to a local characteristic
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))] // Only BlueZ serves GATT applications
+pub enum NotifierImpl {
+    Platform(platform::server::NotifierImpl),
+    Mock(mock::server::NotifierImpl),
+}
+
+impl NotifierImpl {
+    pub fn kind(&self) -> SubscriptionKind {
+        dispatch!(self, inner => inner.kind())
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        dispatch!(self, inner => inner.is_stopped())
+    }
+
+    pub async fn stopped(&mut self) {
+        dispatch!(self, inner => inner.stopped().await)
+    }
+
+    pub async fn notify(&mut self, value: &[u8]) -> Result<()> {
+        dispatch!(self, inner => inner.notify(value).await)
+    }
+}
+
+/// Local GATT services published with [`Adapter::serve_gatt`][crate::Adapter::serve_gatt]
+#[derive(Debug)]
+#[allow(dead_code)] // The services are unpublished when the inner value is dropped
+pub enum GattServerImpl {
+    Platform(platform::server::GattServerImpl),
+    Mock(mock::server::GattServerImpl),
+}
@@ -0,0 +1,54 @@
+use super::dispatch;
+use crate::{mock, platform, Characteristic, Result, Service, Uuid};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ServiceImpl {
+    Platform(platform::service::ServiceImpl),
+    Mock(mock::service::ServiceImpl),
+}
+
+impl ServiceImpl {
+    pub fn uuid(&self) -> Uuid {
+        dispatch!(self, inner => inner.uuid())
+    }
+
+    pub async fn uuid_async(&self) -> Result<Uuid> {
+        dispatch!(self, inner => inner.uuid_async().await)
+    }
+
+    pub fn handle(&self) -> Result<u16> {
+        dispatch!(self, inner => inner.handle())
+    }
+
+    pub fn end_handle(&self) -> Result<u16> {
+        dispatch!(self, inner => inner.end_handle())
+    }
+
+    pub async fn is_primary(&self) -> Result<bool> {
+        dispatch!(self, inner => inner.is_primary().await)
+    }
+
+    pub async fn discover_characteristics(&self) -> Result<Vec<Characteristic>> {
+        dispatch!(self, inner => inner.discover_characteristics().await)
+    }
+
+    pub async fn discover_characteristics_with_uuid(&self, uuid: Uuid) -> Result<Vec<Characteristic>> {
+        dispatch!(self, inner => inner.discover_characteristics_with_uuid(uuid).await)
+    }
+
+    pub async fn characteristics(&self) -> Result<Vec<Characteristic>> {
+        dispatch!(self, inner => inner.characteristics().await)
+    }
+
+    pub async fn discover_included_services(&self) -> Result<Vec<Service>> {
+        dispatch!(self, inner => inner.discover_included_services().await)
+    }
+
+    pub async fn discover_included_services_with_uuid(&self, uuid: Uuid) -> Result<Vec<Service>> {
+        dispatch!(self, inner => inner.discover_included_services_with_uuid(uuid).await)
+    }
+
+    pub async fn included_services(&self) -> Result<Vec<Service>> {
+        dispatch!(self, inner => inner.included_services().await)
+    }
+}
@@ -1,8 +1,10 @@
 pub mod adapter;
+pub mod advertiser;
 pub mod characteristic;
 pub mod descriptor;
 pub mod device;
 pub mod l2cap_channel;
+pub mod server;
 pub mod service;
 
 mod error;
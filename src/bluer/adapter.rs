@@ -1,11 +1,24 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
-use bluer::AdapterProperty;
+use bluer::{AdapterProperty, DiscoveryFilter, DiscoveryTransport};
 use futures_core::Stream;
 use futures_lite::StreamExt;
 
+use super::advertiser::AdvertisingHandleImpl;
+use super::device::DeviceImpl;
+use super::server::GattServerImpl;
+use super::DeviceId;
+use crate::advertiser::{Advertisement, AdvertisingHandle};
 use crate::error::ErrorKind;
-use crate::{AdapterEvent, AdvertisingDevice, ConnectionEvent, Device, DeviceId, Error, Result, Uuid};
+use crate::server::{Application, GattServer};
+use crate::{
+    AdapterEvent, AdvertisingDevice, ConnectionEvent, Device, Error, Result, ScanOptions, ScanTransport, Uuid,
+};
+
+/// The number of incoming L2CAP connections queued by the kernel until they are accepted
+#[cfg(feature = "l2cap")]
+const L2CAP_BACKLOG: u32 = 5;
 
 #[derive(Default)]
 pub struct AdapterConfig {
@@ -20,6 +33,7 @@ pub struct AdapterConfig {
 pub struct AdapterImpl {
     inner: bluer::Adapter,
     session: Arc<bluer::Session>,
+    acquire_io: bool,
 }
 
 impl PartialEq for AdapterImpl {
@@ -45,10 +59,19 @@ impl AdapterImpl {
         } else {
             session.default_adapter().await
         };
-        let adapter = adapter.map(|inner| AdapterImpl { inner, session })?;
+        let adapter = adapter.map(|inner| AdapterImpl {
+            inner,
+            session,
+            acquire_io: false,
+        })?;
         Ok(adapter)
     }
 
+    /// Sets whether devices opened through this adapter use sockets acquired from BlueZ.
+    pub fn set_acquire_io(&mut self, acquire_io: bool) {
+        self.acquire_io = acquire_io;
+    }
+
     /// A stream of [`AdapterEvent`] which allows the application to identify when the adapter is enabled or disabled.
     pub async fn events(&self) -> Result<impl Stream<Item = Result<AdapterEvent>> + Send + Unpin + '_> {
         let stream = self.inner.events().await?;
@@ -82,18 +105,27 @@ impl AdapterImpl {
 
     /// Attempts to create the device identified by `id`
     pub async fn open_device(&self, id: &DeviceId) -> Result<Device> {
-        Device::new(self.session.clone(), &self.inner, id.0)
+        DeviceImpl::new(self.session.clone(), &self.inner, id.0, self.acquire_io).map(Device::from_platform)
     }
 
     /// Finds all connected Bluetooth LE devices
     pub async fn connected_devices(&self) -> Result<Vec<Device>> {
+        Ok(self
+            .connected_device_impls()
+            .await?
+            .into_iter()
+            .map(Device::from_platform)
+            .collect())
+    }
+
+    async fn connected_device_impls(&self) -> Result<Vec<DeviceImpl>> {
         let mut devices = Vec::new();
         for device in self
             .inner
             .device_addresses()
             .await?
             .into_iter()
-            .filter_map(|addr| Device::new(self.session.clone(), &self.inner, addr).ok())
+            .filter_map(|addr| DeviceImpl::new(self.session.clone(), &self.inner, addr, self.acquire_io).ok())
         {
             if device.is_connected().await {
                 devices.push(device);
@@ -111,12 +143,12 @@ impl AdapterImpl {
     pub async fn connected_devices_with_services(&self, services: &[Uuid]) -> Result<Vec<Device>> {
         assert!(!services.is_empty());
 
-        let devices = self.connected_devices().await?;
+        let devices = self.connected_device_impls().await?;
         let mut res = Vec::new();
         for device in devices {
-            for service in device.0.inner.services().await? {
+            for service in device.inner.services().await? {
                 if services.contains(&service.uuid().await?) {
-                    res.push(device);
+                    res.push(Device::from_platform(device));
                     break;
                 }
             }
@@ -137,30 +169,99 @@ impl AdapterImpl {
         &'a self,
         services: &'a [Uuid],
     ) -> Result<impl Stream<Item = AdvertisingDevice> + Send + Unpin + 'a> {
+        // Clear any filter left behind by `scan_with_options`
+        self.set_discovery_filter(DiscoveryFilter::default()).await?;
+        Ok(self.advertisements(false).await?.filter(|x: &AdvertisingDevice| {
+            services.is_empty() || x.adv_data.services.iter().any(|y| services.contains(y))
+        }))
+    }
+
+    /// Starts scanning for Bluetooth advertising packets using the provided options.
+    pub async fn scan_with_options(
+        &self,
+        options: ScanOptions,
+    ) -> Result<impl Stream<Item = AdvertisingDevice> + Send + Unpin + '_> {
+        let filter = DiscoveryFilter {
+            uuids: options.services.iter().copied().collect(),
+            rssi: options.rssi_threshold,
+            // BlueZ does not accept both thresholds at once
+            pathloss: options.pathloss_threshold.filter(|_| options.rssi_threshold.is_none()),
+            transport: match options.transport {
+                ScanTransport::Auto => DiscoveryTransport::Auto,
+                ScanTransport::Le => DiscoveryTransport::Le,
+                ScanTransport::BrEdr => DiscoveryTransport::BrEdr,
+            },
+            duplicate_data: options.allow_duplicates,
+            pattern: options.name_prefix.clone(),
+            ..Default::default()
+        };
+        self.set_discovery_filter(filter).await?;
+        Ok(options.filter(self.advertisements(options.allow_duplicates).await?))
+    }
+
+    /// Sets the discovery filter used by subsequent scans, unless a scan is already active in this session.
+    async fn set_discovery_filter(&self, filter: DiscoveryFilter) -> Result<()> {
+        match self.inner.set_discovery_filter(filter).await {
+            Err(err) if err.kind != bluer::ErrorKind::DiscoveryActive => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// A stream of advertisements for every device discovered by BlueZ, and again whenever a property of a device
+    /// changes.
+    ///
+    /// Unless `allow_duplicates` is set, changes are only reported when the advertised RSSI, manufacturer data, service
+    /// data or transmit power of the device changes.
+    async fn advertisements(
+        &self,
+        allow_duplicates: bool,
+    ) -> Result<impl Stream<Item = AdvertisingDevice> + Send + Unpin + '_> {
+        let mut reported = HashMap::new();
         Ok(self
             .inner
-            .discover_devices()
+            .discover_devices_with_changes()
             .await?
             .then(move |event| {
                 Box::pin(async move {
                     match event {
                         bluer::AdapterEvent::DeviceAdded(addr) => {
-                            let device = Device::new(self.session.clone(), &self.inner, addr).ok()?;
+                            let device =
+                                DeviceImpl::new(self.session.clone(), &self.inner, addr, self.acquire_io).ok()?;
                             if !device.is_connected().await {
-                                let adv_data = device.0.adv_data().await;
-                                let rssi = device.rssi().await.ok();
-                                Some(AdvertisingDevice { device, adv_data, rssi })
+                                let adv_data = device.adv_data().await;
+                                let rssi = device.inner.rssi().await.ok().flatten();
+                                let device = Device::from_platform(device);
+                                Some((addr, Some(AdvertisingDevice { device, adv_data, rssi })))
                             } else {
                                 None
                             }
                         }
+                        bluer::AdapterEvent::DeviceRemoved(addr) => Some((addr, None)),
                         _ => None,
                     }
                 })
             })
-            .filter_map(|x| x)
-            .filter(|x: &AdvertisingDevice| {
-                services.is_empty() || x.adv_data.services.iter().any(|y| services.contains(y))
+            .filter_map(move |event| {
+                let (addr, adv) = match event? {
+                    (addr, Some(adv)) => (addr, adv),
+                    (addr, None) => {
+                        // Forget devices BlueZ no longer tracks, so that the map does not grow without bound
+                        reported.remove(&addr);
+                        return None;
+                    }
+                };
+                if allow_duplicates {
+                    return Some(adv);
+                }
+
+                // BlueZ reports changes to any device property, skip those which don't affect the advertisement
+                let values = (
+                    adv.rssi,
+                    adv.adv_data.manufacturer_data_list.clone(),
+                    adv.adv_data.service_data.clone(),
+                    adv.adv_data.tx_power_level,
+                );
+                (reported.insert(addr, values.clone()) != Some(values)).then_some(adv)
             }))
     }
 
@@ -182,19 +283,20 @@ impl AdapterImpl {
                 Box::pin(async move {
                     match event {
                         bluer::AdapterEvent::DeviceAdded(addr) => {
-                            let device = match Device::new(self.session.clone(), &self.inner, addr) {
+                            let device = match DeviceImpl::new(self.session.clone(), &self.inner, addr, self.acquire_io)
+                            {
                                 Ok(device) => device,
                                 Err(err) => return Some(Err(err)),
                             };
 
                             if services.is_empty() {
-                                Some(Ok(device))
+                                Some(Ok(Device::from_platform(device)))
                             } else {
-                                match device.0.inner.uuids().await {
+                                match device.inner.uuids().await {
                                     Ok(uuids) => {
                                         let uuids = uuids.unwrap_or_default();
                                         if services.iter().any(|x| uuids.contains(x)) {
-                                            Some(Ok(device))
+                                            Some(Ok(Device::from_platform(device)))
                                         } else {
                                             None
                                         }
@@ -211,22 +313,23 @@ impl AdapterImpl {
     }
 
     /// Connects to the [`Device`]
-    pub async fn connect_device(&self, device: &Device) -> Result<()> {
-        device.0.inner.connect().await.map_err(Into::into)
+    pub async fn connect_device(&self, device: &DeviceImpl) -> Result<()> {
+        device.inner.connect().await.map_err(Into::into)
     }
 
     /// Disconnects from the [`Device`]
-    pub async fn disconnect_device(&self, device: &Device) -> Result<()> {
-        device.0.inner.disconnect().await.map_err(Into::into)
+    pub async fn disconnect_device(&self, device: &DeviceImpl) -> Result<()> {
+        super::characteristic::release_writers(self.inner.name(), device.inner.address());
+        device.inner.disconnect().await.map_err(Into::into)
     }
 
     /// Monitors a device for connection/disconnection events.
     #[inline]
     pub async fn device_connection_events<'a>(
         &'a self,
-        device: &'a Device,
+        device: &'a DeviceImpl,
     ) -> Result<impl Stream<Item = ConnectionEvent> + Send + Unpin + 'a> {
-        let events = device.0.inner.events().await?;
+        let events = device.inner.events().await?;
         Ok(events.filter_map(|ev| match ev {
             bluer::DeviceEvent::PropertyChanged(bluer::DeviceProperty::Connected(false)) => {
                 Some(ConnectionEvent::Disconnected)
@@ -237,4 +340,44 @@ impl AdapterImpl {
             _ => None,
         }))
     }
+
+    /// Publishes the local GATT services described by `application`.
+    pub async fn serve_gatt(&self, application: Application) -> Result<GattServer> {
+        GattServerImpl::serve(&self.inner, application)
+            .await
+            .map(GattServer::from_platform)
+    }
+
+    /// Starts sending `advertisement`.
+    pub async fn advertise(&self, advertisement: Advertisement) -> Result<AdvertisingHandle> {
+        AdvertisingHandleImpl::advertise(&self.inner, advertisement)
+            .await
+            .map(AdvertisingHandle::from_platform)
+    }
+
+    #[cfg(feature = "l2cap")]
+    pub async fn listen_l2cap(&self, psm: Option<u16>, secure: bool) -> Result<super::l2cap_channel::L2capListener> {
+        use bluer::l2cap::{Socket, SocketAddr};
+
+        let sa = SocketAddr::new(
+            self.inner.address().await?,
+            self.inner.address_type().await?,
+            psm.unwrap_or(0),
+        );
+        let socket = Socket::new_stream()?;
+        if secure {
+            socket.set_security(super::l2cap_channel::ENCRYPTED)?;
+        }
+        socket.bind(sa)?;
+        // Queue connections from several peers that connect before they are accepted
+        let inner = socket.listen(L2CAP_BACKLOG)?;
+        let psm = inner.as_ref().local_addr()?.psm;
+        Ok(super::l2cap_channel::L2capListener {
+            inner,
+            psm,
+            adapter: self.inner.clone(),
+            session: self.session.clone(),
+            acquire_io: self.acquire_io,
+        })
+    }
 }
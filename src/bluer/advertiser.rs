@@ -0,0 +1,46 @@
+use std::collections::BTreeSet;
+
+use bluer::adv::{self, Feature, Type};
+
+use crate::advertiser::Advertisement;
+use crate::Result;
+
+/// An advertisement registered with BlueZ
+#[derive(Debug)]
+pub struct AdvertisingHandleImpl {
+    _handle: adv::AdvertisementHandle,
+}
+
+impl AdvertisingHandleImpl {
+    pub(super) async fn advertise(adapter: &bluer::Adapter, advertisement: Advertisement) -> Result<Self> {
+        let mut system_includes = BTreeSet::new();
+        if advertisement.tx_power.is_some() {
+            system_includes.insert(Feature::TxPower);
+        }
+
+        let advertisement = adv::Advertisement {
+            advertisement_type: if advertisement.is_connectable {
+                Type::Peripheral
+            } else {
+                Type::Broadcast
+            },
+            service_uuids: advertisement.services.into_iter().collect(),
+            manufacturer_data: advertisement
+                .manufacturer_data
+                .into_iter()
+                .map(|x| (x.company_id, x.data))
+                .collect(),
+            service_data: advertisement.service_data.into_iter().collect(),
+            // BlueZ rejects the discoverable property for broadcast advertisements
+            discoverable: advertisement.is_connectable.then_some(advertisement.is_discoverable),
+            system_includes,
+            local_name: advertisement.local_name,
+            min_interval: advertisement.min_interval,
+            max_interval: advertisement.max_interval,
+            tx_power: advertisement.tx_power,
+            ..Default::default()
+        };
+        let handle = adapter.advertise(advertisement).await?;
+        Ok(AdvertisingHandleImpl { _handle: handle })
+    }
+}
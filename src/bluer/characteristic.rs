@@ -1,14 +1,45 @@
-use bluer::gatt::remote::CharacteristicWriteRequest;
-use bluer::gatt::WriteOp;
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Instant;
+
+use bluer::gatt::remote::{CharacteristicReadRequest, CharacteristicWriteRequest};
+use bluer::gatt::{CharacteristicWriter, WriteOp};
 use futures_core::Stream;
 use futures_lite::StreamExt;
 
-use crate::{Characteristic, CharacteristicProperties, Descriptor, Result, Uuid};
+use super::DeviceId;
+use crate::error::ErrorKind;
+use crate::{
+    Characteristic, CharacteristicProperties, Descriptor, Error, Notification, Result, SubscriptionKind, Uuid,
+};
 
 /// A Bluetooth GATT characteristic
 #[derive(Debug, Clone)]
 pub struct CharacteristicImpl {
     inner: bluer::gatt::remote::Characteristic,
+    acquire_io: bool,
+}
+
+type NotificationStream<'a> = Pin<Box<dyn Stream<Item = Result<Notification>> + Send + 'a>>;
+
+type CharacteristicKey = (String, bluer::Address, u16, u16);
+
+type Writers = HashMap<CharacteristicKey, Arc<CharacteristicWriter>>;
+
+/// Writers acquired with `AcquireWrite`. BlueZ only hands out one writer per characteristic, so it is shared by every
+/// `CharacteristicImpl` referring to the same characteristic.
+fn writers() -> &'static Mutex<Writers> {
+    static WRITERS: OnceLock<Mutex<Writers>> = OnceLock::new();
+    WRITERS.get_or_init(Default::default)
+}
+
+/// Closes the writers acquired for the characteristics of a device, which BlueZ invalidates when it disconnects.
+pub(super) fn release_writers(adapter_name: &str, address: bluer::Address) {
+    writers()
+        .lock()
+        .unwrap()
+        .retain(|(adapter, addr, _, _), _| adapter != adapter_name || *addr != address);
 }
 
 impl PartialEq for CharacteristicImpl {
@@ -32,12 +63,52 @@ impl std::hash::Hash for CharacteristicImpl {
 }
 
 impl Characteristic {
-    pub(super) fn new(inner: bluer::gatt::remote::Characteristic) -> Characteristic {
-        Characteristic(CharacteristicImpl { inner })
+    pub(super) fn new(inner: bluer::gatt::remote::Characteristic, acquire_io: bool) -> Characteristic {
+        Characteristic::from_platform(CharacteristicImpl { inner, acquire_io })
     }
 }
 
 impl CharacteristicImpl {
+    fn key(&self) -> CharacteristicKey {
+        (
+            self.inner.adapter_name().to_string(),
+            self.inner.device_address(),
+            self.inner.service_id(),
+            self.inner.id(),
+        )
+    }
+
+    /// Returns the writer acquired for this characteristic, if it is still open.
+    fn acquired_writer(&self) -> Option<Arc<CharacteristicWriter>> {
+        let key = self.key();
+        let mut writers = writers().lock().unwrap();
+        let writer = writers.get(&key)?;
+        // BlueZ closes the socket when the device disconnects
+        if writer.is_closed().unwrap_or(true) {
+            writers.remove(&key);
+            return None;
+        }
+        Some(writer.clone())
+    }
+
+    /// Returns the writer acquired for this characteristic, acquiring one if necessary.
+    async fn writer(&self) -> Result<Arc<CharacteristicWriter>> {
+        if let Some(writer) = self.acquired_writer() {
+            return Ok(writer);
+        }
+
+        let writer = Arc::new(self.inner.write_io().await?);
+        writers().lock().unwrap().insert(self.key(), writer.clone());
+        Ok(writer)
+    }
+
+    /// Closes the writer acquired for this characteristic, if any.
+    ///
+    /// BlueZ rejects other writes to the characteristic while a writer is held.
+    fn release_writer(&self) {
+        writers().lock().unwrap().remove(&self.key());
+    }
+
     /// The [`Uuid`] identifying the type of this GATT characteristic
     ///
     /// # Panics
@@ -61,6 +132,16 @@ impl CharacteristicImpl {
         self.inner.uuid().await.map_err(Into::into)
     }
 
+    /// The attribute handle of this GATT characteristic.
+    pub fn handle(&self) -> Result<u16> {
+        Ok(self.inner.id())
+    }
+
+    /// The identifier of the device this GATT characteristic belongs to.
+    pub fn device_id(&self) -> Result<DeviceId> {
+        Ok(DeviceId(self.inner.device_address()))
+    }
+
     /// The properties of this this GATT characteristic.
     ///
     /// Characteristic properties indicate which operations (e.g. read, write, notify, etc) may be performed on this
@@ -81,14 +162,43 @@ impl CharacteristicImpl {
         self.inner.read().await.map_err(Into::into)
     }
 
+    /// Read the value of this characteristic from the device, starting at `offset`.
+    pub async fn read_with_offset(&self, offset: u16) -> Result<Vec<u8>> {
+        self.inner
+            .read_ext(&CharacteristicReadRequest {
+                offset,
+                ..Default::default()
+            })
+            .await
+            .map_err(Into::into)
+    }
+
     /// Write the value of this descriptor on the device to `value` and request the device return a response indicating
     /// a successful write.
     pub async fn write(&self, value: &[u8]) -> Result<()> {
+        self.release_writer();
         self.inner.write(value).await.map_err(Into::into)
     }
 
     /// Write the value of this descriptor on the device to `value` without requesting a response.
+    ///
+    /// If the adapter was created with `Adapter::with_acquire_io`, the value is sent over a socket acquired from BlueZ,
+    /// falling back to D-Bus if the socket cannot be acquired.
     pub async fn write_without_response(&self, value: &[u8]) -> Result<()> {
+        if self.acquire_io {
+            if let Ok(writer) = self.writer().await {
+                let res = writer.send(value).await;
+                if res.is_err() {
+                    // The socket is closed when the device disconnects, so acquire a new one next time
+                    self.release_writer();
+                }
+                return res.map_err(Into::into);
+            }
+        }
+
+        // Another `CharacteristicImpl` may hold a writer for the same characteristic
+        self.release_writer();
+
         self.inner
             .write_ext(
                 value,
@@ -101,6 +211,52 @@ impl CharacteristicImpl {
             .map_err(Into::into)
     }
 
+    /// Write `value` to the value of this characteristic on the device, starting at `offset`.
+    pub async fn write_long(&self, offset: u16, value: &[u8]) -> Result<()> {
+        self.release_writer();
+        self.inner
+            .write_ext(
+                value,
+                &CharacteristicWriteRequest {
+                    offset,
+                    op_type: WriteOp::Request,
+                    ..Default::default()
+                },
+            )
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Writes `writes` to the device with a reliable write.
+    ///
+    /// BlueZ only supports reliable writes of a single value, so transactions of more than one write return
+    /// [`ErrorKind::NotSupported`].
+    pub async fn execute_reliable_write(writes: &[(&Self, &[u8])]) -> Result<()> {
+        let (characteristic, value) = match writes {
+            [] => return Ok(()),
+            [write] => write,
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::NotSupported,
+                    None,
+                    "BlueZ only supports reliable writes of a single value",
+                ))
+            }
+        };
+        characteristic.release_writer();
+        characteristic
+            .inner
+            .write_ext(
+                value,
+                &CharacteristicWriteRequest {
+                    op_type: WriteOp::Reliable,
+                    ..Default::default()
+                },
+            )
+            .await
+            .map_err(Into::into)
+    }
+
     /// Get the maximum amount of data that can be written in a single packet for this characteristic.
     pub fn max_write_len(&self) -> Result<usize> {
         // Call an async function from a synchronous context
@@ -114,17 +270,71 @@ impl CharacteristicImpl {
     }
 
     /// Get the maximum amount of data that can be written in a single packet for this characteristic.
+    ///
+    /// If a socket has been acquired from BlueZ for writes without response, this is the MTU reported by the socket.
     pub async fn max_write_len_async(&self) -> Result<usize> {
+        if let Some(writer) = self.acquired_writer() {
+            return Ok(writer.mtu());
+        }
+
         let mtu = self.inner.mtu().await?;
         // GATT characteristic writes have 3 bytes of overhead (opcode + handle id)
         Ok(mtu - 3)
     }
 
-    /// Enables notification of value changes for this GATT characteristic.
+    /// Subscribes to notifications or indications of value changes for this GATT characteristic.
     ///
-    /// Returns a stream of values for the characteristic sent from the device.
-    pub async fn notify(&self) -> Result<impl Stream<Item = Result<Vec<u8>>> + Send + Unpin + '_> {
-        Ok(Box::pin(self.inner.notify().await?.map(Ok)))
+    /// BlueZ enables notifications when a characteristic supports both, so indications are only available for
+    /// characteristics which do not support notifications.
+    ///
+    /// If the adapter was created with `Adapter::with_acquire_io`, notifications are received over a socket acquired
+    /// from BlueZ, falling back to D-Bus if the socket cannot be acquired.
+    pub async fn subscribe(&self, kind: SubscriptionKind) -> Result<NotificationStream<'_>> {
+        let properties = self.properties().await?;
+        kind.check_supported(&properties)?;
+        if kind == SubscriptionKind::Indicate && properties.notify {
+            return Err(Error::new(
+                ErrorKind::NotSupported,
+                None,
+                "BlueZ enables notifications for characteristics supporting both notifications and indications",
+            ));
+        }
+
+        let is_indication = kind == SubscriptionKind::Indicate;
+        if self.acquire_io && !is_indication {
+            if let Ok(reader) = self.inner.notify_io().await {
+                let values = futures_lite::stream::unfold(Some(reader), |reader| async move {
+                    let reader = reader?;
+                    match reader.recv().await {
+                        // BlueZ closes the socket when the device stops sending notifications
+                        Ok(value) if value.is_empty() => None,
+                        Ok(value) => Some((Ok(value), Some(reader))),
+                        Err(err) => Some((Err(err.into()), None)),
+                    }
+                });
+                return Ok(Box::pin(values.map(move |value| {
+                    value.map(|value| Notification {
+                        value,
+                        is_indication,
+                        received_at: Instant::now(),
+                    })
+                })) as NotificationStream<'_>);
+            }
+        }
+
+        Ok(Box::pin(self.inner.notify().await?.map(move |value| {
+            Ok(Notification {
+                value,
+                is_indication,
+                received_at: Instant::now(),
+            })
+        })) as NotificationStream<'_>)
+    }
+
+    /// Disables notifications and indications for this GATT characteristic.
+    pub async fn unsubscribe(&self) -> Result<()> {
+        // BlueZ disables notifications once every stream returned by `notify` has been dropped
+        Ok(())
     }
 
     /// Is the device currently sending notifications for this characteristic?
@@ -1,3 +1,5 @@
+use bluer::gatt::remote::DescriptorReadRequest;
+
 use crate::{Descriptor, Result, Uuid};
 
 /// A Bluetooth GATT descriptor
@@ -20,7 +22,7 @@ impl Eq for DescriptorImpl {}
 
 impl Descriptor {
     pub(super) fn new(inner: bluer::gatt::remote::Descriptor) -> Descriptor {
-        Descriptor(DescriptorImpl { inner })
+        Descriptor::from_platform(DescriptorImpl { inner })
     }
 }
 
@@ -48,6 +50,11 @@ impl DescriptorImpl {
         self.inner.uuid().await.map_err(Into::into)
     }
 
+    /// The attribute handle of this GATT descriptor.
+    pub fn handle(&self) -> Result<u16> {
+        Ok(self.inner.id())
+    }
+
     /// The cached value of this descriptor
     ///
     /// If the value has not yet been read, this method may either return an error or perform a read of the value.
@@ -60,6 +67,17 @@ impl DescriptorImpl {
         self.inner.read().await.map_err(Into::into)
     }
 
+    /// Read the value of this descriptor from the device, starting at `offset`.
+    pub async fn read_with_offset(&self, offset: u16) -> Result<Vec<u8>> {
+        self.inner
+            .read_ext(&DescriptorReadRequest {
+                offset,
+                ..Default::default()
+            })
+            .await
+            .map_err(Into::into)
+    }
+
     /// Write the value of this descriptor on the device to `value`
     pub async fn write(&self, value: &[u8]) -> Result<()> {
         self.inner.write(value).await.map_err(Into::into)
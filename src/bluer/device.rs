@@ -3,17 +3,23 @@ use std::sync::Arc;
 use futures_core::Stream;
 use futures_lite::StreamExt;
 
+use super::service::ServiceImpl;
 use super::DeviceId;
+use crate::advertisement::{AdFlags, AdStructure};
 use crate::device::ServicesChanged;
 use crate::error::ErrorKind;
 use crate::pairing::PairingAgent;
-use crate::{btuuid, AdvertisementData, Device, Error, ManufacturerData, Result, Service, Uuid};
+use crate::{
+    btuuid, AdvertisementData, ConnectionPhy, ConnectionPriority, Device, Error, ManufacturerData, Phy, Result,
+    Service, Uuid,
+};
 
 /// A Bluetooth LE device
 #[derive(Debug, Clone)]
 pub struct DeviceImpl {
     pub(super) inner: Arc<bluer::Device>,
     session: Arc<bluer::Session>,
+    acquire_io: bool,
 }
 
 impl PartialEq for DeviceImpl {
@@ -37,16 +43,20 @@ impl std::fmt::Display for DeviceImpl {
     }
 }
 
-impl Device {
-    pub(super) fn new(session: Arc<bluer::Session>, adapter: &bluer::Adapter, addr: bluer::Address) -> Result<Device> {
-        Ok(Device(DeviceImpl {
+impl DeviceImpl {
+    pub(super) fn new(
+        session: Arc<bluer::Session>,
+        adapter: &bluer::Adapter,
+        addr: bluer::Address,
+        acquire_io: bool,
+    ) -> Result<DeviceImpl> {
+        Ok(DeviceImpl {
             inner: Arc::new(adapter.device(addr)?),
             session,
-        }))
+            acquire_io,
+        })
     }
-}
 
-impl DeviceImpl {
     /// This device's unique identifier
     pub fn id(&self) -> DeviceId {
         DeviceId(self.inner.address())
@@ -109,17 +119,20 @@ impl DeviceImpl {
             // out-live the lifetime of `agent`. Unfortunately, the compiler has no way to prove this, so we must cast
             // `agent` to the static lifetime.
             let agent: &'static T = unsafe { std::mem::transmute(agent) };
+            let acquire_io = self.acquire_io;
 
             async fn req_device(
                 session: Arc<bluer::Session>,
                 adapter: &str,
                 addr: bluer::Address,
+                acquire_io: bool,
             ) -> Result<Device, bluer::agent::ReqError> {
                 let adapter = session.adapter(adapter).map_err(|_| bluer::agent::ReqError::Rejected)?;
                 let device = adapter.device(addr).map_err(|_| bluer::agent::ReqError::Rejected)?;
-                Ok(Device(DeviceImpl {
+                Ok(Device::from_platform(DeviceImpl {
                     inner: Arc::new(device),
                     session,
+                    acquire_io,
                 }))
             }
 
@@ -129,7 +142,7 @@ impl DeviceImpl {
                     move |req: bluer::agent::RequestPasskey| {
                         let session = session.clone();
                         Box::pin(async move {
-                            let device = req_device(session, &req.adapter, req.device).await?;
+                            let device = req_device(session, &req.adapter, req.device, acquire_io).await?;
                             match agent.request_passkey(&device).await {
                                 Ok(passkey) => Ok(passkey.into()),
                                 Err(_) => Err(bluer::agent::ReqError::Rejected),
@@ -142,7 +155,7 @@ impl DeviceImpl {
                     move |req: bluer::agent::DisplayPasskey| {
                         let session = session.clone();
                         Box::pin(async move {
-                            let device = req_device(session, &req.adapter, req.device).await?;
+                            let device = req_device(session, &req.adapter, req.device, acquire_io).await?;
                             if let Ok(passkey) = req.passkey.try_into() {
                                 agent.display_passkey(&device, passkey);
                                 Ok(())
@@ -158,7 +171,7 @@ impl DeviceImpl {
                         let session = session.clone();
                         Box::pin(async move {
                             let session = session.clone();
-                            let device = req_device(session, &req.adapter, req.device).await?;
+                            let device = req_device(session, &req.adapter, req.device, acquire_io).await?;
                             if let Ok(passkey) = req.passkey.try_into() {
                                 agent
                                     .confirm_passkey(&device, passkey)
@@ -213,7 +226,7 @@ impl DeviceImpl {
             .services()
             .await?
             .into_iter()
-            .map(|x| Service::new(self.inner.clone(), x))
+            .map(|x| Service::new(self.inner.clone(), x, self.acquire_io))
             .collect())
     }
 
@@ -240,7 +253,9 @@ impl DeviceImpl {
             if data.len() == 4 {
                 let start_handle = u16::from_le_bytes(data[..2].try_into().unwrap());
                 let end_handle = u16::from_le_bytes(data[2..].try_into().unwrap());
-                Ok(ServicesChanged(ServicesChangedImpl(start_handle..=end_handle)))
+                Ok(ServicesChanged::from_platform(ServicesChangedImpl(
+                    start_handle..=end_handle,
+                )))
             } else {
                 Err(ErrorKind::InvalidParameter.into())
             }
@@ -248,11 +263,68 @@ impl DeviceImpl {
     }
 
     /// Get the current signal strength from the device in dBm.
+    pub async fn rssi(&self) -> Result<i16> {
+        self.inner
+            .rssi()
+            .await?
+            .ok_or_else(|| Error::new(ErrorKind::NotReady, None, "the device is not currently present"))
+    }
+
+    /// Monitors the signal strength of the device.
+    pub async fn rssi_updates(&self) -> Result<impl Stream<Item = i16> + Send + Unpin + '_> {
+        let events = self.inner.events().await?;
+        Ok(events.filter_map(|ev| match ev {
+            bluer::DeviceEvent::PropertyChanged(bluer::DeviceProperty::Rssi(rssi)) => Some(rssi),
+            _ => None,
+        }))
+    }
+
+    /// The ATT MTU of the connection, read from the first GATT characteristic of the device.
+    pub async fn mtu(&self) -> Result<u16> {
+        for service in self.inner.services().await? {
+            if let Some(characteristic) = service.characteristics().await?.first() {
+                // bluer subtracts 5 bytes from the MTU reported by BlueZ, undo that to get the ATT MTU
+                let mtu = characteristic.mtu().await? + 5;
+                return Ok(u16::try_from(mtu).unwrap_or(u16::MAX));
+            }
+        }
+        Err(Error::new(
+            ErrorKind::NotFound,
+            None,
+            "the device has no GATT characteristics to read the MTU from",
+        ))
+    }
+
+    /// BlueZ negotiates the largest supported MTU when connecting, so this returns the current MTU.
+    pub async fn request_mtu(&self, _mtu: u16) -> Result<u16> {
+        self.mtu().await
+    }
+
+    /// Monitors changes to the ATT MTU of the connection.
     ///
-    /// # Platform specific
+    /// Returns [ErrorKind::NotSupported].
+    pub async fn mtu_updates(&self) -> Result<impl Stream<Item = u16> + Send + Unpin + '_> {
+        Err::<futures_lite::stream::Empty<_>, _>(ErrorKind::NotSupported.into())
+    }
+
+    /// Requests connection parameters matching `priority`.
     ///
     /// Returns [ErrorKind::NotSupported].
-    pub async fn rssi(&self) -> Result<i16> {
+    pub async fn set_connection_priority(&self, _priority: ConnectionPriority) -> Result<()> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// The PHYs used by the connection.
+    ///
+    /// Returns [ErrorKind::NotSupported].
+    pub async fn phy(&self) -> Result<ConnectionPhy> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// Sets the preferred PHYs for the connection.
+    ///
+    /// Returns [ErrorKind::NotSupported].
+    pub async fn set_preferred_phy(&self, _tx: Phy, _rx: Phy) -> Result<()> {
         Err(ErrorKind::NotSupported.into())
     }
 
@@ -264,12 +336,16 @@ impl DeviceImpl {
         let local_name = device.alias().await.unwrap_or_default();
         let local_name = (!local_name.is_empty()).then_some(local_name);
 
-        let manufacturer_data = device
+        let mut manufacturer_data_list: Vec<_> = device
             .manufacturer_data()
             .await
             .unwrap_or_default()
-            .and_then(|data| data.into_iter().next())
-            .map(|(company_id, data)| ManufacturerData { company_id, data });
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(company_id, data)| ManufacturerData { company_id, data })
+            .collect();
+        manufacturer_data_list.sort_by_key(|x| x.company_id);
+        let manufacturer_data = manufacturer_data_list.first().cloned();
 
         let tx_power_level = device.tx_power().await.unwrap_or_default();
 
@@ -281,28 +357,69 @@ impl DeviceImpl {
             .unwrap_or_default()
             .map_or(Vec::new(), |x| x.into_iter().collect());
 
-        AdvertisementData {
+        let flags = device
+            .advertising_flags()
+            .await
+            .unwrap_or_default()
+            .and_then(|x| x.first().copied())
+            .map(AdFlags::from_bits);
+
+        let appearance = device.appearance().await.unwrap_or_default();
+
+        let mut adv_data = AdvertisementData {
             local_name,
             manufacturer_data,
+            manufacturer_data_list,
             service_data,
             services,
             tx_power_level,
             is_connectable,
+            flags,
+            appearance,
+            ..Default::default()
+        };
+
+        // BlueZ only exposes the AD types it does not handle itself here
+        let structures = device.advertising_data().await.unwrap_or_default().unwrap_or_default();
+        for (ad_type, data) in structures {
+            if let Ok(structure) = AdStructure::parse(ad_type.into(), &data) {
+                adv_data.merge(structure);
+            }
         }
+
+        adv_data
     }
 
     #[cfg(feature = "l2cap")]
     pub async fn open_l2cap_channel(
         &self,
         psm: u16,
-        _secure: bool,
+        options: &crate::L2capOptions,
     ) -> Result<super::l2cap_channel::L2capChannel, crate::Error> {
         use async_compat::Compat;
-        use bluer::l2cap::{SocketAddr, Stream as L2CapStream};
-        use bluer::AddressType;
+        use bluer::l2cap::{FlowControl, Socket, SocketAddr};
+
+        use crate::L2capMode;
 
-        let target_sa = SocketAddr::new(self.inner.address(), AddressType::LePublic, psm);
-        let stream = L2CapStream::connect(target_sa).await?;
+        // Bind to the adapter the device belongs to, so the socket options below can be set before connecting
+        let adapter = self.session.adapter(self.inner.adapter_name())?;
+        let local_sa = SocketAddr::new(adapter.address().await?, adapter.address_type().await?, 0);
+        let target_sa = SocketAddr::new(self.inner.address(), self.inner.address_type().await?, psm);
+
+        let socket = Socket::new_stream()?;
+        socket.bind(local_sa)?;
+        if options.secure {
+            socket.set_security(super::l2cap_channel::ENCRYPTED)?;
+        }
+        if let Some(mtu) = options.mtu {
+            socket.set_recv_mtu(mtu)?;
+        }
+        // The kernel only accepts BT_MODE when enhanced credit based flow control is enabled, and LE credit based
+        // flow control is already the default for LE sockets
+        if options.mode == L2capMode::EnhancedCreditBased {
+            socket.set_flow_control(FlowControl::Extended)?;
+        }
+        let stream = socket.connect(target_sa).await?;
         Ok(super::l2cap_channel::L2capChannel(Compat::new(stream)))
     }
 }
@@ -311,8 +428,8 @@ impl DeviceImpl {
 pub struct ServicesChangedImpl(std::ops::RangeInclusive<u16>);
 
 impl ServicesChangedImpl {
-    pub fn was_invalidated(&self, service: &Service) -> bool {
-        let service_id = service.0.inner.id();
+    pub fn was_invalidated(&self, service: &ServiceImpl) -> bool {
+        let service_id = service.inner.id();
         self.0.contains(&service_id)
     }
 }
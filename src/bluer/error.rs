@@ -1,4 +1,4 @@
-use crate::error::ErrorKind;
+use crate::error::{AttError, ErrorKind};
 
 impl From<bluer::Error> for crate::Error {
     fn from(err: bluer::Error) -> Self {
@@ -6,17 +6,27 @@ impl From<bluer::Error> for crate::Error {
     }
 }
 
+/// The prefix of the message BlueZ uses for ATT errors without a dedicated D-Bus error.
+const ATT_ERROR_PREFIX: &str = "Operation failed with ATT error: 0x";
+
 fn kind_from_bluer(err: &bluer::Error) -> ErrorKind {
     match err.kind {
         bluer::ErrorKind::ConnectionAttemptFailed => ErrorKind::ConnectionFailed,
-        bluer::ErrorKind::Failed => ErrorKind::Other,
+        bluer::ErrorKind::Failed => match err
+            .message
+            .strip_prefix(ATT_ERROR_PREFIX)
+            .and_then(|code| u8::from_str_radix(code, 16).ok())
+        {
+            Some(code) => ErrorKind::Protocol(AttError::from(code)),
+            None => ErrorKind::Other,
+        },
         bluer::ErrorKind::InvalidArguments => ErrorKind::InvalidParameter,
         bluer::ErrorKind::InvalidLength => ErrorKind::InvalidParameter,
         bluer::ErrorKind::NotAuthorized => ErrorKind::NotAuthorized,
         bluer::ErrorKind::NotReady => ErrorKind::NotReady,
         bluer::ErrorKind::NotSupported => ErrorKind::NotSupported,
         bluer::ErrorKind::NotPermitted => ErrorKind::NotAuthorized,
-        bluer::ErrorKind::InvalidOffset => ErrorKind::InvalidParameter,
+        bluer::ErrorKind::InvalidOffset => ErrorKind::Protocol(AttError::INVALID_OFFSET),
         bluer::ErrorKind::InvalidAddress(_) => ErrorKind::InvalidParameter,
         bluer::ErrorKind::InvalidName(_) => ErrorKind::InvalidParameter,
         bluer::ErrorKind::ServicesUnresolved => ErrorKind::NotReady,
@@ -25,14 +35,12 @@ fn kind_from_bluer(err: &bluer::Error) -> ErrorKind {
     }
 }
 
-#[cfg(feature = "l2cap")]
 impl From<std::io::Error> for crate::Error {
     fn from(err: std::io::Error) -> Self {
         crate::Error::new(kind_from_io(&err.kind()), Some(Box::new(err)), String::new())
     }
 }
 
-#[cfg(feature = "l2cap")]
 fn kind_from_io(err: &std::io::ErrorKind) -> ErrorKind {
     use std::io::ErrorKind as StdErrorKind;
 
@@ -2,18 +2,39 @@
 
 use std::fmt::Debug;
 use std::pin;
+use std::sync::Arc;
 use std::task::{Context, Poll};
 
 use async_compat::Compat;
 use bluer::l2cap::stream::{OwnedReadHalf, OwnedWriteHalf};
-use bluer::l2cap::Stream;
+use bluer::l2cap::{Security, SecurityLevel, Stream, StreamListener};
 use futures_lite::io::{AsyncRead, AsyncWrite};
 
+use super::device::DeviceImpl;
 use crate::l2cap_channel::{derive_async_read, derive_async_write};
+use crate::{Device, Result};
+
+/// The security requested for channels which require an encrypted link.
+pub(super) const ENCRYPTED: Security = Security {
+    level: SecurityLevel::Medium,
+    key_size: 0,
+};
 
 pub struct L2capChannel(pub(super) Compat<Stream>);
 
 impl L2capChannel {
+    pub fn send_mtu(&self) -> Result<u16> {
+        Ok(self.0.get_ref().as_ref().send_mtu()?)
+    }
+
+    pub fn recv_mtu(&self) -> Result<u16> {
+        Ok(self.0.get_ref().as_ref().recv_mtu()?)
+    }
+
+    pub fn is_encrypted(&self) -> Result<bool> {
+        Ok(self.0.get_ref().as_ref().security()?.level >= ENCRYPTED.level)
+    }
+
     pub fn split(self) -> (L2capChannelReader, L2capChannelWriter) {
         let (reader, writer) = self.0.into_inner().into_split();
         let (reader, writer) = (Compat::new(reader), Compat::new(writer));
@@ -47,3 +68,23 @@ impl Debug for L2capChannelWriter {
         Debug::fmt(self.writer.get_ref(), f)
     }
 }
+
+pub struct L2capListener {
+    pub(super) inner: StreamListener,
+    pub(super) psm: u16,
+    pub(super) adapter: bluer::Adapter,
+    pub(super) session: Arc<bluer::Session>,
+    pub(super) acquire_io: bool,
+}
+
+impl L2capListener {
+    pub fn psm(&self) -> u16 {
+        self.psm
+    }
+
+    pub async fn accept(&self) -> Result<(Device, L2capChannel)> {
+        let (stream, sa) = self.inner.accept().await?;
+        let device = DeviceImpl::new(self.session.clone(), &self.adapter, sa.addr, self.acquire_io)?;
+        Ok((Device::from_platform(device), L2capChannel(Compat::new(stream))))
+    }
+}
@@ -0,0 +1,223 @@
+use std::future::Future;
+use std::pin::Pin;
+
+use bluer::gatt::local::{
+    self, CharacteristicNotifier, CharacteristicNotify, CharacteristicNotifyMethod, CharacteristicRead,
+    CharacteristicWrite, CharacteristicWriteMethod, DescriptorRead, DescriptorWrite, ReqError, ReqResult,
+};
+use bluer::gatt::WriteOp;
+
+use crate::error::AttError;
+use crate::server::{
+    Application, LocalCharacteristic, LocalDescriptor, LocalService, Notifier, ReadFn, ReadRequest, WriteFn,
+    WriteRequest,
+};
+use crate::{Result, SubscriptionKind};
+
+type ReqFuture<T> = Pin<Box<dyn Future<Output = ReqResult<T>> + Send>>;
+
+/// The ATT MTU reported for requests which BlueZ does not report the MTU of.
+const DEFAULT_MTU: u16 = 23;
+
+/// A subscription of a remote device to a local characteristic
+pub struct NotifierImpl {
+    inner: CharacteristicNotifier,
+}
+
+impl NotifierImpl {
+    /// Whether values are sent as notifications or indications.
+    pub fn kind(&self) -> SubscriptionKind {
+        if self.inner.confirming() {
+            SubscriptionKind::Indicate
+        } else {
+            SubscriptionKind::Notify
+        }
+    }
+
+    /// Returns `true` if the remote device has unsubscribed.
+    pub fn is_stopped(&self) -> bool {
+        self.inner.is_stopped()
+    }
+
+    /// Waits until the remote device unsubscribes.
+    pub async fn stopped(&mut self) {
+        self.inner.stopped().await
+    }
+
+    /// Sends `value` to the remote device.
+    pub async fn notify(&mut self, value: &[u8]) -> Result<()> {
+        Ok(self.inner.notify(value.to_vec()).await?)
+    }
+}
+
+/// Local GATT services published with BlueZ
+#[derive(Debug)]
+pub struct GattServerImpl {
+    _handle: local::ApplicationHandle,
+}
+
+impl GattServerImpl {
+    pub(super) async fn serve(adapter: &bluer::Adapter, application: Application) -> Result<Self> {
+        let application = local::Application {
+            services: application.services.into_iter().map(service).collect(),
+            ..Default::default()
+        };
+        let handle = adapter.serve_gatt_application(application).await?;
+        Ok(GattServerImpl { _handle: handle })
+    }
+}
+
+fn service(service: LocalService) -> local::Service {
+    local::Service {
+        uuid: service.uuid,
+        primary: service.primary,
+        characteristics: service.characteristics.into_iter().map(characteristic).collect(),
+        ..Default::default()
+    }
+}
+
+fn characteristic(characteristic: LocalCharacteristic) -> local::Characteristic {
+    let properties = characteristic.properties;
+
+    let read = properties.read.then(|| {
+        let handler = characteristic.read;
+        CharacteristicRead {
+            read: true,
+            fun: Box::new(move |request| {
+                read_value(
+                    handler.clone(),
+                    ReadRequest {
+                        offset: request.offset,
+                        mtu: request.mtu,
+                    },
+                )
+            }),
+            ..Default::default()
+        }
+    });
+
+    let write = (properties.write
+        || properties.write_without_response
+        || properties.reliable_write
+        || properties.authenticated_signed_writes)
+        .then(|| {
+            let handler = characteristic.write;
+            CharacteristicWrite {
+                write: properties.write,
+                write_without_response: properties.write_without_response,
+                reliable_write: properties.reliable_write,
+                authenticated_signed_writes: properties.authenticated_signed_writes,
+                method: CharacteristicWriteMethod::Fun(Box::new(move |value, request| {
+                    write_value(
+                        handler.clone(),
+                        WriteRequest {
+                            value,
+                            offset: request.offset,
+                            mtu: request.mtu,
+                            with_response: request.op_type != WriteOp::Command,
+                        },
+                    )
+                })),
+                ..Default::default()
+            }
+        });
+
+    let notify = (properties.notify || properties.indicate).then(|| {
+        let handler = characteristic.subscribe;
+        CharacteristicNotify {
+            notify: properties.notify,
+            indicate: properties.indicate,
+            method: CharacteristicNotifyMethod::Fun(Box::new(move |inner| {
+                let handler = handler.clone();
+                Box::pin(async move {
+                    // Without a handler the subscription is accepted, but no values are ever sent
+                    if let Some(handler) = handler {
+                        handler(Notifier::from_platform(NotifierImpl { inner })).await
+                    }
+                })
+            })),
+            ..Default::default()
+        }
+    });
+
+    local::Characteristic {
+        uuid: characteristic.uuid,
+        broadcast: properties.broadcast,
+        writable_auxiliaries: properties.writable_auxiliaries,
+        descriptors: characteristic.descriptors.into_iter().map(descriptor).collect(),
+        read,
+        write,
+        notify,
+        ..Default::default()
+    }
+}
+
+fn descriptor(descriptor: LocalDescriptor) -> local::Descriptor {
+    let read = descriptor.read.map(|handler| DescriptorRead {
+        read: true,
+        fun: Box::new(move |request| {
+            read_value(
+                Some(handler.clone()),
+                ReadRequest {
+                    offset: request.offset,
+                    mtu: DEFAULT_MTU,
+                },
+            )
+        }),
+        ..Default::default()
+    });
+
+    let write = descriptor.write.map(|handler| DescriptorWrite {
+        write: true,
+        fun: Box::new(move |value, request| {
+            write_value(
+                Some(handler.clone()),
+                WriteRequest {
+                    value,
+                    offset: request.offset,
+                    mtu: DEFAULT_MTU,
+                    with_response: true,
+                },
+            )
+        }),
+        ..Default::default()
+    });
+
+    local::Descriptor {
+        uuid: descriptor.uuid,
+        read,
+        write,
+        ..Default::default()
+    }
+}
+
+fn read_value(handler: Option<ReadFn>, request: ReadRequest) -> ReqFuture<Vec<u8>> {
+    Box::pin(async move {
+        match handler {
+            Some(handler) => handler(request).await.map_err(req_error),
+            None => Err(ReqError::NotPermitted),
+        }
+    })
+}
+
+fn write_value(handler: Option<WriteFn>, request: WriteRequest) -> ReqFuture<()> {
+    Box::pin(async move {
+        match handler {
+            Some(handler) => handler(request).await.map_err(req_error),
+            None => Err(ReqError::NotPermitted),
+        }
+    })
+}
+
+/// Maps a protocol error to the closest error BlueZ can respond with.
+fn req_error(err: AttError) -> ReqError {
+    match err {
+        AttError::INVALID_OFFSET => ReqError::InvalidOffset,
+        AttError::INVALID_ATTRIBUTE_VALUE_LENGTH => ReqError::InvalidValueLength,
+        AttError::READ_NOT_PERMITTED | AttError::WRITE_NOT_PERMITTED => ReqError::NotPermitted,
+        AttError::INSUFFICIENT_AUTHORIZATION => ReqError::NotAuthorized,
+        AttError::REQUEST_NOT_SUPPORTED => ReqError::NotSupported,
+        AttError::PROCEDURE_ALREADY_IN_PROGRESS => ReqError::InProgress,
+        _ => ReqError::Failed,
+    }
+}
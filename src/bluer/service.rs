@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use crate::error::ErrorKind;
 use crate::{Characteristic, Result, Service, Uuid};
 
 /// A Bluetooth GATT service
@@ -7,6 +8,7 @@ use crate::{Characteristic, Result, Service, Uuid};
 pub struct ServiceImpl {
     pub(super) inner: bluer::gatt::remote::Service,
     device: Arc<bluer::Device>,
+    acquire_io: bool,
 }
 
 impl PartialEq for ServiceImpl {
@@ -28,8 +30,12 @@ impl std::hash::Hash for ServiceImpl {
 }
 
 impl Service {
-    pub(super) fn new(device: Arc<bluer::Device>, inner: bluer::gatt::remote::Service) -> Service {
-        Service(ServiceImpl { inner, device })
+    pub(super) fn new(device: Arc<bluer::Device>, inner: bluer::gatt::remote::Service, acquire_io: bool) -> Service {
+        Service::from_platform(ServiceImpl {
+            inner,
+            device,
+            acquire_io,
+        })
     }
 }
 
@@ -57,6 +63,18 @@ impl ServiceImpl {
         self.inner.uuid().await.map_err(Into::into)
     }
 
+    /// The attribute handle of this GATT service.
+    pub fn handle(&self) -> Result<u16> {
+        Ok(self.inner.id())
+    }
+
+    /// The last attribute handle belonging to this GATT service.
+    ///
+    /// Returns [ErrorKind::NotSupported]. BlueZ does not expose the handle range of a service.
+    pub fn end_handle(&self) -> Result<u16> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
     /// Whether this is a primary service of the device.
     pub async fn is_primary(&self) -> Result<bool> {
         self.inner.primary().await.map_err(Into::into)
@@ -85,7 +103,7 @@ impl ServiceImpl {
             .characteristics()
             .await
             .map_err(Into::into)
-            .map(|x| x.into_iter().map(Characteristic::new).collect())
+            .map(|x| x.into_iter().map(|x| Characteristic::new(x, self.acquire_io)).collect())
     }
 
     /// Discover the included services of this service.
@@ -110,7 +128,11 @@ impl ServiceImpl {
         let includes = self.inner.includes().await?;
         let mut res = Vec::with_capacity(includes.len());
         for id in includes {
-            res.push(Service::new(self.device.clone(), self.device.service(id).await?));
+            res.push(Service::new(
+                self.device.clone(),
+                self.device.service(id).await?,
+                self.acquire_io,
+            ));
         }
         Ok(res)
     }
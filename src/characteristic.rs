@@ -1,12 +1,81 @@
 use futures_core::Stream;
+use futures_lite::StreamExt;
 
-use crate::{sys, CharacteristicProperties, Descriptor, Result, Uuid};
+use crate::btuuid::descriptors;
+use crate::codec::{GattCodec, PresentationFormat};
+use crate::error::ErrorKind;
+use crate::notification::{self, Notification, SubscriberOptions, SubscriptionKind};
+use crate::{sys, CharacteristicProperties, Descriptor, Error, Result, Uuid, WriteStream};
+
+/// A reliable write of the values of one or more characteristics of a device (Bluetooth Core Specification, Vol 3,
+/// Part G, §4.9.5).
+///
+/// Writes are queued with [`write`][Self::write] and are not sent to the device until the transaction is
+/// [executed][Self::execute]. The device verifies every queued value before any of them take effect, so either all of
+/// the values are written or none of them are. Characteristics which support reliable writes have the
+/// [`reliable_write`][CharacteristicProperties::reliable_write] property.
+///
+/// # Platform specific
+///
+/// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS and Android.
+///
+/// On Linux, BlueZ only performs reliable writes of a single value, so executing a transaction of more than one write
+/// returns [`NotSupported`][crate::error::ErrorKind::NotSupported].
+#[derive(Debug, Default)]
+pub struct ReliableWriteTransaction {
+    writes: Vec<(Characteristic, Vec<u8>)>,
+}
+
+impl ReliableWriteTransaction {
+    /// Begins a new, empty transaction.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Queues writing `value` to `characteristic`.
+    ///
+    /// Every characteristic in a transaction must belong to the same device. Returns
+    /// [`InvalidParameter`][crate::error::ErrorKind::InvalidParameter] if `characteristic` belongs to a different device
+    /// than the characteristics already queued.
+    pub fn write(&mut self, characteristic: &Characteristic, value: &[u8]) -> Result<()> {
+        if let Some((first, _)) = self.writes.first() {
+            if first.0.device_id()? != characteristic.0.device_id()? {
+                return Err(Error::new(
+                    ErrorKind::InvalidParameter,
+                    None,
+                    "every characteristic of a reliable write must belong to the same device",
+                ));
+            }
+        }
+        self.writes.push((characteristic.clone(), value.to_vec()));
+        Ok(())
+    }
+
+    /// Sends the queued writes to the device and commits them.
+    pub async fn execute(self) -> Result<()> {
+        let writes: Vec<_> = self
+            .writes
+            .iter()
+            .map(|(characteristic, value)| (&characteristic.0, value.as_slice()))
+            .collect();
+        sys::characteristic::CharacteristicImpl::execute_reliable_write(&writes).await
+    }
+
+    /// Discards the queued writes without sending them to the device.
+    pub fn abort(self) {}
+}
 
 /// A Bluetooth GATT characteristic
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Characteristic(pub(crate) sys::characteristic::CharacteristicImpl);
 
 impl Characteristic {
+    pub(crate) fn from_platform(inner: crate::platform::characteristic::CharacteristicImpl) -> Self {
+        #[cfg(feature = "mock")]
+        let inner = sys::characteristic::CharacteristicImpl::Platform(inner);
+        Characteristic(inner)
+    }
+
     /// The [`Uuid`] identifying the type of this GATT characteristic
     ///
     /// # Panics
@@ -25,6 +94,19 @@ impl Characteristic {
         self.0.uuid_async().await
     }
 
+    /// The attribute handle of this GATT characteristic.
+    ///
+    /// This is the handle of the characteristic declaration. The characteristic value is the attribute immediately
+    /// after the declaration, so its handle is `handle + 1`.
+    ///
+    /// # Platform specific
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS and Android.
+    #[inline]
+    pub fn handle(&self) -> Result<u16> {
+        self.0.handle()
+    }
+
     /// The properties of this this GATT characteristic.
     ///
     /// Characteristic properties indicate which operations (e.g. read, write, notify, etc) may be performed on this
@@ -48,6 +130,34 @@ impl Characteristic {
         self.0.read().await
     }
 
+    /// Read the value of this characteristic from the device, starting at `offset`.
+    ///
+    /// Returns the part of the value from `offset` to its end, which may take several ATT Read Blob requests. This can
+    /// be used to page through values which are too long to read at once. The device may reject the read with an
+    /// [`AttError::INVALID_OFFSET`] protocol error if `offset` is past the end of the value, or with
+    /// [`AttError::ATTRIBUTE_NOT_LONG`] if `offset` is not 0 and the value fits in a single ATT Read response.
+    ///
+    /// # Platform specific
+    ///
+    /// An `offset` other than 0 returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS, Windows
+    /// and Android.
+    ///
+    /// [`AttError::INVALID_OFFSET`]: crate::error::AttError::INVALID_OFFSET
+    /// [`AttError::ATTRIBUTE_NOT_LONG`]: crate::error::AttError::ATTRIBUTE_NOT_LONG
+    #[inline]
+    pub async fn read_with_offset(&self, offset: u16) -> Result<Vec<u8>> {
+        self.0.read_with_offset(offset).await
+    }
+
+    /// Read the value of this characteristic from the device and decode it as a `T`.
+    ///
+    /// Returns an error with a kind of [`InvalidParameter`][ErrorKind::InvalidParameter] if the value cannot be
+    /// decoded.
+    #[inline]
+    pub async fn read_as<T: GattCodec>(&self) -> Result<T> {
+        T::decode(&self.read().await?)
+    }
+
     /// Write the value of this descriptor on the device to `value` and request the device return a response indicating
     /// a successful write.
     #[inline]
@@ -55,12 +165,36 @@ impl Characteristic {
         self.0.write(value).await
     }
 
+    /// Encode `value` and write it to the value of this characteristic with [`write`][Self::write].
+    #[inline]
+    pub async fn write_as<T: GattCodec>(&self, value: &T) -> Result<()> {
+        self.write(&value.encode()?).await
+    }
+
     /// Write the value of this descriptor on the device to `value` without requesting a response.
     #[inline]
     pub async fn write_without_response(&self, value: &[u8]) -> Result<()> {
         self.0.write_without_response(value).await
     }
 
+    /// Write `value` to the value of this characteristic on the device, starting at `offset`, using the long write
+    /// procedure (prepared writes) if required.
+    ///
+    /// The device may reject the write with an [`AttError::INVALID_OFFSET`] protocol error if `offset` is past the end of
+    /// the current value.
+    ///
+    /// # Platform specific
+    ///
+    /// An `offset` other than 0 returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS, Windows
+    /// and Android. These platforms use the long write procedure automatically for values longer than
+    /// [`max_write_len`][Self::max_write_len].
+    ///
+    /// [`AttError::INVALID_OFFSET`]: crate::error::AttError::INVALID_OFFSET
+    #[inline]
+    pub async fn write_long(&self, offset: u16, value: &[u8]) -> Result<()> {
+        self.0.write_long(offset, value).await
+    }
+
     /// Get the maximum amount of data that can be written in a single packet for this characteristic.
     ///
     /// # Platform specific
@@ -82,12 +216,104 @@ impl Characteristic {
         self.0.max_write_len_async().await
     }
 
+    /// Creates a [`WriteStream`] which sends the bytes written to it to this characteristic with write without response
+    /// operations, split into chunks of at most [`max_write_len`][Self::max_write_len] bytes.
+    ///
+    /// Returns [`NotSupported`][ErrorKind::NotSupported] if the characteristic does not support write without response.
+    pub async fn write_stream(&self) -> Result<WriteStream<'_>> {
+        if !self.properties().await?.write_without_response {
+            return Err(Error::new(
+                ErrorKind::NotSupported,
+                None,
+                "characteristic does not support write without response",
+            ));
+        }
+        let chunk_len = self.max_write_len_async().await?;
+        Ok(WriteStream::new(self, chunk_len))
+    }
+
     /// Enables notification of value changes for this GATT characteristic.
     ///
-    /// Returns a stream of values for the characteristic sent from the device.
+    /// Returns a stream of values for the characteristic sent from the device. Notifications are used if the
+    /// characteristic supports them, otherwise indications.
+    ///
+    /// Any number of streams may be created for the same characteristic, each receiving every value sent from the
+    /// device. Notifications are enabled on the device once and disabled when the last stream is dropped. Each stream
+    /// buffers values as described by [`SubscriberOptions::default`].
     #[inline]
     pub async fn notify(&self) -> Result<impl Stream<Item = Result<Vec<u8>>> + Send + Unpin + '_> {
-        self.0.notify().await
+        let properties = self.properties().await?;
+        let kind = if properties.notify {
+            SubscriptionKind::Notify
+        } else if properties.indicate {
+            SubscriptionKind::Indicate
+        } else {
+            return Err(Error::new(
+                ErrorKind::NotSupported,
+                None,
+                "characteristic does not support indications or notifications",
+            ));
+        };
+        let subscriber = notification::subscribe(self, kind, SubscriberOptions::default()).await?;
+        Ok(subscriber.map(|x| x.map(|notification| notification.value)))
+    }
+
+    /// Enables notifications or indications like [`notify`][Self::notify] and decodes each value as a `T`.
+    ///
+    /// Values which cannot be decoded are yielded as errors with a kind of
+    /// [`InvalidParameter`][ErrorKind::InvalidParameter] without ending the stream.
+    #[inline]
+    pub async fn notify_as<T: GattCodec>(&self) -> Result<impl Stream<Item = Result<T>> + Send + Unpin + '_> {
+        let values = self.notify().await?;
+        Ok(values.map(|x| x.and_then(|value| T::decode(&value))))
+    }
+
+    /// Subscribes to notifications or indications of value changes for this GATT characteristic.
+    ///
+    /// Returns a stream of the values sent from the device, each recording whether it was sent as an indication and when
+    /// it was received. Unlike [`notify`][Self::notify], which uses notifications when the characteristic supports both,
+    /// the kind of updates is chosen by `kind`. Returns [`NotSupported`][ErrorKind::NotSupported] if the characteristic
+    /// does not support `kind`.
+    ///
+    /// Subscriptions to the same characteristic share a single subscription on the device, as with
+    /// [`notify`][Self::notify]. The stream ends when [`unsubscribe`][Self::unsubscribe] is called. A characteristic can
+    /// only be subscribed to with one kind at a time, so this returns [`InvalidParameter`][ErrorKind::InvalidParameter]
+    /// while streams subscribed with the other kind are open.
+    ///
+    /// # Platform specific
+    ///
+    /// On Linux, MacOS/iOS and Android, subscribing to indications returns [`NotSupported`][ErrorKind::NotSupported] if
+    /// the characteristic also supports notifications, since the OS always enables notifications in that case.
+    #[inline]
+    pub async fn subscribe(
+        &self,
+        kind: SubscriptionKind,
+    ) -> Result<impl Stream<Item = Result<Notification>> + Send + Unpin + '_> {
+        self.subscribe_with_options(kind, SubscriberOptions::default()).await
+    }
+
+    /// Subscribes to notifications or indications of value changes for this GATT characteristic, buffering values for
+    /// the returned stream as described by `options`.
+    ///
+    /// See [`subscribe`][Self::subscribe] for details.
+    #[inline]
+    pub async fn subscribe_with_options(
+        &self,
+        kind: SubscriptionKind,
+        options: SubscriberOptions,
+    ) -> Result<impl Stream<Item = Result<Notification>> + Send + Unpin + '_> {
+        notification::subscribe(self, kind, options).await
+    }
+
+    /// Disables notifications and indications for this GATT characteristic.
+    ///
+    /// Every stream returned by [`notify`][Self::notify] and [`subscribe`][Self::subscribe] for this characteristic
+    /// ends, including the streams of other parts of the process. Returns the number of streams ended.
+    #[inline]
+    pub async fn unsubscribe(&self) -> Result<usize> {
+        let ended = notification::unsubscribe(self);
+        self.0.unsubscribe().await?;
+        Ok(ended)
     }
 
     /// Is the device currently sending notifications for this characteristic?
@@ -109,4 +335,41 @@ impl Characteristic {
     pub async fn descriptors(&self) -> Result<Vec<Descriptor>> {
         self.0.descriptors().await
     }
+
+    /// Read and decode the Characteristic Presentation Format descriptor (`0x2904`) of this characteristic.
+    ///
+    /// Returns `None` if the characteristic has no such descriptor. Characteristics with an aggregate format have
+    /// several presentation format descriptors, in which case only the first is returned.
+    pub async fn presentation_format(&self) -> Result<Option<PresentationFormat>> {
+        match self
+            .find_descriptor(descriptors::CHARACTERISTIC_PRESENTATION_FORMAT)
+            .await?
+        {
+            Some(descriptor) => descriptor.read_as().await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Read the Characteristic User Description descriptor (`0x2901`) of this characteristic.
+    ///
+    /// Returns `None` if the characteristic has no such descriptor. Invalid UTF-8 sequences in the description are
+    /// replaced with `U+FFFD`.
+    pub async fn user_description(&self) -> Result<Option<String>> {
+        match self
+            .find_descriptor(descriptors::CHARACTERISTIC_USER_DESCRIPTION)
+            .await?
+        {
+            Some(descriptor) => Ok(Some(String::from_utf8_lossy(&descriptor.read().await?).into_owned())),
+            None => Ok(None),
+        }
+    }
+
+    async fn find_descriptor(&self, uuid: Uuid) -> Result<Option<Descriptor>> {
+        for descriptor in self.descriptors().await? {
+            if descriptor.uuid_async().await? == uuid {
+                return Ok(Some(descriptor));
+            }
+        }
+        Ok(None)
+    }
 }
@@ -0,0 +1,1108 @@
+//! Typed encoding and decoding of characteristic and descriptor values
+//!
+//! A [`GattCodec`] converts between the raw bytes of a characteristic or descriptor value and a Rust type. It is used
+//! by [`Characteristic::read_as`][crate::Characteristic::read_as], [`write_as`][crate::Characteristic::write_as] and
+//! [`notify_as`][crate::Characteristic::notify_as], and by [`Descriptor::read_as`][crate::Descriptor::read_as].
+//! Besides implementations for byte vectors, strings and little-endian integers, this module provides types for
+//! several characteristics and descriptors defined in the Bluetooth GATT Specification Supplement (GSS). Fields added
+//! by later versions of a specification are ignored when decoding.
+//!
+//! The values of characteristics with a [`PresentationFormat`] descriptor can be rendered for display with
+//! [`PresentationFormat::format_value`].
+//!
+//! ```
+//! use bluest::codec::{GattCodec, HeartRateMeasurement};
+//!
+//! let measurement = HeartRateMeasurement::decode(&[0x16, 0x48, 0x00, 0x04]).unwrap();
+//! assert_eq!(measurement.heart_rate, 72);
+//! assert_eq!(measurement.sensor_contact, Some(true));
+//! assert_eq!(measurement.rr_intervals, vec![1024]);
+//! ```
+
+use crate::error::ErrorKind;
+use crate::{Error, Result};
+
+/// Conversion between a characteristic value and a Rust type
+pub trait GattCodec: Sized {
+    /// Decodes a characteristic value.
+    ///
+    /// Returns an error with a kind of [`InvalidParameter`][ErrorKind::InvalidParameter] if `value` is not valid.
+    fn decode(value: &[u8]) -> Result<Self>;
+
+    /// Encodes `self` as a characteristic value.
+    ///
+    /// Returns an error with a kind of [`InvalidParameter`][ErrorKind::InvalidParameter] if `self` cannot be
+    /// represented, for example because a field is out of range.
+    fn encode(&self) -> Result<Vec<u8>>;
+}
+
+impl GattCodec for Vec<u8> {
+    fn decode(value: &[u8]) -> Result<Self> {
+        Ok(value.to_vec())
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        Ok(self.clone())
+    }
+}
+
+impl GattCodec for String {
+    fn decode(value: &[u8]) -> Result<Self> {
+        String::from_utf8(value.to_vec())
+            .map_err(|err| Error::new(ErrorKind::InvalidParameter, Some(Box::new(err)), "invalid UTF-8 string"))
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        Ok(self.as_bytes().to_vec())
+    }
+}
+
+macro_rules! impl_int_codec {
+    ($($ty:ty),*) => {
+        $(
+            impl GattCodec for $ty {
+                fn decode(value: &[u8]) -> Result<Self> {
+                    let bytes = value.try_into().map_err(|_| invalid_length())?;
+                    Ok(<$ty>::from_le_bytes(bytes))
+                }
+
+                fn encode(&self) -> Result<Vec<u8>> {
+                    Ok(self.to_le_bytes().to_vec())
+                }
+            }
+        )*
+    };
+}
+
+impl_int_codec!(u8, i8, u16, i16, u32, i32, u64, i64);
+
+/// An IEEE 11073-20601 16-bit floating point number (`SFLOAT`)
+///
+/// The value is `mantissa * 10^exponent`. The mantissa is a 12-bit and the exponent a 4-bit signed integer. The special
+/// values defined by IEEE 11073-20601 are represented with an exponent of 0 and are converted to infinities and NaN by
+/// [`to_f64`][Self::to_f64].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SFloat {
+    /// The mantissa, between -2048 and 2047
+    pub mantissa: i16,
+    /// The base 10 exponent, between -8 and 7
+    pub exponent: i8,
+}
+
+impl SFloat {
+    /// Not a number
+    pub const NAN: SFloat = SFloat {
+        mantissa: 0x07ff,
+        exponent: 0,
+    };
+
+    /// Creates a number with the value `mantissa * 10^exponent`.
+    pub const fn new(mantissa: i16, exponent: i8) -> Self {
+        SFloat { mantissa, exponent }
+    }
+
+    /// Converts the number to an [`f64`].
+    pub fn to_f64(self) -> f64 {
+        match (self.exponent, self.mantissa) {
+            (0, 0x07fe) => f64::INFINITY,
+            (0, -0x07fe) => f64::NEG_INFINITY,
+            (0, 0x07ff | -0x0800 | -0x07ff) => f64::NAN,
+            (exponent, mantissa) => scale(mantissa.into(), exponent),
+        }
+    }
+
+    fn from_bits(bits: u16) -> Self {
+        SFloat {
+            mantissa: ((bits << 4) as i16) >> 4,
+            exponent: ((bits >> 8) as i8) >> 4,
+        }
+    }
+
+    fn to_bits(self) -> Result<u16> {
+        if !(-0x0800..=0x07ff).contains(&self.mantissa) || !(-8..=7).contains(&self.exponent) {
+            return Err(Error::new(ErrorKind::InvalidParameter, None, "SFLOAT out of range"));
+        }
+        Ok((self.exponent as u16) << 12 | (self.mantissa as u16 & 0x0fff))
+    }
+}
+
+/// An IEEE 11073-20601 32-bit floating point number (`FLOAT`)
+///
+/// The value is `mantissa * 10^exponent`. The mantissa is a 24-bit and the exponent an 8-bit signed integer. The
+/// special values defined by IEEE 11073-20601 are represented with an exponent of 0 and are converted to infinities
+/// and NaN by [`to_f64`][Self::to_f64].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Float {
+    /// The mantissa, between -8388608 and 8388607
+    pub mantissa: i32,
+    /// The base 10 exponent
+    pub exponent: i8,
+}
+
+impl Float {
+    /// Not a number
+    pub const NAN: Float = Float {
+        mantissa: 0x007f_ffff,
+        exponent: 0,
+    };
+
+    /// Creates a number with the value `mantissa * 10^exponent`.
+    pub const fn new(mantissa: i32, exponent: i8) -> Self {
+        Float { mantissa, exponent }
+    }
+
+    /// Converts the number to an [`f64`].
+    pub fn to_f64(self) -> f64 {
+        match (self.exponent, self.mantissa) {
+            (0, 0x007f_fffe) => f64::INFINITY,
+            (0, -0x007f_fffe) => f64::NEG_INFINITY,
+            (0, 0x007f_ffff | -0x0080_0000 | -0x007f_ffff) => f64::NAN,
+            (exponent, mantissa) => scale(mantissa.into(), exponent),
+        }
+    }
+
+    fn from_bits(bits: u32) -> Self {
+        Float {
+            mantissa: ((bits << 8) as i32) >> 8,
+            exponent: (bits >> 24) as i8,
+        }
+    }
+
+    fn to_bits(self) -> Result<u32> {
+        if !(-0x0080_0000..=0x007f_ffff).contains(&self.mantissa) {
+            return Err(Error::new(ErrorKind::InvalidParameter, None, "FLOAT out of range"));
+        }
+        Ok((self.exponent as u8 as u32) << 24 | (self.mantissa as u32 & 0x00ff_ffff))
+    }
+}
+
+/// A date and time as used in GATT characteristics (`Date Time`)
+///
+/// A field of 0 means the field is not known.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DateTime {
+    /// The year, between 1582 and 9999
+    pub year: u16,
+    /// The month of the year, between 1 and 12
+    pub month: u8,
+    /// The day of the month, between 1 and 31
+    pub day: u8,
+    /// The hours past midnight, between 0 and 23
+    pub hours: u8,
+    /// The minutes since the start of the hour, between 0 and 59
+    pub minutes: u8,
+    /// The seconds since the start of the minute, between 0 and 59
+    pub seconds: u8,
+}
+
+impl DateTime {
+    fn read(reader: &mut Reader<'_>) -> Result<Self> {
+        Ok(DateTime {
+            year: reader.u16()?,
+            month: reader.u8()?,
+            day: reader.u8()?,
+            hours: reader.u8()?,
+            minutes: reader.u8()?,
+            seconds: reader.u8()?,
+        })
+    }
+
+    fn write(&self, value: &mut Vec<u8>) {
+        value.extend_from_slice(&self.year.to_le_bytes());
+        value.extend_from_slice(&[self.month, self.day, self.hours, self.minutes, self.seconds]);
+    }
+}
+
+impl GattCodec for DateTime {
+    fn decode(value: &[u8]) -> Result<Self> {
+        DateTime::read(&mut Reader(value))
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        let mut value = Vec::with_capacity(7);
+        self.write(&mut value);
+        Ok(value)
+    }
+}
+
+/// The value of the Battery Level characteristic (`0x2A19`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BatteryLevel {
+    /// The remaining charge in percent, between 0 and 100
+    pub percent: u8,
+}
+
+impl GattCodec for BatteryLevel {
+    fn decode(value: &[u8]) -> Result<Self> {
+        let percent = Reader(value).u8()?;
+        if percent > 100 {
+            return Err(Error::new(
+                ErrorKind::InvalidParameter,
+                None,
+                "battery level above 100%",
+            ));
+        }
+        Ok(BatteryLevel { percent })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        if self.percent > 100 {
+            return Err(Error::new(
+                ErrorKind::InvalidParameter,
+                None,
+                "battery level above 100%",
+            ));
+        }
+        Ok(vec![self.percent])
+    }
+}
+
+/// The value of the Heart Rate Measurement characteristic (`0x2A37`)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct HeartRateMeasurement {
+    /// The heart rate in beats per minute
+    pub heart_rate: u16,
+    /// Whether the sensor detects skin contact, if the sensor supports contact detection
+    pub sensor_contact: Option<bool>,
+    /// The energy expended since the last reset in kilojoules
+    pub energy_expended: Option<u16>,
+    /// The intervals between consecutive beats in units of 1/1024 second, oldest first
+    pub rr_intervals: Vec<u16>,
+}
+
+impl GattCodec for HeartRateMeasurement {
+    fn decode(value: &[u8]) -> Result<Self> {
+        let mut reader = Reader(value);
+        let flags = reader.u8()?;
+        let heart_rate = if flags & 0x01 != 0 {
+            reader.u16()?
+        } else {
+            reader.u8()?.into()
+        };
+        let sensor_contact = (flags & 0x04 != 0).then_some(flags & 0x02 != 0);
+        let energy_expended = reader.u16_if(flags & 0x08 != 0)?;
+        let mut rr_intervals = Vec::new();
+        if flags & 0x10 != 0 {
+            while !reader.0.is_empty() {
+                rr_intervals.push(reader.u16()?);
+            }
+        }
+
+        Ok(HeartRateMeasurement {
+            heart_rate,
+            sensor_contact,
+            energy_expended,
+            rr_intervals,
+        })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        let mut flags = 0;
+        let mut value = vec![0];
+        if let Ok(heart_rate) = u8::try_from(self.heart_rate) {
+            value.push(heart_rate);
+        } else {
+            flags |= 0x01;
+            value.extend_from_slice(&self.heart_rate.to_le_bytes());
+        }
+        if let Some(contact) = self.sensor_contact {
+            flags |= 0x04 | if contact { 0x02 } else { 0 };
+        }
+        if let Some(energy_expended) = self.energy_expended {
+            flags |= 0x08;
+            value.extend_from_slice(&energy_expended.to_le_bytes());
+        }
+        if !self.rr_intervals.is_empty() {
+            flags |= 0x10;
+            for interval in &self.rr_intervals {
+                value.extend_from_slice(&interval.to_le_bytes());
+            }
+        }
+        value[0] = flags;
+        Ok(value)
+    }
+}
+
+/// The unit of a [`TemperatureMeasurement`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub enum TemperatureUnit {
+    /// Degrees Celsius
+    #[default]
+    Celsius,
+    /// Degrees Fahrenheit
+    Fahrenheit,
+}
+
+/// The value of the Temperature Measurement characteristic (`0x2A1C`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TemperatureMeasurement {
+    /// The temperature
+    pub temperature: Float,
+    /// The unit of [`temperature`][Self::temperature]
+    pub unit: TemperatureUnit,
+    /// The time of the measurement
+    pub timestamp: Option<DateTime>,
+    /// Where the temperature was measured, as defined for the Temperature Type characteristic (`0x2A1D`)
+    pub temperature_type: Option<u8>,
+}
+
+impl GattCodec for TemperatureMeasurement {
+    fn decode(value: &[u8]) -> Result<Self> {
+        let mut reader = Reader(value);
+        let flags = reader.u8()?;
+        let temperature = Float::from_bits(reader.u32()?);
+        let unit = if flags & 0x01 != 0 {
+            TemperatureUnit::Fahrenheit
+        } else {
+            TemperatureUnit::Celsius
+        };
+        let timestamp = if flags & 0x02 != 0 {
+            Some(DateTime::read(&mut reader)?)
+        } else {
+            None
+        };
+        let temperature_type = if flags & 0x04 != 0 { Some(reader.u8()?) } else { None };
+
+        Ok(TemperatureMeasurement {
+            temperature,
+            unit,
+            timestamp,
+            temperature_type,
+        })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        let mut flags = 0;
+        let mut value = vec![0];
+        value.extend_from_slice(&self.temperature.to_bits()?.to_le_bytes());
+        if self.unit == TemperatureUnit::Fahrenheit {
+            flags |= 0x01;
+        }
+        if let Some(timestamp) = &self.timestamp {
+            flags |= 0x02;
+            timestamp.write(&mut value);
+        }
+        if let Some(temperature_type) = self.temperature_type {
+            flags |= 0x04;
+            value.push(temperature_type);
+        }
+        value[0] = flags;
+        Ok(value)
+    }
+}
+
+/// Cumulative wheel revolutions reported in a [`CscMeasurement`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct WheelRevolutions {
+    /// The number of wheel revolutions since the last reset
+    pub cumulative_revolutions: u32,
+    /// The time of the last wheel revolution in units of 1/1024 second, wrapping around
+    pub last_event_time: u16,
+}
+
+/// Cumulative crank revolutions reported in a [`CscMeasurement`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CrankRevolutions {
+    /// The number of crank revolutions, wrapping around
+    pub cumulative_revolutions: u16,
+    /// The time of the last crank revolution in units of 1/1024 second, wrapping around
+    pub last_event_time: u16,
+}
+
+/// The value of the CSC (Cycling Speed and Cadence) Measurement characteristic (`0x2A5B`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct CscMeasurement {
+    /// Wheel revolution data, used to compute speed and distance
+    pub wheel: Option<WheelRevolutions>,
+    /// Crank revolution data, used to compute cadence
+    pub crank: Option<CrankRevolutions>,
+}
+
+impl GattCodec for CscMeasurement {
+    fn decode(value: &[u8]) -> Result<Self> {
+        let mut reader = Reader(value);
+        let flags = reader.u8()?;
+        let wheel = if flags & 0x01 != 0 {
+            Some(WheelRevolutions {
+                cumulative_revolutions: reader.u32()?,
+                last_event_time: reader.u16()?,
+            })
+        } else {
+            None
+        };
+        let crank = if flags & 0x02 != 0 {
+            Some(CrankRevolutions {
+                cumulative_revolutions: reader.u16()?,
+                last_event_time: reader.u16()?,
+            })
+        } else {
+            None
+        };
+        Ok(CscMeasurement { wheel, crank })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        let mut flags = 0;
+        let mut value = vec![0];
+        if let Some(wheel) = &self.wheel {
+            flags |= 0x01;
+            value.extend_from_slice(&wheel.cumulative_revolutions.to_le_bytes());
+            value.extend_from_slice(&wheel.last_event_time.to_le_bytes());
+        }
+        if let Some(crank) = &self.crank {
+            flags |= 0x02;
+            value.extend_from_slice(&crank.cumulative_revolutions.to_le_bytes());
+            value.extend_from_slice(&crank.last_event_time.to_le_bytes());
+        }
+        value[0] = flags;
+        Ok(value)
+    }
+}
+
+/// The value of the RSC (Running Speed and Cadence) Measurement characteristic (`0x2A53`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct RscMeasurement {
+    /// The speed in units of 1/256 meter per second
+    pub speed: u16,
+    /// The cadence in steps per minute
+    pub cadence: u8,
+    /// The length of a stride in centimeters
+    pub stride_length: Option<u16>,
+    /// The distance travelled since the last reset in decimeters
+    pub total_distance: Option<u32>,
+    /// Whether the user is running rather than walking
+    pub is_running: bool,
+}
+
+impl GattCodec for RscMeasurement {
+    fn decode(value: &[u8]) -> Result<Self> {
+        let mut reader = Reader(value);
+        let flags = reader.u8()?;
+        Ok(RscMeasurement {
+            speed: reader.u16()?,
+            cadence: reader.u8()?,
+            stride_length: reader.u16_if(flags & 0x01 != 0)?,
+            total_distance: if flags & 0x02 != 0 { Some(reader.u32()?) } else { None },
+            is_running: flags & 0x04 != 0,
+        })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        let mut flags = 0;
+        let mut value = vec![0];
+        value.extend_from_slice(&self.speed.to_le_bytes());
+        value.push(self.cadence);
+        if let Some(stride_length) = self.stride_length {
+            flags |= 0x01;
+            value.extend_from_slice(&stride_length.to_le_bytes());
+        }
+        if let Some(total_distance) = self.total_distance {
+            flags |= 0x02;
+            value.extend_from_slice(&total_distance.to_le_bytes());
+        }
+        if self.is_running {
+            flags |= 0x04;
+        }
+        value[0] = flags;
+        Ok(value)
+    }
+}
+
+/// The value of the PnP ID characteristic (`0x2A50`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PnpId {
+    /// Who assigned [`vendor_id`][Self::vendor_id]: 1 for the Bluetooth SIG, 2 for the USB Implementer's Forum
+    pub vendor_id_source: u8,
+    /// The vendor identifier
+    pub vendor_id: u16,
+    /// The product identifier, assigned by the vendor
+    pub product_id: u16,
+    /// The product version, assigned by the vendor
+    pub product_version: u16,
+}
+
+impl GattCodec for PnpId {
+    fn decode(value: &[u8]) -> Result<Self> {
+        let mut reader = Reader(value);
+        Ok(PnpId {
+            vendor_id_source: reader.u8()?,
+            vendor_id: reader.u16()?,
+            product_id: reader.u16()?,
+            product_version: reader.u16()?,
+        })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        let mut value = vec![self.vendor_id_source];
+        value.extend_from_slice(&self.vendor_id.to_le_bytes());
+        value.extend_from_slice(&self.product_id.to_le_bytes());
+        value.extend_from_slice(&self.product_version.to_le_bytes());
+        Ok(value)
+    }
+}
+
+/// The value of the System ID characteristic (`0x2A23`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SystemId {
+    /// The manufacturer defined identifier, 40 bits
+    pub manufacturer_identifier: u64,
+    /// The organizationally unique identifier (OUI) of the manufacturer, 24 bits
+    pub organizationally_unique_identifier: u32,
+}
+
+impl GattCodec for SystemId {
+    fn decode(value: &[u8]) -> Result<Self> {
+        let value = u64::from_le_bytes(Reader(value).bytes()?);
+        Ok(SystemId {
+            manufacturer_identifier: value & 0xff_ffff_ffff,
+            organizationally_unique_identifier: (value >> 40) as u32,
+        })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        if self.manufacturer_identifier > 0xff_ffff_ffff || self.organizationally_unique_identifier > 0xff_ffff {
+            return Err(Error::new(ErrorKind::InvalidParameter, None, "system ID out of range"));
+        }
+        let value = u64::from(self.organizationally_unique_identifier) << 40 | self.manufacturer_identifier;
+        Ok(value.to_le_bytes().to_vec())
+    }
+}
+
+/// The unit of the pressures in a [`BloodPressureMeasurement`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub enum PressureUnit {
+    /// Millimeters of mercury
+    #[default]
+    MillimetersOfMercury,
+    /// Kilopascals
+    Kilopascals,
+}
+
+/// The value of the Blood Pressure Measurement characteristic (`0x2A35`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BloodPressureMeasurement {
+    /// The systolic pressure
+    pub systolic: SFloat,
+    /// The diastolic pressure
+    pub diastolic: SFloat,
+    /// The mean arterial pressure
+    pub mean_arterial_pressure: SFloat,
+    /// The unit of the pressures
+    pub unit: PressureUnit,
+    /// The time of the measurement
+    pub timestamp: Option<DateTime>,
+    /// The pulse rate in beats per minute
+    pub pulse_rate: Option<SFloat>,
+    /// The user the measurement belongs to, 255 if unknown
+    pub user_id: Option<u8>,
+    /// Status flags of the measurement, such as body movement or irregular pulse detection
+    pub measurement_status: Option<u16>,
+}
+
+impl GattCodec for BloodPressureMeasurement {
+    fn decode(value: &[u8]) -> Result<Self> {
+        let mut reader = Reader(value);
+        let flags = reader.u8()?;
+        let systolic = SFloat::from_bits(reader.u16()?);
+        let diastolic = SFloat::from_bits(reader.u16()?);
+        let mean_arterial_pressure = SFloat::from_bits(reader.u16()?);
+        let unit = if flags & 0x01 != 0 {
+            PressureUnit::Kilopascals
+        } else {
+            PressureUnit::MillimetersOfMercury
+        };
+        let timestamp = if flags & 0x02 != 0 {
+            Some(DateTime::read(&mut reader)?)
+        } else {
+            None
+        };
+        let pulse_rate = reader.u16_if(flags & 0x04 != 0)?.map(SFloat::from_bits);
+        let user_id = if flags & 0x08 != 0 { Some(reader.u8()?) } else { None };
+        let measurement_status = reader.u16_if(flags & 0x10 != 0)?;
+
+        Ok(BloodPressureMeasurement {
+            systolic,
+            diastolic,
+            mean_arterial_pressure,
+            unit,
+            timestamp,
+            pulse_rate,
+            user_id,
+            measurement_status,
+        })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        let mut flags = 0;
+        let mut value = vec![0];
+        for pressure in [self.systolic, self.diastolic, self.mean_arterial_pressure] {
+            value.extend_from_slice(&pressure.to_bits()?.to_le_bytes());
+        }
+        if self.unit == PressureUnit::Kilopascals {
+            flags |= 0x01;
+        }
+        if let Some(timestamp) = &self.timestamp {
+            flags |= 0x02;
+            timestamp.write(&mut value);
+        }
+        if let Some(pulse_rate) = self.pulse_rate {
+            flags |= 0x04;
+            value.extend_from_slice(&pulse_rate.to_bits()?.to_le_bytes());
+        }
+        if let Some(user_id) = self.user_id {
+            flags |= 0x08;
+            value.push(user_id);
+        }
+        if let Some(measurement_status) = self.measurement_status {
+            flags |= 0x10;
+            value.extend_from_slice(&measurement_status.to_le_bytes());
+        }
+        value[0] = flags;
+        Ok(value)
+    }
+}
+
+/// Assigned number identifying the format of a characteristic value in a [`PresentationFormat`]. See the Bluetooth
+/// Assigned Numbers document, §2.4.1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FormatType(u8);
+
+impl FormatType {
+    /// Unsigned 1-bit; 0 = false, 1 = true
+    pub const BOOLEAN: FormatType = FormatType(0x01);
+    /// Unsigned 2-bit integer
+    pub const UINT2: FormatType = FormatType(0x02);
+    /// Unsigned 4-bit integer
+    pub const UINT4: FormatType = FormatType(0x03);
+    /// Unsigned 8-bit integer
+    pub const UINT8: FormatType = FormatType(0x04);
+    /// Unsigned 12-bit integer
+    pub const UINT12: FormatType = FormatType(0x05);
+    /// Unsigned 16-bit integer
+    pub const UINT16: FormatType = FormatType(0x06);
+    /// Unsigned 24-bit integer
+    pub const UINT24: FormatType = FormatType(0x07);
+    /// Unsigned 32-bit integer
+    pub const UINT32: FormatType = FormatType(0x08);
+    /// Unsigned 48-bit integer
+    pub const UINT48: FormatType = FormatType(0x09);
+    /// Unsigned 64-bit integer
+    pub const UINT64: FormatType = FormatType(0x0a);
+    /// Unsigned 128-bit integer
+    pub const UINT128: FormatType = FormatType(0x0b);
+    /// Signed 8-bit integer
+    pub const SINT8: FormatType = FormatType(0x0c);
+    /// Signed 12-bit integer
+    pub const SINT12: FormatType = FormatType(0x0d);
+    /// Signed 16-bit integer
+    pub const SINT16: FormatType = FormatType(0x0e);
+    /// Signed 24-bit integer
+    pub const SINT24: FormatType = FormatType(0x0f);
+    /// Signed 32-bit integer
+    pub const SINT32: FormatType = FormatType(0x10);
+    /// Signed 48-bit integer
+    pub const SINT48: FormatType = FormatType(0x11);
+    /// Signed 64-bit integer
+    pub const SINT64: FormatType = FormatType(0x12);
+    /// Signed 128-bit integer
+    pub const SINT128: FormatType = FormatType(0x13);
+    /// IEEE-754 32-bit floating point
+    pub const FLOAT32: FormatType = FormatType(0x14);
+    /// IEEE-754 64-bit floating point
+    pub const FLOAT64: FormatType = FormatType(0x15);
+    /// IEEE 11073-20601 16-bit SFLOAT, see [`SFloat`]
+    pub const SFLOAT: FormatType = FormatType(0x16);
+    /// IEEE 11073-20601 32-bit FLOAT, see [`Float`]
+    pub const FLOAT: FormatType = FormatType(0x17);
+    /// IEEE 11073-20601 nomenclature code
+    pub const DUINT16: FormatType = FormatType(0x18);
+    /// UTF-8 string
+    pub const UTF8S: FormatType = FormatType(0x19);
+    /// UTF-16 string
+    pub const UTF16S: FormatType = FormatType(0x1a);
+    /// Opaque structure
+    pub const STRUCT: FormatType = FormatType(0x1b);
+
+    /// The number of bytes taken by a value of this format, or `None` for strings, structures and unknown formats.
+    pub fn size(self) -> Option<usize> {
+        Some(match self {
+            FormatType::BOOLEAN | FormatType::UINT2 | FormatType::UINT4 | FormatType::UINT8 | FormatType::SINT8 => 1,
+            FormatType::UINT12 | FormatType::UINT16 | FormatType::SINT12 | FormatType::SINT16 => 2,
+            FormatType::SFLOAT | FormatType::DUINT16 => 2,
+            FormatType::UINT24 | FormatType::SINT24 => 3,
+            FormatType::UINT32 | FormatType::SINT32 | FormatType::FLOAT32 | FormatType::FLOAT => 4,
+            FormatType::UINT48 | FormatType::SINT48 => 6,
+            FormatType::UINT64 | FormatType::SINT64 | FormatType::FLOAT64 => 8,
+            FormatType::UINT128 | FormatType::SINT128 => 16,
+            _ => return None,
+        })
+    }
+}
+
+impl From<u8> for FormatType {
+    fn from(number: u8) -> Self {
+        FormatType(number)
+    }
+}
+
+impl From<FormatType> for u8 {
+    fn from(val: FormatType) -> Self {
+        val.0
+    }
+}
+
+/// The value of the Characteristic Presentation Format descriptor (`0x2904`)
+///
+/// Describes how to interpret the value of a characteristic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PresentationFormat {
+    /// The format of the value
+    pub format: FormatType,
+    /// The base 10 exponent applied to integer values
+    pub exponent: i8,
+    /// The unit of the value, a 16-bit assigned number from the Bluetooth Assigned Numbers document, §3.5
+    pub unit: u16,
+    /// The organization defining [`description`][Self::description], 1 for the Bluetooth SIG
+    pub namespace: u8,
+    /// A description of the value defined by [`namespace`][Self::namespace], such as "first" or "left"
+    pub description: u16,
+}
+
+impl PresentationFormat {
+    /// The symbol of [`unit`][Self::unit] for commonly used units.
+    ///
+    /// Returns `Some("")` for unitless values and `None` for units without a known symbol.
+    pub fn unit_symbol(&self) -> Option<&'static str> {
+        Some(match self.unit {
+            0x2700 => "",
+            0x2701 => "m",
+            0x2702 => "kg",
+            0x2703 => "s",
+            0x2704 => "A",
+            0x2705 => "K",
+            0x2706 => "mol",
+            0x2707 => "cd",
+            0x2710 => "m²",
+            0x2711 => "m³",
+            0x2712 => "m/s",
+            0x2713 => "m/s²",
+            0x2720 => "rad",
+            0x2721 => "sr",
+            0x2722 => "Hz",
+            0x2723 => "N",
+            0x2724 => "Pa",
+            0x2725 => "J",
+            0x2726 => "W",
+            0x2727 => "C",
+            0x2728 => "V",
+            0x2729 => "F",
+            0x272a => "Ω",
+            0x272b => "S",
+            0x272f => "°C",
+            0x2730 => "lm",
+            0x2731 => "lx",
+            0x2760 => "min",
+            0x2761 => "h",
+            0x2762 => "d",
+            0x2763 => "°",
+            0x27ac => "°F",
+            0x27ad => "%",
+            0x27ae => "‰",
+            _ => return None,
+        })
+    }
+
+    /// Renders a characteristic value described by this format for display.
+    ///
+    /// Integers are scaled by [`exponent`][Self::exponent], and the [symbol][Self::unit_symbol] of the unit is
+    /// appended if it is known. Structures and unknown formats are rendered as hexadecimal bytes. Returns an error
+    /// with a kind of [`InvalidParameter`][ErrorKind::InvalidParameter] if `value` is too short for the format.
+    pub fn format_value(&self, value: &[u8]) -> Result<String> {
+        let len = self.format.size();
+        if let Some(len) = len {
+            if value.len() < len {
+                return Err(invalid_length());
+            }
+        }
+
+        let text = match self.format {
+            FormatType::BOOLEAN => (value[0] & 0x01 != 0).to_string(),
+            FormatType::UINT2 => decimal((value[0] & 0x03).into(), self.exponent),
+            FormatType::UINT4 => decimal((value[0] & 0x0f).into(), self.exponent),
+            FormatType::UINT12 => decimal(
+                (u16::from_le_bytes([value[0], value[1]]) & 0x0fff).into(),
+                self.exponent,
+            ),
+            FormatType::SINT12 => decimal(
+                ((u16::from_le_bytes([value[0], value[1]]) << 4) as i16 >> 4).into(),
+                self.exponent,
+            ),
+            FormatType::UINT8
+            | FormatType::UINT16
+            | FormatType::UINT24
+            | FormatType::UINT32
+            | FormatType::UINT48
+            | FormatType::UINT64
+            | FormatType::UINT128
+            | FormatType::DUINT16 => {
+                let mut bytes = [0; 16];
+                bytes[..len.unwrap()].copy_from_slice(&value[..len.unwrap()]);
+                let value = u128::from_le_bytes(bytes);
+                match i128::try_from(value) {
+                    Ok(value) => decimal(value, self.exponent),
+                    Err(_) => value.to_string(),
+                }
+            }
+            FormatType::SINT8
+            | FormatType::SINT16
+            | FormatType::SINT24
+            | FormatType::SINT32
+            | FormatType::SINT48
+            | FormatType::SINT64
+            | FormatType::SINT128 => {
+                let len = len.unwrap();
+                let mut bytes = [0; 16];
+                bytes[..len].copy_from_slice(&value[..len]);
+                // Sign extend from the most significant byte of the value
+                let shift = 128 - 8 * len as u32;
+                decimal(i128::from_le_bytes(bytes) << shift >> shift, self.exponent)
+            }
+            FormatType::FLOAT32 => f32::from_le_bytes(value[..4].try_into().unwrap()).to_string(),
+            FormatType::FLOAT64 => f64::from_le_bytes(value[..8].try_into().unwrap()).to_string(),
+            FormatType::SFLOAT => SFloat::from_bits(u16::from_le_bytes([value[0], value[1]]))
+                .to_f64()
+                .to_string(),
+            FormatType::FLOAT => Float::from_bits(u32::from_le_bytes(value[..4].try_into().unwrap()))
+                .to_f64()
+                .to_string(),
+            FormatType::UTF8S => String::from_utf8_lossy(value).into_owned(),
+            FormatType::UTF16S => {
+                let units: Vec<u16> = value
+                    .chunks_exact(2)
+                    .map(|x| u16::from_le_bytes([x[0], x[1]]))
+                    .collect();
+                String::from_utf16_lossy(&units)
+            }
+            _ => value.iter().map(|x| format!("{x:02x}")).collect::<Vec<_>>().join(" "),
+        };
+
+        match self.unit_symbol() {
+            Some(symbol) if !symbol.is_empty() => Ok(format!("{text} {symbol}")),
+            _ => Ok(text),
+        }
+    }
+}
+
+impl GattCodec for PresentationFormat {
+    fn decode(value: &[u8]) -> Result<Self> {
+        let mut reader = Reader(value);
+        Ok(PresentationFormat {
+            format: FormatType(reader.u8()?),
+            exponent: reader.u8()? as i8,
+            unit: reader.u16()?,
+            namespace: reader.u8()?,
+            description: reader.u16()?,
+        })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        let mut value = vec![self.format.0, self.exponent as u8];
+        value.extend_from_slice(&self.unit.to_le_bytes());
+        value.push(self.namespace);
+        value.extend_from_slice(&self.description.to_le_bytes());
+        Ok(value)
+    }
+}
+
+/// The value of the Characteristic Extended Properties descriptor (`0x2900`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ExtendedProperties {
+    /// The characteristic supports reliable writes
+    pub reliable_write: bool,
+    /// The Characteristic User Description descriptor can be written
+    pub writable_auxiliaries: bool,
+}
+
+impl GattCodec for ExtendedProperties {
+    fn decode(value: &[u8]) -> Result<Self> {
+        let bits = Reader(value).u16()?;
+        Ok(ExtendedProperties {
+            reliable_write: bits & 0x0001 != 0,
+            writable_auxiliaries: bits & 0x0002 != 0,
+        })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        let bits = u16::from(self.reliable_write) | u16::from(self.writable_auxiliaries) << 1;
+        Ok(bits.to_le_bytes().to_vec())
+    }
+}
+
+/// The value of the Valid Range descriptor (`0x2906`)
+///
+/// The bounds are inclusive and have the format of the characteristic value, so they can be interpreted with the
+/// characteristic's [`PresentationFormat`] or [`GattCodec`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ValidRange {
+    /// The lower bound
+    pub lower: Vec<u8>,
+    /// The upper bound
+    pub upper: Vec<u8>,
+}
+
+impl GattCodec for ValidRange {
+    fn decode(value: &[u8]) -> Result<Self> {
+        if value.is_empty() || !value.len().is_multiple_of(2) {
+            return Err(invalid_length());
+        }
+        let (lower, upper) = value.split_at(value.len() / 2);
+        Ok(ValidRange {
+            lower: lower.to_vec(),
+            upper: upper.to_vec(),
+        })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        if self.lower.len() != self.upper.len() {
+            return Err(Error::new(
+                ErrorKind::InvalidParameter,
+                None,
+                "valid range bounds have different lengths",
+            ));
+        }
+        Ok([&self.lower[..], &self.upper[..]].concat())
+    }
+}
+
+/// The value of the Environmental Sensing Measurement descriptor (`0x290C`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct EnvironmentalSensingMeasurement {
+    /// Reserved for future use
+    pub flags: u16,
+    /// How the value was sampled, such as instantaneous (1) or arithmetic mean (2)
+    pub sampling_function: u8,
+    /// The period over which the value was measured in seconds, 24 bits
+    pub measurement_period: u32,
+    /// The interval between updates of the value in seconds, 24 bits
+    pub update_interval: u32,
+    /// The intended application of the value, such as air (1) or water (2)
+    pub application: u8,
+    /// The uncertainty of the value in units of 0.5%
+    pub measurement_uncertainty: u8,
+}
+
+impl GattCodec for EnvironmentalSensingMeasurement {
+    fn decode(value: &[u8]) -> Result<Self> {
+        let mut reader = Reader(value);
+        Ok(EnvironmentalSensingMeasurement {
+            flags: reader.u16()?,
+            sampling_function: reader.u8()?,
+            measurement_period: reader.u24()?,
+            update_interval: reader.u24()?,
+            application: reader.u8()?,
+            measurement_uncertainty: reader.u8()?,
+        })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        if self.measurement_period > 0xff_ffff || self.update_interval > 0xff_ffff {
+            return Err(Error::new(ErrorKind::InvalidParameter, None, "interval out of range"));
+        }
+        let mut value = self.flags.to_le_bytes().to_vec();
+        value.push(self.sampling_function);
+        value.extend_from_slice(&self.measurement_period.to_le_bytes()[..3]);
+        value.extend_from_slice(&self.update_interval.to_le_bytes()[..3]);
+        value.extend_from_slice(&[self.application, self.measurement_uncertainty]);
+        Ok(value)
+    }
+}
+
+/// The value of the Environmental Sensing Trigger Setting descriptor (`0x290D`)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EnvironmentalSensingTriggerSetting {
+    /// When notifications are sent, such as when the value changes (3) or crosses a threshold (4 to 9)
+    pub condition: u8,
+    /// The time interval or threshold the condition compares against, in the format of the characteristic value for
+    /// threshold conditions
+    pub operand: Vec<u8>,
+}
+
+impl GattCodec for EnvironmentalSensingTriggerSetting {
+    fn decode(value: &[u8]) -> Result<Self> {
+        let mut reader = Reader(value);
+        Ok(EnvironmentalSensingTriggerSetting {
+            condition: reader.u8()?,
+            operand: reader.0.to_vec(),
+        })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        Ok([&[self.condition][..], &self.operand[..]].concat())
+    }
+}
+
+/// Renders `value * 10^exponent` without rounding errors.
+fn decimal(value: i128, exponent: i8) -> String {
+    if exponent >= 0 {
+        let mut text = value.to_string();
+        if value != 0 {
+            text.extend(std::iter::repeat_n('0', exponent as usize));
+        }
+        return text;
+    }
+
+    let digits = value.unsigned_abs().to_string();
+    let scale = exponent.unsigned_abs() as usize;
+    let digits = format!("{digits:0>width$}", width = scale + 1);
+    let (int, frac) = digits.split_at(digits.len() - scale);
+    let sign = if value < 0 { "-" } else { "" };
+    format!("{sign}{int}.{frac}")
+}
+
+/// Reads little-endian fields from the front of a value.
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn bytes<const N: usize>(&mut self) -> Result<[u8; N]> {
+        if self.0.len() < N {
+            return Err(invalid_length());
+        }
+        let (bytes, rest) = self.0.split_at(N);
+        self.0 = rest;
+        Ok(bytes.try_into().unwrap())
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.bytes::<1>()?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        self.bytes().map(u16::from_le_bytes)
+    }
+
+    fn u16_if(&mut self, present: bool) -> Result<Option<u16>> {
+        present.then(|| self.u16()).transpose()
+    }
+
+    fn u24(&mut self) -> Result<u32> {
+        let [a, b, c] = self.bytes()?;
+        Ok(u32::from_le_bytes([a, b, c, 0]))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        self.bytes().map(u32::from_le_bytes)
+    }
+}
+
+/// Computes `mantissa * 10^exponent`, dividing for negative exponents so that values such as 36.5 are exact.
+fn scale(mantissa: f64, exponent: i8) -> f64 {
+    if exponent < 0 {
+        mantissa / 10f64.powi(-i32::from(exponent))
+    } else {
+        mantissa * 10f64.powi(exponent.into())
+    }
+}
+
+fn invalid_length() -> Error {
+    Error::new(ErrorKind::InvalidParameter, None, "invalid characteristic value length")
+}
@@ -3,12 +3,14 @@
 use crate::Uuid;
 
 pub mod adapter;
+pub mod advertiser;
 pub mod characteristic;
 pub mod descriptor;
 pub mod device;
 pub mod error;
 #[cfg(feature = "l2cap")]
 pub mod l2cap_channel;
+pub mod server;
 pub mod service;
 
 mod ad;
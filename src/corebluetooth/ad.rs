@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use objc2_core_bluetooth::{
     CBAdvertisementDataIsConnectable, CBAdvertisementDataLocalNameKey, CBAdvertisementDataManufacturerDataKey,
     CBAdvertisementDataOverflowServiceUUIDsKey, CBAdvertisementDataServiceDataKey, CBAdvertisementDataServiceUUIDsKey,
-    CBAdvertisementDataTxPowerLevelKey, CBUUID,
+    CBAdvertisementDataSolicitedServiceUUIDsKey, CBAdvertisementDataTxPowerLevelKey, CBUUID,
 };
 use objc2_foundation::{NSArray, NSData, NSDictionary, NSNumber, NSString};
 use uuid::Uuid;
@@ -71,13 +71,26 @@ impl AdvertisementData {
             .map(|data| unsafe { Uuid::from_bluetooth_bytes(data.as_bytes_unchecked()) })
             .collect();
 
+        let solicited_services = adv_data
+            .objectForKey(unsafe { CBAdvertisementDataSolicitedServiceUUIDsKey })
+            .into_iter()
+            .flat_map(|x| x.downcast::<NSArray>())
+            .flatten()
+            .flat_map(|obj| obj.downcast::<CBUUID>())
+            .map(|uuid| unsafe { uuid.data() })
+            .map(|data| unsafe { Uuid::from_bluetooth_bytes(data.as_bytes_unchecked()) })
+            .collect();
+
         AdvertisementData {
             local_name,
+            manufacturer_data_list: manufacturer_data.iter().cloned().collect(),
             manufacturer_data,
             services,
             service_data,
             tx_power_level,
             is_connectable,
+            solicited_services,
+            ..Default::default()
         }
     }
 }
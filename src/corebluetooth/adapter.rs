@@ -10,18 +10,23 @@ use objc2::rc::Retained;
 use objc2::runtime::ProtocolObject;
 use objc2::{AnyThread, Message};
 use objc2_core_bluetooth::{
-    CBCentralManager, CBCentralManagerOptionShowPowerAlertKey, CBManager, CBManagerAuthorization, CBManagerState,
-    CBUUID,
+    CBCentralManager, CBCentralManagerOptionShowPowerAlertKey, CBCentralManagerScanOptionAllowDuplicatesKey, CBManager,
+    CBManagerAuthorization, CBManagerState, CBUUID,
 };
 use objc2_foundation::{NSArray, NSData, NSDictionary, NSNumber, NSString, NSUUID};
 use tracing::{debug, error, info, warn};
 
 use super::delegates::{self, CentralDelegate};
+use super::device::DeviceImpl;
 use super::dispatch::{self, Dispatched};
+use super::DeviceId;
+use crate::advertiser::{Advertisement, AdvertisingHandle};
 use crate::error::ErrorKind;
+use crate::server::{Application, GattServer};
 use crate::util::defer;
 use crate::{
-    AdapterEvent, AdvertisingDevice, BluetoothUuidExt, ConnectionEvent, Device, DeviceId, Error, Result, Uuid,
+    AdapterEvent, AdvertisingDevice, BluetoothUuidExt, ConnectionEvent, Device, Error, Result, ScanOptions,
+    ScanTransport, Uuid,
 };
 
 #[derive(Default)]
@@ -201,6 +206,29 @@ impl AdapterImpl {
         &'a self,
         services: &'a [Uuid],
     ) -> Result<impl Stream<Item = AdvertisingDevice> + Send + Unpin + 'a> {
+        self.start_scan(services, false)
+    }
+
+    /// Starts scanning for Bluetooth advertising packets using the provided options.
+    pub async fn scan_with_options(
+        &self,
+        options: ScanOptions,
+    ) -> Result<impl Stream<Item = AdvertisingDevice> + Send + Unpin + '_> {
+        if options.transport == ScanTransport::BrEdr {
+            return Err(Error::new(
+                ErrorKind::NotSupported,
+                None,
+                "scanning for BR/EDR devices is not supported",
+            ));
+        }
+        Ok(options.filter(self.start_scan(&options.services, options.allow_duplicates)?))
+    }
+
+    fn start_scan(
+        &self,
+        services: &[Uuid],
+        allow_duplicates: bool,
+    ) -> Result<impl Stream<Item = AdvertisingDevice> + Send + Unpin + '_> {
         if self.state() != CBManagerState::PoweredOn {
             return Err(Error::from(ErrorKind::AdapterUnavailable));
         }
@@ -220,7 +248,12 @@ impl AdapterImpl {
                 NSArray::from_retained_slice(&vec[..])
             });
 
-            unsafe { central.scanForPeripheralsWithServices_options(services.as_deref(), None) };
+            let options: Retained<NSDictionary<NSString>> = NSDictionary::from_retained_objects(
+                &[unsafe { CBCentralManagerScanOptionAllowDuplicatesKey }],
+                &[NSNumber::numberWithBool(allow_duplicates).into()],
+            );
+
+            unsafe { central.scanForPeripheralsWithServices_options(services.as_deref(), Some(options.deref())) };
         });
 
         let guard = defer(|| {
@@ -240,7 +273,7 @@ impl AdapterImpl {
                     } => peripheral.dispatch(|peripheral| {
                         Some(AdvertisingDevice {
                             device: Device::new(peripheral.retain()),
-                            adv_data,
+                            adv_data: *adv_data,
                             rssi: Some(rssi),
                         })
                     }),
@@ -281,7 +314,7 @@ impl AdapterImpl {
     /// successful return from this method, a connection has been established with the device (if one did not already
     /// exist) and the application can then interact with the device. This connection will be maintained until either
     /// [`disconnect_device`][Self::disconnect_device] is called or the `Adapter` is dropped.
-    pub async fn connect_device(&self, device: &Device) -> Result<()> {
+    pub async fn connect_device(&self, device: &DeviceImpl) -> Result<()> {
         if self.state() != CBManagerState::PoweredOn {
             return Err(ErrorKind::AdapterUnavailable.into());
         }
@@ -289,11 +322,11 @@ impl AdapterImpl {
         let mut events = self.delegate.sender().new_receiver();
         debug!("Connecting to {:?}", device);
         self.central
-            .dispatch(|central| unsafe { central.connectPeripheral_options(device.0.peripheral.get(), None) });
+            .dispatch(|central| unsafe { central.connectPeripheral_options(device.peripheral.get(), None) });
 
         let drop = defer(|| {
             self.central.dispatch(|central| unsafe {
-                central.cancelPeripheralConnection(device.0.peripheral.get());
+                central.cancelPeripheralConnection(device.peripheral.get());
             })
         });
 
@@ -303,11 +336,11 @@ impl AdapterImpl {
                 return Err(ErrorKind::AdapterUnavailable.into());
             }
             match event {
-                delegates::CentralEvent::Connect { peripheral } if peripheral == device.0.peripheral => {
+                delegates::CentralEvent::Connect { peripheral } if peripheral == device.peripheral => {
                     drop.defuse();
                     return Ok(());
                 }
-                delegates::CentralEvent::ConnectFailed { peripheral, error } if peripheral == device.0.peripheral => {
+                delegates::CentralEvent::ConnectFailed { peripheral, error } if peripheral == device.peripheral => {
                     drop.defuse();
                     return Err(error.map_or(ErrorKind::ConnectionFailed.into(), Error::from_nserror));
                 }
@@ -323,7 +356,7 @@ impl AdapterImpl {
     /// Once this method is called, the application will no longer have access to the [`Device`] and any methods
     /// which would require a connection will fail. If no other application has a connection to the same device,
     /// the underlying Bluetooth connection will be closed.
-    pub async fn disconnect_device(&self, device: &Device) -> Result<()> {
+    pub async fn disconnect_device(&self, device: &DeviceImpl) -> Result<()> {
         if self.state() != CBManagerState::PoweredOn {
             return Err(ErrorKind::AdapterUnavailable.into());
         }
@@ -331,7 +364,7 @@ impl AdapterImpl {
         let mut events = self.delegate.sender().new_receiver();
         debug!("Disconnecting from {:?}", device);
         self.central
-            .dispatch(|central| unsafe { central.cancelPeripheralConnection(device.0.peripheral.get()) });
+            .dispatch(|central| unsafe { central.cancelPeripheralConnection(device.peripheral.get()) });
         while let Some(event) = events.next().await {
             if self.state() != CBManagerState::PoweredOn {
                 return Err(ErrorKind::AdapterUnavailable.into());
@@ -340,11 +373,11 @@ impl AdapterImpl {
                 delegates::CentralEvent::Disconnect {
                     peripheral,
                     error: None,
-                } if peripheral == device.0.peripheral => return Ok(()),
+                } if peripheral == device.peripheral => return Ok(()),
                 delegates::CentralEvent::Disconnect {
                     peripheral,
                     error: Some(err),
-                } if peripheral == device.0.peripheral => return Err(Error::from_nserror(err)),
+                } if peripheral == device.peripheral => return Err(Error::from_nserror(err)),
                 _ => (),
             }
         }
@@ -412,13 +445,12 @@ impl AdapterImpl {
     #[cfg(not(target_os = "macos"))]
     pub async fn device_connection_events<'a>(
         &'a self,
-        device: &'a Device,
+        device: &'a DeviceImpl,
     ) -> Result<impl Stream<Item = ConnectionEvent> + Send + Unpin + 'a> {
         let events = self.delegate.sender().new_receiver();
         let guard = self.register_connection_events(device.id());
 
         let id = device
-            .0
             .peripheral
             .dispatch(|peripheral| unsafe { peripheral.identifier() });
 
@@ -457,19 +489,41 @@ impl AdapterImpl {
     #[cfg(target_os = "macos")]
     pub async fn device_connection_events<'a>(
         &'a self,
-        device: &'a Device,
+        device: &'a DeviceImpl,
     ) -> Result<impl Stream<Item = ConnectionEvent> + Send + Unpin + 'a> {
         let events = self.delegate.sender().new_receiver();
         Ok(events
             .take_while(|_| self.state() == CBManagerState::PoweredOn)
             .filter_map(move |x| match x {
-                delegates::CentralEvent::Connect { peripheral } if peripheral == device.0.peripheral => {
+                delegates::CentralEvent::Connect { peripheral } if peripheral == device.peripheral => {
                     Some(ConnectionEvent::Connected)
                 }
-                delegates::CentralEvent::Disconnect { peripheral, .. } if peripheral == device.0.peripheral => {
+                delegates::CentralEvent::Disconnect { peripheral, .. } if peripheral == device.peripheral => {
                     Some(ConnectionEvent::Disconnected)
                 }
                 _ => None,
             }))
     }
+
+    /// Publishes the local GATT services described by `application`.
+    ///
+    /// Returns [ErrorKind::NotSupported].
+    pub async fn serve_gatt(&self, _application: Application) -> Result<GattServer> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// Starts sending `advertisement`.
+    ///
+    /// Returns [ErrorKind::NotSupported].
+    pub async fn advertise(&self, _advertisement: Advertisement) -> Result<AdvertisingHandle> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// Listens for L2CAP channels opened by remote devices.
+    ///
+    /// Returns [ErrorKind::NotSupported].
+    #[cfg(feature = "l2cap")]
+    pub async fn listen_l2cap(&self, _psm: Option<u16>, _secure: bool) -> Result<super::l2cap_channel::L2capListener> {
+        Err(ErrorKind::NotSupported.into())
+    }
 }
@@ -0,0 +1,5 @@
+/// An advertisement sent on this platform
+///
+/// Advertising is not supported on this platform, so this type has no values.
+#[derive(Debug)]
+pub enum AdvertisingHandleImpl {}
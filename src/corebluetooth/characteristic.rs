@@ -1,3 +1,5 @@
+use std::time::Instant;
+
 use futures_core::Stream;
 use futures_lite::StreamExt;
 use objc2::rc::Retained;
@@ -8,9 +10,13 @@ use objc2_foundation::NSData;
 
 use super::delegates::{PeripheralDelegate, PeripheralEvent};
 use super::dispatch::Dispatched;
+use super::DeviceId;
 use crate::error::ErrorKind;
 use crate::util::defer;
-use crate::{BluetoothUuidExt, Characteristic, CharacteristicProperties, Descriptor, Error, Result, Uuid};
+use crate::{
+    BluetoothUuidExt, Characteristic, CharacteristicProperties, Descriptor, Error, Notification, Result,
+    SubscriptionKind, Uuid,
+};
 
 /// A Bluetooth GATT characteristic
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -21,7 +27,7 @@ pub struct CharacteristicImpl {
 
 impl Characteristic {
     pub(super) fn new(characteristic: Retained<CBCharacteristic>, delegate: Retained<PeripheralDelegate>) -> Self {
-        Characteristic(CharacteristicImpl {
+        Characteristic::from_platform(CharacteristicImpl {
             inner: unsafe { Dispatched::new(characteristic) },
             delegate,
         })
@@ -41,6 +47,27 @@ impl CharacteristicImpl {
         Ok(self.uuid())
     }
 
+    /// The attribute handle of this GATT characteristic.
+    ///
+    /// Returns [ErrorKind::NotSupported]. CoreBluetooth does not expose attribute handles.
+    pub fn handle(&self) -> Result<u16> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// The identifier of the device this GATT characteristic belongs to.
+    pub fn device_id(&self) -> Result<DeviceId> {
+        self.inner.dispatch(|characteristic| {
+            let peripheral = unsafe { characteristic.service().and_then(|x| x.peripheral()) }.ok_or(Error::new(
+                ErrorKind::NotFound,
+                None,
+                "peripheral not found",
+            ))?;
+            Ok(DeviceId(unsafe {
+                Uuid::from_bluetooth_bytes(&peripheral.identifier().as_bytes()[..])
+            }))
+        })
+    }
+
     /// The properties of this this GATT characteristic.
     ///
     /// Characteristic properties indicate which operations (e.g. read, write, notify, etc) may be performed on this
@@ -134,6 +161,20 @@ impl CharacteristicImpl {
         }
     }
 
+    /// Read the value of this characteristic from the device, starting at `offset`.
+    ///
+    /// An `offset` other than 0 returns [ErrorKind::NotSupported].
+    pub async fn read_with_offset(&self, offset: u16) -> Result<Vec<u8>> {
+        if offset != 0 {
+            return Err(Error::new(
+                ErrorKind::NotSupported,
+                None,
+                "reading at an offset is not supported by CoreBluetooth",
+            ));
+        }
+        self.read().await
+    }
+
     /// Write the value of this descriptor on the device to `value` and request the device return a response indicating
     /// a successful write.
     pub async fn write(&self, value: &[u8]) -> Result<()> {
@@ -239,6 +280,28 @@ impl CharacteristicImpl {
         }
     }
 
+    /// Write `value` to the value of this characteristic on the device, starting at `offset`.
+    ///
+    /// CoreBluetooth uses prepared writes automatically for long values, but an `offset` other than 0 returns
+    /// [ErrorKind::NotSupported].
+    pub async fn write_long(&self, offset: u16, value: &[u8]) -> Result<()> {
+        if offset != 0 {
+            return Err(Error::new(
+                ErrorKind::NotSupported,
+                None,
+                "writing at an offset is not supported by CoreBluetooth",
+            ));
+        }
+        self.write(value).await
+    }
+
+    /// Writes every value in `writes` in a single reliable write transaction.
+    ///
+    /// Returns [ErrorKind::NotSupported].
+    pub async fn execute_reliable_write(_writes: &[(&Self, &[u8])]) -> Result<()> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
     /// Get the maximum amount of data that can be written in a single packet for this characteristic.
     pub fn max_write_len(&self) -> Result<usize> {
         self.inner.dispatch(|characteristic| {
@@ -355,6 +418,75 @@ impl CharacteristicImpl {
         Ok(updates)
     }
 
+    /// Subscribes to notifications or indications of value changes for this GATT characteristic.
+    ///
+    /// CoreBluetooth enables notifications when a characteristic supports both, so indications are only available for
+    /// characteristics which do not support notifications.
+    pub async fn subscribe(
+        &self,
+        kind: SubscriptionKind,
+    ) -> Result<impl Stream<Item = Result<Notification>> + Send + Unpin + '_> {
+        let properties = self.properties().await?;
+        kind.check_supported(&properties)?;
+        if kind == SubscriptionKind::Indicate && properties.notify {
+            return Err(Error::new(
+                ErrorKind::NotSupported,
+                None,
+                "CoreBluetooth enables notifications for characteristics supporting both notifications and indications",
+            ));
+        }
+
+        let is_indication = kind == SubscriptionKind::Indicate;
+        Ok(self.notify().await?.map(move |x| {
+            x.map(|value| Notification {
+                value,
+                is_indication,
+                received_at: Instant::now(),
+            })
+        }))
+    }
+
+    /// Disables notifications and indications for this GATT characteristic.
+    pub async fn unsubscribe(&self) -> Result<()> {
+        let mut receiver = self.delegate.sender().new_receiver();
+        let service = self.inner.dispatch(|characteristic| {
+            let service = unsafe { characteristic.service() }.ok_or(Error::new(
+                ErrorKind::NotFound,
+                None,
+                "service not found",
+            ))?;
+            let peripheral =
+                unsafe { service.peripheral() }.ok_or(Error::new(ErrorKind::NotFound, None, "peripheral not found"))?;
+
+            if unsafe { peripheral.state() } != CBPeripheralState::Connected {
+                return Err(Error::from(ErrorKind::NotConnected));
+            }
+
+            unsafe { peripheral.setNotifyValue_forCharacteristic(false, characteristic) };
+            Ok(unsafe { Dispatched::new(service) })
+        })?;
+
+        loop {
+            match receiver.recv().await.map_err(Error::from_recv_error)? {
+                PeripheralEvent::NotificationStateUpdate { characteristic, error } if characteristic == self.inner => {
+                    match error {
+                        Some(err) => return Err(Error::from_nserror(err)),
+                        None => return Ok(()),
+                    }
+                }
+                PeripheralEvent::Disconnected { error } => {
+                    return Err(Error::from_kind_and_nserror(ErrorKind::NotConnected, error));
+                }
+                PeripheralEvent::ServicesChanged { invalidated_services }
+                    if invalidated_services.contains(&service) =>
+                {
+                    return Err(ErrorKind::ServiceChanged.into());
+                }
+                _ => (),
+            }
+        }
+    }
+
     /// Is the device currently sending notifications for this characteristic?
     pub async fn is_notifying(&self) -> Result<bool> {
         Ok(self
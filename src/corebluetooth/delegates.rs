@@ -29,7 +29,7 @@ pub enum CentralEvent {
     },
     Discovered {
         peripheral: Dispatched<CBPeripheral>,
-        adv_data: AdvertisementData,
+        adv_data: Box<AdvertisementData>,
         rssi: i16,
     },
     StateChanged,
@@ -198,7 +198,7 @@ define_class!(
             let rssi: i16 = rssi.shortValue();
             let event = CentralEvent::Discovered {
                 peripheral: unsafe { Dispatched::retain(peripheral) },
-                adv_data: AdvertisementData::from_nsdictionary(adv_data),
+                adv_data: Box::new(AdvertisementData::from_nsdictionary(adv_data)),
                 rssi,
             };
             debug!("CentralDelegate received {:?}", event);
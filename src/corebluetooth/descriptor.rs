@@ -41,7 +41,7 @@ fn value_to_slice(val: &AnyObject) -> Vec<u8> {
 
 impl Descriptor {
     pub(super) fn new(descriptor: Retained<CBDescriptor>, delegate: Retained<PeripheralDelegate>) -> Self {
-        Descriptor(DescriptorImpl {
+        Descriptor::from_platform(DescriptorImpl {
             inner: unsafe { Dispatched::new(descriptor) },
             delegate,
         })
@@ -60,6 +60,13 @@ impl DescriptorImpl {
         Ok(self.uuid())
     }
 
+    /// The attribute handle of this GATT descriptor.
+    ///
+    /// Returns [ErrorKind::NotSupported]. CoreBluetooth does not expose attribute handles.
+    pub fn handle(&self) -> Result<u16> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
     /// The cached value of this descriptor
     ///
     /// If the value has not yet been read, this method may either return an error or perform a read of the value.
@@ -113,6 +120,20 @@ impl DescriptorImpl {
         }
     }
 
+    /// Read the value of this descriptor from the device, starting at `offset`.
+    ///
+    /// An `offset` other than 0 returns [ErrorKind::NotSupported].
+    pub async fn read_with_offset(&self, offset: u16) -> Result<Vec<u8>> {
+        if offset != 0 {
+            return Err(Error::new(
+                ErrorKind::NotSupported,
+                None,
+                "reading at an offset is not supported by CoreBluetooth",
+            ));
+        }
+        self.read().await
+    }
+
     /// Write the value of this descriptor on the device to `value`
     pub async fn write(&self, value: &[u8]) -> Result<()> {
         let mut receiver = self.delegate.sender().new_receiver();
@@ -4,17 +4,19 @@ use futures_core::Stream;
 use futures_lite::StreamExt;
 use objc2::rc::Retained;
 use objc2::runtime::ProtocolObject;
-use objc2_core_bluetooth::{CBPeripheral, CBPeripheralState, CBService, CBUUID};
+use objc2_core_bluetooth::{CBCharacteristicWriteType, CBPeripheral, CBPeripheralState, CBService, CBUUID};
 use objc2_foundation::{NSArray, NSData};
 
 use super::delegates::{PeripheralDelegate, PeripheralEvent};
 use super::dispatch::Dispatched;
 #[cfg(feature = "l2cap")]
 use super::l2cap_channel::{L2capChannelReader, L2capChannelWriter};
+use super::service::ServiceImpl;
+use super::DeviceId;
 use crate::device::ServicesChanged;
 use crate::error::ErrorKind;
 use crate::pairing::PairingAgent;
-use crate::{BluetoothUuidExt, Device, DeviceId, Error, Result, Service, Uuid};
+use crate::{BluetoothUuidExt, ConnectionPhy, ConnectionPriority, Device, Error, Phy, Result, Service, Uuid};
 
 /// A Bluetooth LE device
 #[derive(Clone)]
@@ -60,7 +62,7 @@ impl Device {
 
         let delegate = delegate.downcast().unwrap();
 
-        Device(DeviceImpl {
+        Device::from_platform(DeviceImpl {
             peripheral: unsafe { Dispatched::new(peripheral) },
             delegate,
         })
@@ -200,9 +202,9 @@ impl DeviceImpl {
         }
 
         Ok(receiver.filter_map(|ev| match ev {
-            PeripheralEvent::ServicesChanged { invalidated_services } => {
-                Some(Ok(ServicesChanged(ServicesChangedImpl(invalidated_services))))
-            }
+            PeripheralEvent::ServicesChanged { invalidated_services } => Some(Ok(ServicesChanged::from_platform(
+                ServicesChangedImpl(invalidated_services),
+            ))),
             PeripheralEvent::Disconnected { error } => {
                 Some(Err(Error::from_kind_and_nserror(ErrorKind::NotConnected, error)))
             }
@@ -225,9 +227,68 @@ impl DeviceImpl {
         }
     }
 
+    /// Monitors the signal strength of the device.
+    pub async fn rssi_updates(&self) -> Result<impl Stream<Item = i16> + Send + Unpin + '_> {
+        let receiver = self.delegate.sender().new_receiver();
+        Ok(receiver.filter_map(|ev| match ev {
+            PeripheralEvent::ReadRssi { rssi, error: None } => Some(rssi),
+            _ => None,
+        }))
+    }
+
+    /// The ATT MTU of the connection.
+    pub async fn mtu(&self) -> Result<u16> {
+        if !self.is_connected().await {
+            return Err(ErrorKind::NotConnected.into());
+        }
+
+        let len = self.peripheral.dispatch(|peripheral| unsafe {
+            peripheral.maximumWriteValueLengthForType(CBCharacteristicWriteType::WithoutResponse)
+        });
+        // GATT characteristic writes have 3 bytes of overhead (opcode + handle id)
+        Ok(u16::try_from(len + 3).unwrap_or(u16::MAX))
+    }
+
+    /// CoreBluetooth negotiates the largest supported MTU when connecting, so this returns the current MTU.
+    pub async fn request_mtu(&self, _mtu: u16) -> Result<u16> {
+        self.mtu().await
+    }
+
+    /// Monitors changes to the ATT MTU of the connection.
+    ///
+    /// Returns [ErrorKind::NotSupported].
+    pub async fn mtu_updates(&self) -> Result<impl Stream<Item = u16> + Send + Unpin + '_> {
+        Err::<futures_lite::stream::Empty<_>, _>(ErrorKind::NotSupported.into())
+    }
+
+    /// Requests connection parameters matching `priority`.
+    ///
+    /// Returns [ErrorKind::NotSupported].
+    pub async fn set_connection_priority(&self, _priority: ConnectionPriority) -> Result<()> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// The PHYs used by the connection.
+    ///
+    /// Returns [ErrorKind::NotSupported].
+    pub async fn phy(&self) -> Result<ConnectionPhy> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// Sets the preferred PHYs for the connection.
+    ///
+    /// Returns [ErrorKind::NotSupported].
+    pub async fn set_preferred_phy(&self, _tx: Phy, _rx: Phy) -> Result<()> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
     /// Open L2CAP channel given PSM
     #[cfg(feature = "l2cap")]
-    pub async fn open_l2cap_channel(&self, psm: u16, _secure: bool) -> Result<super::l2cap_channel::L2capChannel> {
+    pub async fn open_l2cap_channel(
+        &self,
+        psm: u16,
+        _options: &crate::L2capOptions,
+    ) -> Result<super::l2cap_channel::L2capChannel> {
         use tracing::{debug, info};
 
         let mut receiver = self.delegate.sender().new_receiver();
@@ -271,7 +332,7 @@ impl DeviceImpl {
 pub struct ServicesChangedImpl(Vec<Dispatched<CBService>>);
 
 impl ServicesChangedImpl {
-    pub fn was_invalidated(&self, service: &Service) -> bool {
-        self.0.contains(&service.0.inner)
+    pub fn was_invalidated(&self, service: &ServiceImpl) -> bool {
+        self.0.contains(&service.inner)
     }
 }
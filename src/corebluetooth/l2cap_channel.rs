@@ -16,7 +16,9 @@ use objc2_foundation::{
 use tracing::{debug, trace, warn};
 
 use super::dispatch::Dispatched;
+use crate::error::ErrorKind;
 use crate::l2cap_channel::{derive_async_read, derive_async_write, PIPE_CAPACITY};
+use crate::{Device, Result};
 
 /// Utility struct to close the channel on drop.
 pub(super) struct L2capCloser {
@@ -48,6 +50,18 @@ pub struct L2capChannel {
 }
 
 impl L2capChannel {
+    pub fn send_mtu(&self) -> Result<u16> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    pub fn recv_mtu(&self) -> Result<u16> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    pub fn is_encrypted(&self) -> Result<bool> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
     pub fn split(self) -> (L2capChannelReader, L2capChannelWriter) {
         (self.reader, self.writer)
     }
@@ -56,6 +70,21 @@ impl L2capChannel {
 derive_async_read!(L2capChannel, reader);
 derive_async_write!(L2capChannel, writer);
 
+/// A listener for L2CAP channels opened by remote devices
+///
+/// Listening for L2CAP channels is not supported on this platform, so this type has no values.
+pub enum L2capListener {}
+
+impl L2capListener {
+    pub fn psm(&self) -> u16 {
+        match *self {}
+    }
+
+    pub async fn accept(&self) -> Result<(Device, L2capChannel)> {
+        match *self {}
+    }
+}
+
 /// The reader side of an L2CAP channel.
 pub struct L2capChannelReader {
     stream: piper::Reader,
@@ -16,7 +16,7 @@ pub struct ServiceImpl {
 
 impl Service {
     pub(super) fn new(service: Retained<CBService>, delegate: Retained<PeripheralDelegate>) -> Self {
-        Service(ServiceImpl {
+        Service::from_platform(ServiceImpl {
             inner: unsafe { Dispatched::new(service) },
             delegate,
         })
@@ -35,6 +35,20 @@ impl ServiceImpl {
         Ok(self.uuid())
     }
 
+    /// The attribute handle of this GATT service.
+    ///
+    /// Returns [ErrorKind::NotSupported]. CoreBluetooth does not expose attribute handles.
+    pub fn handle(&self) -> Result<u16> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// The last attribute handle belonging to this GATT service.
+    ///
+    /// Returns [ErrorKind::NotSupported]. CoreBluetooth does not expose attribute handles.
+    pub fn end_handle(&self) -> Result<u16> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
     /// Whether this is a primary service of the device.
     pub async fn is_primary(&self) -> Result<bool> {
         self.inner.dispatch(|service| unsafe { Ok(service.isPrimary()) })
@@ -1,3 +1,4 @@
+use crate::codec::GattCodec;
 use crate::{sys, Result, Uuid};
 
 /// A Bluetooth GATT descriptor
@@ -5,6 +6,12 @@ use crate::{sys, Result, Uuid};
 pub struct Descriptor(pub(crate) sys::descriptor::DescriptorImpl);
 
 impl Descriptor {
+    pub(crate) fn from_platform(inner: crate::platform::descriptor::DescriptorImpl) -> Self {
+        #[cfg(feature = "mock")]
+        let inner = sys::descriptor::DescriptorImpl::Platform(inner);
+        Descriptor(inner)
+    }
+
     /// The [`Uuid`] identifying the type of this GATT descriptor
     ///
     /// # Panics
@@ -23,6 +30,16 @@ impl Descriptor {
         self.0.uuid_async().await
     }
 
+    /// The attribute handle of this GATT descriptor.
+    ///
+    /// # Platform specific
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS and Android.
+    #[inline]
+    pub fn handle(&self) -> Result<u16> {
+        self.0.handle()
+    }
+
     /// The cached value of this descriptor
     ///
     /// If the value has not yet been read, this method may either return an error or perform a read of the value.
@@ -37,9 +54,43 @@ impl Descriptor {
         self.0.read().await
     }
 
+    /// Read the value of this descriptor from the device and decode it as a `T`.
+    ///
+    /// Returns an error with a kind of [`InvalidParameter`][crate::error::ErrorKind::InvalidParameter] if the value
+    /// cannot be decoded.
+    #[inline]
+    pub async fn read_as<T: GattCodec>(&self) -> Result<T> {
+        T::decode(&self.read().await?)
+    }
+
+    /// Read the value of this descriptor from the device, starting at `offset`.
+    ///
+    /// Returns the part of the value from `offset` to its end, which may take several ATT Read Blob requests. This can
+    /// be used to page through values which are too long to read at once. The device may reject the read with an
+    /// [`AttError::INVALID_OFFSET`] protocol error if `offset` is past the end of the value, or with
+    /// [`AttError::ATTRIBUTE_NOT_LONG`] if `offset` is not 0 and the value fits in a single ATT Read response.
+    ///
+    /// # Platform specific
+    ///
+    /// An `offset` other than 0 returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS, Windows
+    /// and Android.
+    ///
+    /// [`AttError::INVALID_OFFSET`]: crate::error::AttError::INVALID_OFFSET
+    /// [`AttError::ATTRIBUTE_NOT_LONG`]: crate::error::AttError::ATTRIBUTE_NOT_LONG
+    #[inline]
+    pub async fn read_with_offset(&self, offset: u16) -> Result<Vec<u8>> {
+        self.0.read_with_offset(offset).await
+    }
+
     /// Write the value of this descriptor on the device to `value`
     #[inline]
     pub async fn write(&self, value: &[u8]) -> Result<()> {
         self.0.write(value).await
     }
+
+    /// Encode `value` and write it to the value of this descriptor with [`write`][Self::write].
+    #[inline]
+    pub async fn write_as<T: GattCodec>(&self, value: &T) -> Result<()> {
+        self.write(&value.encode()?).await
+    }
 }
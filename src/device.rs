@@ -5,9 +5,12 @@ use futures_lite::StreamExt;
 
 use crate::error::ErrorKind;
 #[cfg(feature = "l2cap")]
-use crate::l2cap_channel::L2capChannel;
+use crate::l2cap_channel::{L2capChannel, L2capOptions};
 use crate::pairing::PairingAgent;
-use crate::{sys, DeviceId, Error, Result, Service, Uuid};
+use crate::{
+    sys, ConnectionPhy, ConnectionPriority, DeviceId, DeviceInformation, Error, GattDatabase, Phy, Result, Service,
+    Uuid,
+};
 
 /// A Bluetooth LE device
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -21,6 +24,12 @@ impl std::fmt::Display for Device {
 }
 
 impl Device {
+    pub(crate) fn from_platform(inner: crate::platform::device::DeviceImpl) -> Self {
+        #[cfg(feature = "mock")]
+        let inner = sys::device::DeviceImpl::Platform(inner);
+        Device(inner)
+    }
+
     /// This device's unique identifier
     #[inline]
     pub fn id(&self) -> DeviceId {
@@ -98,6 +107,24 @@ impl Device {
         self.0.unpair().await
     }
 
+    /// Reads the characteristics of the Device Information Service of this device.
+    ///
+    /// Characteristics which the device does not provide are left empty. Returns an error with a kind of
+    /// [`NotFound`][ErrorKind::NotFound] if the device has no Device Information Service.
+    pub async fn device_information(&self) -> Result<DeviceInformation> {
+        DeviceInformation::read(self).await
+    }
+
+    /// Discovers the services, included services, characteristics and descriptors of this device into a
+    /// [`GattDatabase`].
+    ///
+    /// If `read_values` is true, the values of readable characteristics and of descriptors are read from the device as
+    /// well. Values which the device or platform refuses to read, for example because they require authentication, are
+    /// left empty. Other errors, such as the device disconnecting, are returned.
+    pub async fn gatt_snapshot(&self, read_values: bool) -> Result<GattDatabase> {
+        GattDatabase::from_device(self, read_values).await
+    }
+
     /// Discover the primary services of this device.
     #[inline]
     pub async fn discover_services(&self) -> Result<Vec<Service>> {
@@ -149,23 +176,140 @@ impl Device {
     ///
     /// # Platform specific
     ///
-    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on Windows and Linux.
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on Windows.
+    ///
+    /// On Linux this is the signal strength of the last advertisement received from the device. Returns
+    /// [`NotReady`][crate::error::ErrorKind::NotReady] if the device has not been seen by a recent scan.
     #[inline]
     pub async fn rssi(&self) -> Result<i16> {
         self.0.rssi().await
     }
 
+    /// Monitors the signal strength of the device in dBm.
+    ///
+    /// # Platform specific
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on Windows and Android.
+    ///
+    /// On Linux a new value is produced whenever BlueZ receives an advertisement with a different signal strength,
+    /// which requires an active scan. On MacOS/iOS a new value is produced whenever the signal strength is read with
+    /// [`rssi`][Self::rssi].
+    #[inline]
+    pub async fn rssi_updates(&self) -> Result<impl Stream<Item = i16> + Send + Unpin + '_> {
+        self.0.rssi_updates().await
+    }
+
+    /// The ATT MTU of the connection to this device.
+    ///
+    /// The device must be connected.
+    ///
+    /// # Platform specific
+    ///
+    /// On Linux and Android the MTU is read from the first GATT characteristic of the device. Linux requires BlueZ 5.62
+    /// or later.
+    #[inline]
+    pub async fn mtu(&self) -> Result<u16> {
+        self.0.mtu().await
+    }
+
+    /// Requests an ATT MTU of `mtu` for the connection to this device, returning the MTU in effect afterwards.
+    ///
+    /// The resulting MTU may be smaller than requested if the device does not support it.
+    ///
+    /// # Platform specific
+    ///
+    /// MacOS/iOS, Windows, Linux and Android negotiate the largest supported MTU automatically when connecting, so this
+    /// method returns the current MTU without changing it. On Android this requires
+    /// `AdapterConfig::request_mtu_on_connect`, which is enabled by default.
+    #[inline]
+    pub async fn request_mtu(&self, mtu: u16) -> Result<u16> {
+        self.0.request_mtu(mtu).await
+    }
+
+    /// Monitors changes to the ATT MTU of the connection to this device.
+    ///
+    /// # Platform specific
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS, Linux and Android.
+    #[inline]
+    pub async fn mtu_updates(&self) -> Result<impl Stream<Item = u16> + Send + Unpin + '_> {
+        self.0.mtu_updates().await
+    }
+
+    /// Requests connection parameters (connection interval, peripheral latency and supervision timeout) matching
+    /// `priority` for the connection to this device.
+    ///
+    /// # Platform specific
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS, Linux and Android. CoreBluetooth
+    /// and BlueZ do not let applications request connection parameters, and the `android-ble` crate does not expose
+    /// the `BluetoothGatt` object that Android requests them with.
+    ///
+    /// Requires Windows 11. The parameters remain in effect until another priority is requested or every clone of this
+    /// `Device` is dropped.
+    #[inline]
+    pub async fn set_connection_priority(&self, priority: ConnectionPriority) -> Result<()> {
+        self.0.set_connection_priority(priority).await
+    }
+
+    /// The PHYs used by the connection to this device.
+    ///
+    /// # Platform specific
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS, Linux and Android, for the same
+    /// reasons as [`set_connection_priority`][Self::set_connection_priority].
+    ///
+    /// Requires Windows 11.
+    #[inline]
+    pub async fn phy(&self) -> Result<ConnectionPhy> {
+        self.0.phy().await
+    }
+
+    /// Sets the preferred PHYs for the connection to this device. The controllers may choose other PHYs.
+    ///
+    /// # Platform specific
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS, Windows, Linux and Android, whose
+    /// APIs available to Bluest have no way to set the preferred PHYs. Only simulated devices of the `mock` backend
+    /// support it.
+    #[inline]
+    pub async fn set_preferred_phy(&self, tx: Phy, rx: Phy) -> Result<()> {
+        self.0.set_preferred_phy(tx, rx).await
+    }
+
     /// Open an L2CAP connection-oriented channel (CoC) to this device.
     ///
+    /// If `secure` is `true`, the channel requires an encrypted link. This is equivalent to
+    /// [`open_l2cap_channel_with_options`][Self::open_l2cap_channel_with_options] with only
+    /// [`secure`][L2capOptions::secure] set.
+    ///
     /// # Platform specific
     ///
-    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on iOS/MacOS and Linux.
+    /// `secure` is ignored on iOS/MacOS, where the security of the link is decided by the system.
     /// The `l2cap` feature is not available on Windows.
     #[inline]
     #[cfg(feature = "l2cap")]
     pub async fn open_l2cap_channel(&self, psm: u16, secure: bool) -> Result<L2capChannel> {
-        let channel = self.0.open_l2cap_channel(psm, secure).await?;
-        Ok(L2capChannel(channel))
+        let options = L2capOptions {
+            secure,
+            ..Default::default()
+        };
+        self.open_l2cap_channel_with_options(psm, &options).await
+    }
+
+    /// Open an L2CAP connection-oriented channel (CoC) to this device with the given `options`.
+    ///
+    /// # Platform specific
+    ///
+    /// On Linux, all options are honoured and the channel is opened over the address type BlueZ reports for this
+    /// device.
+    ///
+    /// On iOS/MacOS all options are ignored, and on Android only [`secure`][L2capOptions::secure] is honoured.
+    /// The `l2cap` feature is not available on Windows.
+    #[cfg(feature = "l2cap")]
+    pub async fn open_l2cap_channel_with_options(&self, psm: u16, options: &L2capOptions) -> Result<L2capChannel> {
+        let channel = self.0.open_l2cap_channel(psm, options).await?;
+        Ok(L2capChannel::new(channel, self.clone(), psm))
     }
 }
 
@@ -174,6 +318,12 @@ impl Device {
 pub struct ServicesChanged(pub(crate) sys::device::ServicesChangedImpl);
 
 impl ServicesChanged {
+    pub(crate) fn from_platform(inner: crate::platform::device::ServicesChangedImpl) -> Self {
+        #[cfg(feature = "mock")]
+        let inner = sys::device::ServicesChangedImpl::Platform(inner);
+        ServicesChanged(inner)
+    }
+
     /// Check if `service` was invalidated by this service changed indication.
     ///
     /// # Platform specific
@@ -181,6 +331,6 @@ impl ServicesChanged {
     /// Windows does not indicate which services were affected by a services changed event, so this method will
     /// pessimistically return true for all services.
     pub fn was_invalidated(&self, service: &Service) -> bool {
-        self.0.was_invalidated(service)
+        self.0.was_invalidated(&service.0)
     }
 }
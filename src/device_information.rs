@@ -0,0 +1,76 @@
+use crate::btuuid::{characteristics, services};
+use crate::codec::{GattCodec, PnpId, SystemId};
+use crate::error::ErrorKind;
+use crate::{Characteristic, Device, Error, Result, Uuid};
+
+/// The contents of the Device Information Service (`0x180A`) of a device
+///
+/// Returned by [`Device::device_information`]. Characteristics which the device does not provide are `None`.
+#[non_exhaustive]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct DeviceInformation {
+    /// The Manufacturer Name String characteristic
+    pub manufacturer_name: Option<String>,
+    /// The Model Number String characteristic
+    pub model_number: Option<String>,
+    /// The Serial Number String characteristic
+    pub serial_number: Option<String>,
+    /// The Hardware Revision String characteristic
+    pub hardware_revision: Option<String>,
+    /// The Firmware Revision String characteristic
+    pub firmware_revision: Option<String>,
+    /// The Software Revision String characteristic
+    pub software_revision: Option<String>,
+    /// The System ID characteristic
+    pub system_id: Option<SystemId>,
+    /// The PnP ID characteristic
+    pub pnp_id: Option<PnpId>,
+}
+
+impl DeviceInformation {
+    pub(crate) async fn read(device: &Device) -> Result<Self> {
+        let service = device
+            .discover_services_with_uuid(services::DEVICE_INFORMATION)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::new(ErrorKind::NotFound, None, "device information service not found"))?;
+        let characteristics = service.discover_characteristics().await?;
+
+        Ok(DeviceInformation {
+            manufacturer_name: read_string(&characteristics, characteristics::MANUFACTURER_NAME_STRING).await?,
+            model_number: read_string(&characteristics, characteristics::MODEL_NUMBER_STRING).await?,
+            serial_number: read_string(&characteristics, characteristics::SERIAL_NUMBER_STRING).await?,
+            hardware_revision: read_string(&characteristics, characteristics::HARDWARE_REVISION_STRING).await?,
+            firmware_revision: read_string(&characteristics, characteristics::FIRMWARE_REVISION_STRING).await?,
+            software_revision: read_string(&characteristics, characteristics::SOFTWARE_REVISION_STRING).await?,
+            system_id: read(&characteristics, characteristics::SYSTEM_ID).await?,
+            pnp_id: read(&characteristics, characteristics::PNP_ID).await?,
+        })
+    }
+}
+
+async fn find(characteristics: &[Characteristic], uuid: Uuid) -> Result<Option<&Characteristic>> {
+    for characteristic in characteristics {
+        if characteristic.uuid_async().await? == uuid {
+            return Ok(Some(characteristic));
+        }
+    }
+    Ok(None)
+}
+
+async fn read<T: GattCodec>(characteristics: &[Characteristic], uuid: Uuid) -> Result<Option<T>> {
+    match find(characteristics, uuid).await? {
+        Some(characteristic) => characteristic.read_as().await.map(Some),
+        None => Ok(None),
+    }
+}
+
+async fn read_string(characteristics: &[Characteristic], uuid: Uuid) -> Result<Option<String>> {
+    let Some(characteristic) = find(characteristics, uuid).await? else {
+        return Ok(None);
+    };
+    // Some devices pad their strings with NUL characters or use encodings other than UTF-8
+    let value = characteristic.read().await?;
+    Ok(Some(String::from_utf8_lossy(&value).trim_end_matches('\0').to_string()))
+}
@@ -82,6 +82,8 @@ pub enum ErrorKind {
     Internal,
     /// the service changed and is no longer valid
     ServiceChanged,
+    /// values were dropped because a subscriber fell behind
+    Lagged,
     /// error
     Other,
 }
@@ -102,6 +104,7 @@ impl std::fmt::Display for ErrorKind {
             ErrorKind::Protocol(err) => write!(f, "protocol error: {err}"),
             ErrorKind::Internal => f.write_str("an internal error has occured"),
             ErrorKind::ServiceChanged => f.write_str("the service changed and is no longer valid"),
+            ErrorKind::Lagged => f.write_str("values were dropped because a subscriber fell behind"),
             ErrorKind::Other => f.write_str("error"),
         }
     }
@@ -0,0 +1,363 @@
+use std::future::Future;
+use std::pin::Pin;
+
+use crate::error::ErrorKind;
+use crate::{Characteristic, CharacteristicProperties, Descriptor, Device, Result, Service, Uuid};
+
+/// A snapshot of the GATT database of a device
+///
+/// Created by [`Device::gatt_snapshot`]. With the `serde` feature enabled, snapshots can be serialized, for example to
+/// store a device's GATT database as a fixture for offline tests. Two snapshots can be compared with
+/// [`diff`][Self::diff].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GattDatabase {
+    /// The services of the device
+    pub services: Vec<GattService>,
+}
+
+/// A service in a [`GattDatabase`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GattService {
+    /// The [`Uuid`] identifying the type of the service
+    pub uuid: Uuid,
+    /// Whether the service is a primary service, if reported by the platform
+    pub is_primary: Option<bool>,
+    /// The services included by the service
+    pub included_services: Vec<GattService>,
+    /// The characteristics of the service
+    pub characteristics: Vec<GattCharacteristic>,
+}
+
+/// A characteristic in a [`GattDatabase`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GattCharacteristic {
+    /// The [`Uuid`] identifying the type of the characteristic
+    pub uuid: Uuid,
+    /// The properties of the characteristic
+    pub properties: CharacteristicProperties,
+    /// The value of the characteristic, if values were read and the characteristic could be read
+    pub value: Option<Vec<u8>>,
+    /// The descriptors of the characteristic
+    pub descriptors: Vec<GattDescriptor>,
+}
+
+/// A descriptor in a [`GattDatabase`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GattDescriptor {
+    /// The [`Uuid`] identifying the type of the descriptor
+    pub uuid: Uuid,
+    /// The value of the descriptor, if values were read and the descriptor could be read
+    pub value: Option<Vec<u8>>,
+}
+
+/// A difference between two [`GattDatabase`] snapshots, as returned by [`GattDatabase::diff`]
+///
+/// Services, characteristics and descriptors are matched by their [`Uuid`] and, where several share a [`Uuid`], by
+/// their order.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GattDifference {
+    /// A service was added
+    ServiceAdded {
+        /// The added service
+        service: Uuid,
+    },
+    /// A service was removed
+    ServiceRemoved {
+        /// The removed service
+        service: Uuid,
+    },
+    /// A service changed between primary and secondary or changed its included services
+    ServiceChanged {
+        /// The changed service
+        service: Uuid,
+    },
+    /// A characteristic was added
+    CharacteristicAdded {
+        /// The service containing the characteristic
+        service: Uuid,
+        /// The added characteristic
+        characteristic: Uuid,
+    },
+    /// A characteristic was removed
+    CharacteristicRemoved {
+        /// The service containing the characteristic
+        service: Uuid,
+        /// The removed characteristic
+        characteristic: Uuid,
+    },
+    /// The properties of a characteristic changed
+    PropertiesChanged {
+        /// The service containing the characteristic
+        service: Uuid,
+        /// The changed characteristic
+        characteristic: Uuid,
+        /// The properties in the old snapshot
+        old: CharacteristicProperties,
+        /// The properties in the new snapshot
+        new: CharacteristicProperties,
+    },
+    /// The value of a characteristic changed
+    CharacteristicValueChanged {
+        /// The service containing the characteristic
+        service: Uuid,
+        /// The changed characteristic
+        characteristic: Uuid,
+        /// The value in the old snapshot
+        old: Option<Vec<u8>>,
+        /// The value in the new snapshot
+        new: Option<Vec<u8>>,
+    },
+    /// A descriptor was added
+    DescriptorAdded {
+        /// The service containing the descriptor
+        service: Uuid,
+        /// The characteristic containing the descriptor
+        characteristic: Uuid,
+        /// The added descriptor
+        descriptor: Uuid,
+    },
+    /// A descriptor was removed
+    DescriptorRemoved {
+        /// The service containing the descriptor
+        service: Uuid,
+        /// The characteristic containing the descriptor
+        characteristic: Uuid,
+        /// The removed descriptor
+        descriptor: Uuid,
+    },
+    /// The value of a descriptor changed
+    DescriptorValueChanged {
+        /// The service containing the descriptor
+        service: Uuid,
+        /// The characteristic containing the descriptor
+        characteristic: Uuid,
+        /// The changed descriptor
+        descriptor: Uuid,
+        /// The value in the old snapshot
+        old: Option<Vec<u8>>,
+        /// The value in the new snapshot
+        new: Option<Vec<u8>>,
+    },
+}
+
+impl GattDatabase {
+    pub(crate) async fn from_device(device: &Device, read_values: bool) -> Result<Self> {
+        let mut services = Vec::new();
+        for service in device.discover_services().await? {
+            services.push(GattService::from_service(service, read_values).await?);
+        }
+        Ok(GattDatabase { services })
+    }
+
+    /// Returns a copy of this snapshot with every characteristic and descriptor value removed.
+    ///
+    /// Useful to compare only the structure of two databases, for example to fingerprint a device.
+    pub fn without_values(&self) -> GattDatabase {
+        fn strip(service: &mut GattService) {
+            service.included_services.iter_mut().for_each(strip);
+            for characteristic in &mut service.characteristics {
+                characteristic.value = None;
+                for descriptor in &mut characteristic.descriptors {
+                    descriptor.value = None;
+                }
+            }
+        }
+
+        let mut database = self.clone();
+        database.services.iter_mut().for_each(strip);
+        database
+    }
+
+    /// Lists the differences between this snapshot and a `newer` one.
+    ///
+    /// Returns an empty list if the snapshots are equal.
+    pub fn diff(&self, newer: &GattDatabase) -> Vec<GattDifference> {
+        let mut differences = Vec::new();
+        for (old, new) in match_by_uuid(&self.services, &newer.services, |x| x.uuid) {
+            match (old, new) {
+                (Some(old), Some(new)) => diff_service(old, new, &mut differences),
+                (Some(old), None) => differences.push(GattDifference::ServiceRemoved { service: old.uuid }),
+                (None, Some(new)) => differences.push(GattDifference::ServiceAdded { service: new.uuid }),
+                (None, None) => unreachable!(),
+            }
+        }
+        differences
+    }
+}
+
+impl GattService {
+    fn from_service(service: Service, read_values: bool) -> Pin<Box<dyn Future<Output = Result<Self>> + Send>> {
+        // Included services are boxed to allow the recursion
+        Box::pin(async move {
+            let mut included_services = Vec::new();
+            for included in service.discover_included_services().await? {
+                included_services.push(GattService::from_service(included, read_values).await?);
+            }
+
+            let mut characteristics = Vec::new();
+            for characteristic in service.discover_characteristics().await? {
+                characteristics.push(GattCharacteristic::from_characteristic(characteristic, read_values).await?);
+            }
+
+            Ok(GattService {
+                uuid: service.uuid_async().await?,
+                is_primary: service.is_primary().await.ok(),
+                included_services,
+                characteristics,
+            })
+        })
+    }
+}
+
+impl GattCharacteristic {
+    async fn from_characteristic(characteristic: Characteristic, read_values: bool) -> Result<Self> {
+        let properties = characteristic.properties().await?;
+        let value = if read_values && properties.read {
+            unreadable_as_none(characteristic.read().await)?
+        } else {
+            None
+        };
+
+        let mut descriptors = Vec::new();
+        for descriptor in characteristic.discover_descriptors().await? {
+            descriptors.push(GattDescriptor::from_descriptor(descriptor, read_values).await?);
+        }
+
+        Ok(GattCharacteristic {
+            uuid: characteristic.uuid_async().await?,
+            properties,
+            value,
+            descriptors,
+        })
+    }
+}
+
+impl GattDescriptor {
+    async fn from_descriptor(descriptor: Descriptor, read_values: bool) -> Result<Self> {
+        let value = if read_values {
+            unreadable_as_none(descriptor.read().await)?
+        } else {
+            None
+        };
+
+        Ok(GattDescriptor {
+            uuid: descriptor.uuid_async().await?,
+            value,
+        })
+    }
+}
+
+/// Maps the errors of values which the device or platform refuses to read to `None`, keeping other errors such as
+/// disconnections.
+fn unreadable_as_none(value: Result<Vec<u8>>) -> Result<Option<Vec<u8>>> {
+    match value {
+        Ok(value) => Ok(Some(value)),
+        Err(err)
+            if matches!(
+                err.kind(),
+                ErrorKind::Protocol(_) | ErrorKind::NotAuthorized | ErrorKind::NotSupported
+            ) =>
+        {
+            Ok(None)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+fn diff_service(old: &GattService, new: &GattService, differences: &mut Vec<GattDifference>) {
+    let service = old.uuid;
+    if old.is_primary != new.is_primary || old.included_services != new.included_services {
+        differences.push(GattDifference::ServiceChanged { service });
+    }
+
+    for (old, new) in match_by_uuid(&old.characteristics, &new.characteristics, |x| x.uuid) {
+        let (old, new) = match (old, new) {
+            (Some(old), Some(new)) => (old, new),
+            (Some(old), None) => {
+                differences.push(GattDifference::CharacteristicRemoved {
+                    service,
+                    characteristic: old.uuid,
+                });
+                continue;
+            }
+            (None, Some(new)) => {
+                differences.push(GattDifference::CharacteristicAdded {
+                    service,
+                    characteristic: new.uuid,
+                });
+                continue;
+            }
+            (None, None) => unreachable!(),
+        };
+
+        let characteristic = old.uuid;
+        if old.properties != new.properties {
+            differences.push(GattDifference::PropertiesChanged {
+                service,
+                characteristic,
+                old: old.properties,
+                new: new.properties,
+            });
+        }
+        if old.value != new.value {
+            differences.push(GattDifference::CharacteristicValueChanged {
+                service,
+                characteristic,
+                old: old.value.clone(),
+                new: new.value.clone(),
+            });
+        }
+
+        for (old, new) in match_by_uuid(&old.descriptors, &new.descriptors, |x| x.uuid) {
+            match (old, new) {
+                (Some(old), Some(new)) if old.value != new.value => {
+                    differences.push(GattDifference::DescriptorValueChanged {
+                        service,
+                        characteristic,
+                        descriptor: old.uuid,
+                        old: old.value.clone(),
+                        new: new.value.clone(),
+                    })
+                }
+                (Some(_), Some(_)) => (),
+                (Some(old), None) => differences.push(GattDifference::DescriptorRemoved {
+                    service,
+                    characteristic,
+                    descriptor: old.uuid,
+                }),
+                (None, Some(new)) => differences.push(GattDifference::DescriptorAdded {
+                    service,
+                    characteristic,
+                    descriptor: new.uuid,
+                }),
+                (None, None) => unreachable!(),
+            }
+        }
+    }
+}
+
+/// Pairs up the items of `old` and `new`, matching the n-th item with a given [`Uuid`] in `old` with the n-th item
+/// with the same [`Uuid`] in `new`.
+fn match_by_uuid<'a, T>(old: &'a [T], new: &'a [T], uuid: impl Fn(&T) -> Uuid) -> Vec<(Option<&'a T>, Option<&'a T>)> {
+    let mut matched = vec![false; new.len()];
+    let mut pairs = Vec::with_capacity(old.len().max(new.len()));
+    for item in old {
+        let found = (0..new.len()).find(|&i| !matched[i] && uuid(&new[i]) == uuid(item));
+        if let Some(i) = found {
+            matched[i] = true;
+        }
+        pairs.push((Some(item), found.map(|i| &new[i])));
+    }
+    pairs.extend(
+        new.iter()
+            .zip(matched)
+            .filter(|(_, matched)| !matched)
+            .map(|(item, _)| (None, Some(item))),
+    );
+    pairs
+}
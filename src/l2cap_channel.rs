@@ -3,7 +3,7 @@ use std::task::{Context, Poll};
 
 use futures_lite::io::{AsyncRead, AsyncWrite};
 
-use crate::sys;
+use crate::{sys, Device, Result};
 
 #[allow(unused)]
 pub(crate) const PIPE_CAPACITY: usize = 0x100000; // 1Mb
@@ -60,19 +60,132 @@ macro_rules! derive_async_write {
 pub(crate) use derive_async_read;
 pub(crate) use derive_async_write;
 
+/// Options for opening an L2CAP Connection-oriented Channel (CoC) with
+/// [`Device::open_l2cap_channel_with_options`]
+#[non_exhaustive]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct L2capOptions {
+    /// Whether the channel requires an encrypted link
+    pub secure: bool,
+    /// The largest SDU to accept on the channel, or `None` to use the platform default
+    pub mtu: Option<u16>,
+    /// The flow control mode of the channel
+    pub mode: L2capMode,
+}
+
+/// The flow control mode of an L2CAP Connection-oriented Channel (CoC)
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum L2capMode {
+    /// LE Credit Based Flow Control mode
+    #[default]
+    LeCreditBased,
+    /// Enhanced Credit Based Flow Control mode, introduced in Bluetooth 5.2
+    EnhancedCreditBased,
+}
+
 /// A Bluetooth LE L2CAP Connection-oriented Channel (CoC)
-pub struct L2capChannel(pub(super) sys::l2cap_channel::L2capChannel);
+pub struct L2capChannel {
+    inner: sys::l2cap_channel::L2capChannel,
+    device: Device,
+    psm: u16,
+}
 
 impl L2capChannel {
+    pub(crate) fn new(inner: sys::l2cap_channel::L2capChannel, device: Device, psm: u16) -> Self {
+        L2capChannel { inner, device, psm }
+    }
+
+    /// The remote device at the other end of the channel.
+    #[inline]
+    pub fn device(&self) -> &Device {
+        &self.device
+    }
+
+    /// The PSM the channel was opened on.
+    #[inline]
+    pub fn psm(&self) -> u16 {
+        self.psm
+    }
+
+    /// The largest SDU which can be sent on the channel, as negotiated with the remote device.
+    ///
+    /// # Platform specific
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on iOS/MacOS and Android.
+    #[inline]
+    pub fn send_mtu(&self) -> Result<u16> {
+        self.inner.send_mtu()
+    }
+
+    /// The largest SDU which can be received on the channel.
+    ///
+    /// # Platform specific
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on iOS/MacOS and Android.
+    #[inline]
+    pub fn recv_mtu(&self) -> Result<u16> {
+        self.inner.recv_mtu()
+    }
+
+    /// Returns `true` if the link the channel runs over is encrypted.
+    ///
+    /// # Platform specific
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on iOS/MacOS and Android.
+    #[inline]
+    pub fn is_encrypted(&self) -> Result<bool> {
+        self.inner.is_encrypted()
+    }
+
     /// Splits the channel into a read half and a write half
     pub fn split(self) -> (L2capChannelReader, L2capChannelWriter) {
-        let (reader, writer) = self.0.split();
+        let (reader, writer) = self.inner.split();
         (L2capChannelReader { reader }, L2capChannelWriter { writer })
     }
 }
 
-derive_async_read!(L2capChannel, 0);
-derive_async_write!(L2capChannel, 0);
+derive_async_read!(L2capChannel, inner);
+derive_async_write!(L2capChannel, inner);
+
+impl std::fmt::Debug for L2capChannel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("L2capChannel")
+            .field("device", &self.device)
+            .field("psm", &self.psm)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A listener accepting L2CAP Connection-oriented Channels (CoC) opened by remote devices
+///
+/// Created with [`Adapter::listen_l2cap`][crate::Adapter::listen_l2cap]. The listener stops accepting channels when it
+/// is dropped. Channels which have already been accepted stay open.
+pub struct L2capListener(pub(super) sys::l2cap_channel::L2capListener);
+
+impl L2capListener {
+    /// The PSM channels are accepted on.
+    ///
+    /// When listening on a dynamically assigned PSM, this is the PSM chosen by the platform. It must be published to
+    /// remote devices, for example in a GATT characteristic, for them to open channels.
+    #[inline]
+    pub fn psm(&self) -> u16 {
+        self.0.psm()
+    }
+
+    /// Waits for a remote device to open a channel, returning the remote device and the channel.
+    pub async fn accept(&self) -> Result<(Device, L2capChannel)> {
+        let (device, channel) = self.0.accept().await?;
+        let psm = self.psm();
+        Ok((device.clone(), L2capChannel::new(channel, device, psm)))
+    }
+}
+
+impl std::fmt::Debug for L2capListener {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("L2capListener").field("psm", &self.psm()).finish()
+    }
+}
 
 /// Reader half of a L2CAP Connection-oriented Channel (CoC)
 #[derive(Debug)]
@@ -5,7 +5,7 @@
 //!
 //! The goal of Bluest is to create a *thin* abstraction on top of the platform-specific Bluetooth APIs in order to
 //! provide safe, cross-platform access to Bluetooth LE devices. The crate currently supports the GAP Central and
-//! GATT Client roles. Peripheral and Server roles are not supported.
+//! GATT Client roles, and the GAP Peripheral and GATT Server roles on Linux.
 //!
 //! [Rust]: https://www.rust-lang.org/
 //! [Bluetooth Low Energy]: https://www.bluetooth.com/specifications/specs/
@@ -44,7 +44,10 @@
 //! The primary functions provided by Bluest are:
 //!
 //! - Device discovery:
-//!   - [Scanning][Adapter::scan] for devices and receiving advertisements
+//!   - [Scanning][Adapter::scan] for devices and receiving advertisements, optionally with
+//!     [filters][Adapter::scan_with_options]
+//!   - [Parsing][advertisement] raw advertising data
+//!   - [Tracking][Adapter::track_devices] nearby devices as they appear, change and disappear
 //!   - Finding [connected devices][Adapter::connected_devices]
 //!   - [Opening][Adapter::open_device] previously found devices
 //!   - [Connecting][Adapter::connect_device] to discovered devices
@@ -56,7 +59,16 @@
 //!   - [Read][Characteristic::read], [write][Characteristic::write] (including
 //!     [write without response][Characteristic::write_without_response]), and
 //!     [notify/indicate][Characteristic::notify] operations on remote characteristics
+//!   - [Long][Characteristic::write_long] and [reliable][ReliableWriteTransaction] writes
+//!   - Choosing between [notifications and indications][Characteristic::subscribe]
+//!   - [Streaming writes][Characteristic::write_stream] paced by the platform's flow control
+//!   - [Typed values][Characteristic::read_as] with [decoders][codec] for standard characteristics
 //!   - [Read][Descriptor::read] and [write][Descriptor::write] operations on characteristic descriptors
+//!   - [Snapshotting][Device::gatt_snapshot] and comparing a device's whole GATT database
+//! - Acting as a peripheral:
+//!   - [Serving][Adapter::serve_gatt] local [services][server] with asynchronous read, write and subscribe handlers
+//!   - [Advertising][Adapter::advertise] local name, services, service and manufacturer data as a connectable or
+//!     [broadcast][advertiser] advertisement
 //!
 //! # Asynchronous runtimes
 //!
@@ -79,17 +91,29 @@
 //!|----------------------------------------------------------|:---------:|:-------:|:-----:|
 //!| [`Adapter::connect_device`][Adapter::connect_device]                     | ✅ | ✨ | ✅ |
 //!| [`Adapter::disconnect_device`][Adapter::disconnect_device]               | ✅ | ✨ | ✅ |
+//!| [`Adapter::serve_gatt`][Adapter::serve_gatt]                             | ❌ | ❌ | ✅ |
+//!| [`Adapter::advertise`][Adapter::advertise]                               | ❌ | ❌ | ✅ |
 //!| [`Device::name`][Device::name]                                           | ✅ | ✅ | ⌛️ |
 //!| [`Device::is_paired`][Device::is_paired]                                 | ❌ | ✅ | ✅ |
 //!| [`Device::pair`][Device::pair]                                           | ✨ | ✅ | ✅ |
 //!| [`Device::pair_with_agent`][Device::pair_with_agent]                     | ✨ | ✅ | ✅ |
 //!| [`Device::unpair`][Device::unpair]                                       | ❌ | ✅ | ✅ |
-//!| [`Device::rssi`][Device::rssi]                                           | ✅ | ❌ | ❌ |
+//!| [`Device::rssi`][Device::rssi]                                           | ✅ | ❌ | ✅ |
+//!| [`Device::rssi_updates`][Device::rssi_updates]                           | ✅ | ❌ | ✅ |
+//!| [`Device::request_mtu`][Device::request_mtu]                             | ✨ | ✨ | ✨ |
+//!| [`Device::mtu_updates`][Device::mtu_updates]                             | ❌ | ✅ | ❌ |
+//!| [`Device::set_connection_priority`][Device::set_connection_priority]     | ❌ | ✅ | ❌ |
+//!| [`Device::phy`][Device::phy]                                             | ❌ | ✅ | ❌ |
+//!| [`Device::set_preferred_phy`][Device::set_preferred_phy]                 | ❌ | ❌ | ❌ |
 //!| [`Service::uuid`][Service::uuid]                                         | ✅ | ✅ | ⌛️ |
 //!| [`Service::is_primary`][Service::is_primary]                             | ✅ | ❌ | ✅ |
+//!| [`Service::handle`][Service::handle]                                     | ❌ | ✅ | ✅ |
+//!| [`Service::end_handle`][Service::end_handle]                             | ❌ | ❌ | ❌ |
 //!| [`Characteristic::uuid`][Characteristic::uuid]                           | ✅ | ✅ | ⌛️ |
 //!| [`Characteristic::max_write_len`][Characteristic::max_write_len]         | ✅ | ✅ | ⌛️ |
+//!| [`Characteristic::handle`][Characteristic::handle]                       | ❌ | ✅ | ✅ |
 //!| [`Descriptor::uuid`][Descriptor::uuid]                                   | ✅ | ✅ | ⌛️ |
+//!| [`Descriptor::handle`][Descriptor::handle]                               | ❌ | ✅ | ✅ |
 //!
 //! ✅ = supported
 //! ✨ = managed automatically by the OS, this method is a no-op
@@ -107,6 +131,9 @@
 //! The `serde` feature is available to enable serializing/deserializing device
 //! identifiers.
 //!
+//! The `mock` feature adds an in-process simulated backend which can be selected at runtime by creating an
+//! [`Adapter`] with `Adapter::with_mock`. See the `mock` module for details.
+//!
 //! # Examples
 //!
 //! Examples demonstrating basic usage are available in the [examples folder].
@@ -114,18 +141,27 @@
 //! [examples folder]: https://github.com/alexmoon/bluest/tree/master/bluest/examples
 
 mod adapter;
+pub mod advertisement;
+pub mod advertiser;
 pub mod btuuid;
 mod characteristic;
+pub mod codec;
 mod descriptor;
 mod device;
+mod device_information;
 pub mod error;
+mod gatt_database;
 
 #[cfg(feature = "l2cap")]
 mod l2cap_channel;
 
+mod notification;
 pub mod pairing;
+pub mod server;
 mod service;
+mod tracker;
 mod util;
+mod write_stream;
 
 #[cfg(all(windows, feature = "l2cap"))]
 compile_error!("L2CAP support is not available on Windows");
@@ -142,31 +178,47 @@ mod corebluetooth;
 #[cfg(target_os = "windows")]
 mod windows;
 
-use std::collections::HashMap;
+#[cfg(feature = "mock")]
+mod backend;
+#[cfg(feature = "mock")]
+pub mod mock;
+
+use std::collections::{HashMap, HashSet};
 
 #[cfg(target_os = "linux")]
 pub use ::bluer::Uuid;
 pub use adapter::{Adapter, AdapterConfig};
 pub use btuuid::BluetoothUuidExt;
-pub use characteristic::Characteristic;
+pub use characteristic::{Characteristic, ReliableWriteTransaction};
 pub use descriptor::Descriptor;
 pub use device::{Device, ServicesChanged};
+pub use device_information::DeviceInformation;
 pub use error::Error;
+use futures_core::Stream;
+use futures_lite::StreamExt;
+pub use gatt_database::{GattCharacteristic, GattDatabase, GattDescriptor, GattDifference, GattService};
 #[cfg(feature = "l2cap")]
-pub use l2cap_channel::{L2capChannel, L2capChannelReader, L2capChannelWriter};
+pub use l2cap_channel::{L2capChannel, L2capChannelReader, L2capChannelWriter, L2capListener, L2capMode, L2capOptions};
+pub use notification::{LagPolicy, Notification, SubscriberOptions, SubscriptionKind};
 pub use service::Service;
 pub use sys::DeviceId;
+pub use tracker::{DeviceTracker, TrackedDevice, TrackerEvent, TrackerOptions};
 #[cfg(not(target_os = "linux"))]
 pub use uuid::Uuid;
+pub use write_stream::WriteStream;
 
 #[cfg(target_os = "android")]
-use crate::android as sys;
+use crate::android as platform;
+#[cfg(feature = "mock")]
+use crate::backend as sys;
 #[cfg(target_os = "linux")]
-use crate::bluer as sys;
+use crate::bluer as platform;
 #[cfg(any(target_os = "macos", target_os = "ios"))]
-use crate::corebluetooth as sys;
+use crate::corebluetooth as platform;
+#[cfg(not(feature = "mock"))]
+use crate::platform as sys;
 #[cfg(target_os = "windows")]
-use crate::windows as sys;
+use crate::windows as platform;
 
 /// Convenience alias for a result with [`Error`]
 pub type Result<T, E = Error> = core::result::Result<T, E>;
@@ -189,6 +241,40 @@ pub enum ConnectionEvent {
     Connected,
 }
 
+/// Connection parameter presets for [`Device::set_connection_priority`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ConnectionPriority {
+    /// Connection parameters balancing throughput, latency and power consumption
+    #[default]
+    Balanced,
+    /// A short connection interval for high throughput and low latency
+    High,
+    /// A long connection interval and peripheral latency to reduce power consumption
+    LowPower,
+}
+
+/// Bluetooth LE physical layers (PHYs)
+#[non_exhaustive]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Phy {
+    /// LE 1M PHY
+    #[default]
+    Le1M,
+    /// LE 2M PHY
+    Le2M,
+    /// LE Coded PHY
+    LeCoded,
+}
+
+/// The PHYs used by a connection, returned by [`Device::phy`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ConnectionPhy {
+    /// The PHY used to transmit to the device
+    pub tx: Phy,
+    /// The PHY used to receive from the device
+    pub rx: Phy,
+}
+
 /// Represents a device discovered during a scan operation
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AdvertisingDevice {
@@ -200,13 +286,135 @@ pub struct AdvertisingDevice {
     pub rssi: Option<i16>,
 }
 
+/// Options for [`Adapter::scan_with_options`]
+///
+/// Filters which the platform cannot apply natively are applied to the received advertisements by Bluest.
+#[non_exhaustive]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScanOptions {
+    /// Only report advertisements including at least one GATT service with a UUID in `services`. Reports all
+    /// advertisements if empty.
+    pub services: Vec<Uuid>,
+    /// Report every advertisement received from a device, rather than only the first one.
+    pub allow_duplicates: bool,
+    /// Whether to request scan responses from advertising devices.
+    ///
+    /// # Platform specific
+    ///
+    /// Only supported on Windows. Other platforms always perform an active scan.
+    pub mode: ScanMode,
+    /// Only report advertisements received with a signal strength of at least this many dBm.
+    pub rssi_threshold: Option<i16>,
+    /// Only report advertisements from devices which advertise their transmit power and whose path loss (the
+    /// difference between the transmit power and the received signal strength) is at most this many dB.
+    pub pathloss_threshold: Option<u16>,
+    /// Only report advertisements with a local name starting with this prefix.
+    pub name_prefix: Option<String>,
+    /// Only report advertisements including manufacturer specific data from this company identifier.
+    pub company_id: Option<u16>,
+    /// The transport to scan on.
+    pub transport: ScanTransport,
+}
+
+/// Scanning modes for [`ScanOptions::mode`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ScanMode {
+    /// Request scan responses from advertising devices
+    #[default]
+    Active,
+    /// Only listen for advertising packets
+    Passive,
+}
+
+/// Transports for [`ScanOptions::transport`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ScanTransport {
+    /// Scan on every transport supported by the adapter
+    #[default]
+    Auto,
+    /// Scan for Bluetooth LE devices only
+    Le,
+    /// Scan for Bluetooth Classic (BR/EDR) devices only. Only supported on Linux.
+    BrEdr,
+}
+
+impl ScanOptions {
+    /// Returns `true` if `device` satisfies the filters in these options.
+    pub(crate) fn matches(&self, device: &AdvertisingDevice) -> bool {
+        let adv_data = &device.adv_data;
+
+        if !self.services.is_empty() && !adv_data.services.iter().any(|x| self.services.contains(x)) {
+            return false;
+        }
+
+        if let Some(threshold) = self.rssi_threshold {
+            match device.rssi {
+                Some(rssi) if rssi >= threshold => {}
+                _ => return false,
+            }
+        }
+
+        if let Some(threshold) = self.pathloss_threshold {
+            match (adv_data.tx_power_level, device.rssi) {
+                (Some(tx_power_level), Some(rssi))
+                    if i32::from(tx_power_level) - i32::from(rssi) <= i32::from(threshold) => {}
+                _ => return false,
+            }
+        }
+
+        if let Some(prefix) = &self.name_prefix {
+            if !adv_data.local_name.as_ref().is_some_and(|x| x.starts_with(prefix)) {
+                return false;
+            }
+        }
+
+        if let Some(company_id) = self.company_id {
+            if !adv_data
+                .manufacturer_data_list
+                .iter()
+                .any(|x| x.company_id == company_id)
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Applies the filters in these options, including duplicate suppression, to a stream of advertisements.
+    pub(crate) fn filter<'a, S>(&self, stream: S) -> impl Stream<Item = AdvertisingDevice> + Send + Unpin + 'a
+    where
+        S: Stream<Item = AdvertisingDevice> + Send + Unpin + 'a,
+    {
+        let options = self.clone();
+        let mut seen = HashSet::new();
+        stream.filter(move |x| options.matches(x) && (options.allow_duplicates || seen.insert(x.device.id())))
+    }
+}
+
 /// Data included in a Bluetooth advertisement or scan reponse.
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// Platforms only report a subset of the AD types, so fields other than `local_name`, `manufacturer_data`,
+/// `manufacturer_data_list`, `services`, `service_data`, `tx_power_level` and `is_connectable` are only populated where
+/// the platform exposes them (see [`raw`][AdvertisementData::raw]). [`AdvertisementData::from_raw`] decodes every field
+/// from a raw payload.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct AdvertisementData {
     /// The (possibly shortened) local name of the device (CSS §A.1.2)
     pub local_name: Option<String>,
+    /// Set to true if `local_name` is known to be a shortened name (CSS §A.1.2)
+    pub is_local_name_shortened: bool,
     /// Manufacturer specific data (CSS §A.1.4)
+    ///
+    /// This is the first entry of `manufacturer_data_list`, kept for compatibility.
     pub manufacturer_data: Option<ManufacturerData>,
+    /// All manufacturer specific data entries (CSS §A.1.4)
+    ///
+    /// # Platform specific
+    ///
+    /// On MacOS/iOS and Android at most one entry is reported. On Linux the entries are ordered by company identifier
+    /// and only the latest entry for each company identifier is reported.
+    pub manufacturer_data_list: Vec<ManufacturerData>,
     /// Advertised GATT service UUIDs (CSS §A.1.1)
     pub services: Vec<Uuid>,
     /// Service associated data (CSS §A.1.11)
@@ -215,6 +423,27 @@ pub struct AdvertisementData {
     pub tx_power_level: Option<i16>,
     /// Set to true for connectable advertising packets
     pub is_connectable: bool,
+    /// Flags (CSS §A.1.3)
+    pub flags: Option<advertisement::AdFlags>,
+    /// External appearance of the device (CSS §A.1.12)
+    pub appearance: Option<u16>,
+    /// Service UUIDs the device solicits from a central (CSS §A.1.10)
+    pub solicited_services: Vec<Uuid>,
+    /// Preferred connection interval range of the device (CSS §A.1.9)
+    pub connection_interval_range: Option<advertisement::ConnectionIntervalRange>,
+    /// URI (CSS §A.1.18)
+    pub uri: Option<String>,
+    /// Supported LE roles (CSS §A.1.17)
+    pub le_role: Option<advertisement::LeRole>,
+    /// Advertising interval (CSS §A.1.15)
+    pub advertising_interval: Option<std::time::Duration>,
+    /// The raw advertising payload this data was decoded from
+    ///
+    /// # Platform specific
+    ///
+    /// Only available on Windows and for data created with [`AdvertisementData::from_raw`]. On Windows the payload is
+    /// reassembled from the AD structures reported by the OS.
+    pub raw: Option<Vec<u8>>,
 }
 
 /// Manufacturer specific data included in Bluetooth advertisements. See the Bluetooth Core Specification Supplement
@@ -232,6 +461,7 @@ pub struct ManufacturerData {
 #[allow(missing_docs)]
 #[non_exhaustive]
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CharacteristicProperties {
     pub broadcast: bool,
     pub read: bool,
@@ -0,0 +1,1031 @@
+//! An in-memory simulated Bluetooth backend.
+//!
+//! The mock backend allows applications to exercise their Bluetooth code without any Bluetooth hardware. A
+//! [`MockAdapter`] describes a set of virtual peripherals, each with its own advertisement data and GATT database. An
+//! [`Adapter`][crate::Adapter] created with [`Adapter::with_mock`][crate::Adapter::with_mock] will then discover, connect to and interact with
+//! those peripherals through the regular Bluest API.
+//!
+//! ```rust
+//!# use bluest::btuuid::{characteristics, services};
+//!# use bluest::mock::{MockAdapter, MockCharacteristic, MockPeripheral, MockService};
+//!# use bluest::{Adapter, CharacteristicProperties};
+//!# use futures_lite::StreamExt;
+//!# #[tokio::main]
+//!# async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!let mock = MockAdapter::new();
+//!
+//!let mut peripheral = MockPeripheral::new("Battery");
+//!peripheral.adv_data.services.push(services::BATTERY);
+//!let mut battery = MockService::new(services::BATTERY);
+//!let mut properties = CharacteristicProperties::default();
+//!properties.read = true;
+//!properties.notify = true;
+//!battery
+//!    .characteristics
+//!    .push(MockCharacteristic::new(characteristics::BATTERY_LEVEL, properties, vec![87]));
+//!peripheral.services.push(battery);
+//!let handle = mock.add_peripheral(peripheral)?;
+//!
+//!let adapter = Adapter::with_mock(mock);
+//!let device = adapter.scan(&[services::BATTERY]).await?.next().await.unwrap().device;
+//!assert_eq!(device.id(), handle.id());
+//!
+//!adapter.connect_device(&device).await?;
+//!let service = &device.discover_services_with_uuid(services::BATTERY).await?[0];
+//!let level = &service.discover_characteristics().await?[0];
+//!assert_eq!(level.read().await?, vec![87]);
+//!
+//!let mut updates = level.notify().await?;
+//!handle.notify(characteristics::BATTERY_LEVEL, &[86])?;
+//!assert_eq!(updates.next().await.unwrap()?, vec![86]);
+//!#    Ok(())
+//!# }
+//! ```
+//!
+//! Local GATT services published with [`Adapter::serve_gatt`][crate::Adapter::serve_gatt] can be accessed with the
+//! [`MockCentral`] returned by [`MockAdapter::central`], which plays the part of a remote central. It also observes
+//! the advertisements sent with [`Adapter::advertise`][crate::Adapter::advertise].
+
+pub(crate) mod adapter;
+pub(crate) mod advertiser;
+pub(crate) mod characteristic;
+pub(crate) mod descriptor;
+pub(crate) mod device;
+pub(crate) mod server;
+pub(crate) mod service;
+
+use std::ops::RangeInclusive;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use futures_channel::mpsc;
+use futures_core::Stream;
+
+use crate::advertiser::Advertisement;
+use crate::error::{AttError, ErrorKind};
+use crate::server::{LocalCharacteristic, LocalDescriptor, LocalService, ReadRequest, WriteRequest};
+use crate::util::sleep;
+use crate::{
+    backend, AdapterEvent, AdvertisementData, AdvertisingDevice, CharacteristicProperties, ConnectionEvent,
+    ConnectionPhy, ConnectionPriority, DeviceId, Error, Notification, Result, ServicesChanged, SubscriptionKind, Uuid,
+};
+
+/// The default ATT MTU used by simulated peripherals.
+pub const DEFAULT_MTU: u16 = 23;
+
+/// The maximum length of an attribute value (Bluetooth Core Specification, Vol 3, Part F, §3.2.9).
+const MAX_ATTRIBUTE_LEN: usize = 512;
+
+/// A simulated Bluetooth adapter.
+///
+/// Cloning a `MockAdapter` yields another handle to the same simulated environment, so peripherals may be added or
+/// modified after the adapter has been passed to [`Adapter::with_mock`][crate::Adapter::with_mock].
+#[derive(Debug, Clone, Default)]
+pub struct MockAdapter {
+    pub(crate) shared: Arc<Shared>,
+}
+
+/// A virtual peripheral to be added to a [`MockAdapter`].
+#[derive(Debug, Clone)]
+pub struct MockPeripheral {
+    /// The name reported by [`Device::name`][crate::Device::name].
+    pub name: Option<String>,
+    /// The data included in this peripheral's advertisements.
+    pub adv_data: AdvertisementData,
+    /// The signal strength reported with advertisements and by [`Device::rssi`][crate::Device::rssi].
+    pub rssi: Option<i16>,
+    /// The GATT services provided by this peripheral.
+    pub services: Vec<MockService>,
+    /// The largest ATT MTU supported by this peripheral. It is negotiated when connecting.
+    pub mtu: u16,
+}
+
+/// A GATT service provided by a [`MockPeripheral`].
+///
+/// Attribute handles are assigned in declaration order starting from 1. Each service occupies one handle for its
+/// declaration, one for each included service, two for each characteristic (declaration and value) and one for each
+/// descriptor. Included services are assigned handles after the service which includes them.
+#[derive(Debug, Clone)]
+pub struct MockService {
+    /// The [`Uuid`] identifying the type of this service.
+    pub uuid: Uuid,
+    /// Whether this is a primary service.
+    pub primary: bool,
+    /// The characteristics of this service.
+    pub characteristics: Vec<MockCharacteristic>,
+    /// The services included by this service.
+    pub included_services: Vec<MockService>,
+}
+
+/// A GATT characteristic provided by a [`MockService`].
+#[derive(Debug, Clone)]
+pub struct MockCharacteristic {
+    /// The [`Uuid`] identifying the type of this characteristic.
+    pub uuid: Uuid,
+    /// The operations permitted on this characteristic.
+    pub properties: CharacteristicProperties,
+    /// The initial value of this characteristic.
+    pub value: Vec<u8>,
+    /// The descriptors of this characteristic.
+    pub descriptors: Vec<MockDescriptor>,
+}
+
+/// A GATT descriptor provided by a [`MockCharacteristic`].
+#[derive(Debug, Clone)]
+pub struct MockDescriptor {
+    /// The [`Uuid`] identifying the type of this descriptor.
+    pub uuid: Uuid,
+    /// The initial value of this descriptor.
+    pub value: Vec<u8>,
+}
+
+/// A handle to a peripheral which has been added to a [`MockAdapter`].
+///
+/// The handle plays the part of the remote device: it can update advertisements, change characteristic values, send
+/// notifications and terminate the connection.
+#[derive(Debug, Clone)]
+pub struct MockPeripheralHandle {
+    shared: Arc<Shared>,
+    peripheral: Arc<Peripheral>,
+}
+
+/// A simulated remote central accessing the local GATT services published with
+/// [`Adapter::serve_gatt`][crate::Adapter::serve_gatt].
+///
+/// Characteristics and descriptors are identified by their [`Uuid`]. If several published characteristics share a
+/// [`Uuid`], the first one is used. The requests are passed to the handlers of the local characteristics and
+/// descriptors, and protocol errors returned by the handlers are reported as [`ErrorKind::Protocol`].
+#[derive(Debug, Clone)]
+pub struct MockCentral {
+    shared: Arc<Shared>,
+}
+
+impl MockAdapter {
+    /// Creates an empty simulated environment with an available adapter.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns a simulated remote central which accesses the local GATT services published with
+    /// [`Adapter::serve_gatt`][crate::Adapter::serve_gatt].
+    pub fn central(&self) -> MockCentral {
+        MockCentral {
+            shared: self.shared.clone(),
+        }
+    }
+
+    /// Adds a virtual peripheral to the simulated environment.
+    ///
+    /// The peripheral starts advertising immediately. Returns an error with a kind of
+    /// [`InvalidParameter`][ErrorKind::InvalidParameter] if its GATT database needs more attribute handles than are
+    /// available.
+    pub fn add_peripheral(&self, mut peripheral: MockPeripheral) -> Result<MockPeripheralHandle> {
+        let services = build_database(std::mem::take(&mut peripheral.services))?;
+        let peripheral = {
+            let mut state = self.shared.lock();
+            state.next_id += 1;
+            let peripheral = Arc::new(Peripheral::new(state.next_id, peripheral, services));
+            state.peripherals.push(peripheral.clone());
+            peripheral
+        };
+        self.shared.advertise(&peripheral);
+
+        Ok(MockPeripheralHandle {
+            shared: self.shared.clone(),
+            peripheral,
+        })
+    }
+
+    /// Simulates the adapter being enabled or disabled.
+    ///
+    /// Disabling the adapter disconnects all peripherals and ends any active scans.
+    pub fn set_available(&self, available: bool) {
+        let peripherals = {
+            let mut state = self.shared.lock();
+            if state.available == available {
+                return;
+            }
+            state.available = available;
+            if !available {
+                state.scanners.clear();
+            }
+            state.peripherals.clone()
+        };
+
+        if !available {
+            for peripheral in peripherals {
+                peripheral.disconnect();
+            }
+        }
+
+        let event = if available {
+            AdapterEvent::Available
+        } else {
+            AdapterEvent::Unavailable
+        };
+        self.shared
+            .lock()
+            .events
+            .retain(|sender| sender.unbounded_send(event).is_ok());
+    }
+}
+
+impl MockPeripheral {
+    /// Creates a connectable peripheral advertising `name` with no services.
+    pub fn new(name: impl Into<String>) -> Self {
+        let name = name.into();
+        let mut peripheral = MockPeripheral::default();
+        peripheral.adv_data.local_name = Some(name.clone());
+        peripheral.name = Some(name);
+        peripheral
+    }
+}
+
+impl Default for MockPeripheral {
+    fn default() -> Self {
+        MockPeripheral {
+            name: None,
+            adv_data: AdvertisementData {
+                is_connectable: true,
+                ..Default::default()
+            },
+            rssi: Some(-60),
+            services: Vec::new(),
+            mtu: DEFAULT_MTU,
+        }
+    }
+}
+
+impl MockService {
+    /// Creates a primary service with no characteristics.
+    pub fn new(uuid: Uuid) -> Self {
+        MockService {
+            uuid,
+            primary: true,
+            characteristics: Vec::new(),
+            included_services: Vec::new(),
+        }
+    }
+}
+
+impl MockCharacteristic {
+    /// Creates a characteristic with no descriptors.
+    pub fn new(uuid: Uuid, properties: CharacteristicProperties, value: Vec<u8>) -> Self {
+        MockCharacteristic {
+            uuid,
+            properties,
+            value,
+            descriptors: Vec::new(),
+        }
+    }
+}
+
+impl MockDescriptor {
+    /// Creates a descriptor.
+    pub fn new(uuid: Uuid, value: Vec<u8>) -> Self {
+        MockDescriptor { uuid, value }
+    }
+}
+
+impl MockCentral {
+    /// The advertisements currently sent with [`Adapter::advertise`][crate::Adapter::advertise], in the order they
+    /// were started.
+    pub fn advertisements(&self) -> Vec<Advertisement> {
+        let state = self.shared.lock();
+        state.advertisements.iter().map(|(_, x)| x.clone()).collect()
+    }
+
+    /// The [`Uuid`]s of the published services.
+    pub fn services(&self) -> Vec<Uuid> {
+        let state = self.shared.lock();
+        state.servers.iter().flat_map(|(_, x)| x).map(|x| x.uuid).collect()
+    }
+
+    /// Reads the value of the characteristic identified by `uuid`.
+    pub async fn read(&self, uuid: Uuid) -> Result<Vec<u8>> {
+        let (properties, handler) = self
+            .shared
+            .lock()
+            .local_characteristic(uuid, |x| (x.properties, x.read.clone()))?;
+        match handler {
+            Some(handler) if properties.read => handler(read_request()).await.map_err(protocol_error),
+            _ => Err(ErrorKind::Protocol(AttError::READ_NOT_PERMITTED).into()),
+        }
+    }
+
+    /// Writes `value` to the characteristic identified by `uuid` and waits for the response.
+    pub async fn write(&self, uuid: Uuid, value: &[u8]) -> Result<()> {
+        let (properties, handler) = self
+            .shared
+            .lock()
+            .local_characteristic(uuid, |x| (x.properties, x.write.clone()))?;
+        check_value_len(value)?;
+        match handler {
+            Some(handler) if properties.write => handler(write_request(value, true)).await.map_err(protocol_error),
+            _ => Err(ErrorKind::Protocol(AttError::WRITE_NOT_PERMITTED).into()),
+        }
+    }
+
+    /// Writes `value` to the characteristic identified by `uuid` without requesting a response.
+    ///
+    /// As with a real write without response, errors returned by the handler are not reported.
+    pub async fn write_without_response(&self, uuid: Uuid, value: &[u8]) -> Result<()> {
+        let (properties, handler) = self
+            .shared
+            .lock()
+            .local_characteristic(uuid, |x| (x.properties, x.write.clone()))?;
+        check_value_len(value)?;
+        match handler {
+            Some(handler) if properties.write_without_response => {
+                let _ = handler(write_request(value, false)).await;
+                Ok(())
+            }
+            _ => Err(ErrorKind::Protocol(AttError::WRITE_NOT_PERMITTED).into()),
+        }
+    }
+
+    /// Subscribes to notifications or indications of the characteristic identified by `uuid`.
+    ///
+    /// The subscribe handler of the characteristic is driven by the returned stream, which ends when the handler
+    /// returns. Dropping the stream unsubscribes and drops the handler.
+    pub async fn subscribe(
+        &self,
+        uuid: Uuid,
+        kind: SubscriptionKind,
+    ) -> Result<impl Stream<Item = Vec<u8>> + Send + Unpin> {
+        let (properties, handler) = self
+            .shared
+            .lock()
+            .local_characteristic(uuid, |x| (x.properties, x.subscribe.clone()))?;
+        kind.check_supported(&properties)?;
+        Ok(server::Subscription::new(kind, handler))
+    }
+
+    /// Reads the value of the descriptor identified by `descriptor` of the characteristic identified by
+    /// `characteristic`.
+    pub async fn read_descriptor(&self, characteristic: Uuid, descriptor: Uuid) -> Result<Vec<u8>> {
+        let handler = self
+            .shared
+            .lock()
+            .local_descriptor(characteristic, descriptor, |x| x.read.clone())?;
+        match handler {
+            Some(handler) => handler(read_request()).await.map_err(protocol_error),
+            None => Err(ErrorKind::Protocol(AttError::READ_NOT_PERMITTED).into()),
+        }
+    }
+
+    /// Writes `value` to the descriptor identified by `descriptor` of the characteristic identified by
+    /// `characteristic`.
+    pub async fn write_descriptor(&self, characteristic: Uuid, descriptor: Uuid, value: &[u8]) -> Result<()> {
+        let handler = self
+            .shared
+            .lock()
+            .local_descriptor(characteristic, descriptor, |x| x.write.clone())?;
+        check_value_len(value)?;
+        match handler {
+            Some(handler) => handler(write_request(value, true)).await.map_err(protocol_error),
+            None => Err(ErrorKind::Protocol(AttError::WRITE_NOT_PERMITTED).into()),
+        }
+    }
+}
+
+fn protocol_error(err: AttError) -> Error {
+    ErrorKind::Protocol(err).into()
+}
+
+fn read_request() -> ReadRequest {
+    ReadRequest {
+        offset: 0,
+        mtu: DEFAULT_MTU,
+    }
+}
+
+fn write_request(value: &[u8], with_response: bool) -> WriteRequest {
+    WriteRequest {
+        value: value.to_vec(),
+        offset: 0,
+        mtu: DEFAULT_MTU,
+        with_response,
+    }
+}
+
+impl MockPeripheralHandle {
+    /// The identifier of the [`Device`][crate::Device] representing this peripheral.
+    pub fn id(&self) -> DeviceId {
+        self.peripheral.device_id()
+    }
+
+    /// Whether a central is currently connected to this peripheral.
+    pub fn is_connected(&self) -> bool {
+        self.peripheral.lock().connected
+    }
+
+    /// Simulates the peripheral terminating its connection.
+    pub fn disconnect(&self) {
+        self.peripheral.disconnect();
+    }
+
+    /// Starts or stops advertising. Any active scan receives an advertisement when advertising starts.
+    pub fn set_advertising(&self, advertising: bool) {
+        self.peripheral.lock().advertising = advertising;
+        self.shared.advertise(&self.peripheral);
+    }
+
+    /// Replaces the advertisement data of this peripheral and sends a new advertisement to any active scan.
+    pub fn set_adv_data(&self, adv_data: AdvertisementData) {
+        self.peripheral.lock().adv_data = adv_data;
+        self.shared.advertise(&self.peripheral);
+    }
+
+    /// Changes the signal strength of this peripheral and sends a new advertisement to any active scan.
+    pub fn set_rssi(&self, rssi: Option<i16>) {
+        {
+            let mut state = self.peripheral.lock();
+            state.rssi = rssi;
+            if let Some(rssi) = rssi {
+                state.rssi_updates.retain(|sender| sender.unbounded_send(rssi).is_ok());
+            }
+        }
+        self.shared.advertise(&self.peripheral);
+    }
+
+    /// Simulates the peripheral exchanging the ATT MTU of the current connection.
+    ///
+    /// The MTU is limited to the MTU supported by the peripheral.
+    pub fn exchange_mtu(&self, mtu: u16) -> Result<()> {
+        let mut state = self.peripheral.lock_connected()?;
+        let mtu = mtu.clamp(DEFAULT_MTU, state.max_mtu.max(DEFAULT_MTU));
+        state.set_mtu(mtu);
+        Ok(())
+    }
+
+    /// The connection priority last requested with [`Device::set_connection_priority`][crate::Device::set_connection_priority]
+    /// for the current connection.
+    pub fn connection_priority(&self) -> ConnectionPriority {
+        self.peripheral.lock().connection_priority
+    }
+
+    /// Replaces the GATT database of this peripheral.
+    ///
+    /// Connected centrals receive a service changed indication covering the entire handle range. Subscriptions to
+    /// characteristics of the previous database are ended. Returns an error with a kind of
+    /// [`InvalidParameter`][ErrorKind::InvalidParameter], leaving the database unchanged, if `services` need more
+    /// attribute handles than are available.
+    pub fn set_services(&self, services: Vec<MockService>) -> Result<()> {
+        let services = build_database(services)?;
+        let mut state = self.peripheral.lock();
+        let old = std::mem::replace(&mut state.services, services);
+        for subscriber in old
+            .into_iter()
+            .flat_map(|x| x.characteristics)
+            .flat_map(|x| x.subscribers)
+        {
+            let _ = subscriber.sender.unbounded_send(Err(ErrorKind::ServiceChanged.into()));
+        }
+        state.send_services_changed(0x0001..=0xffff);
+        Ok(())
+    }
+
+    /// Sends a service changed indication covering the attribute handles in `range` to connected centrals.
+    ///
+    /// The GATT database is not modified.
+    pub fn send_services_changed(&self, range: RangeInclusive<u16>) {
+        self.peripheral.lock().send_services_changed(range);
+    }
+
+    /// Terminates the connection when the central attempts its next GATT operation after `operations` more operations
+    /// have completed. `None` cancels a pending disconnection.
+    ///
+    /// GATT operations are service, characteristic and descriptor discovery, reads, writes and enabling
+    /// notifications.
+    pub fn disconnect_after(&self, operations: Option<usize>) {
+        self.peripheral.lock().faults.disconnect_after = operations;
+    }
+
+    /// Delays the response to every GATT operation by `delay`.
+    pub fn set_response_delay(&self, delay: Option<Duration>) {
+        self.peripheral.lock().faults.response_delay = delay;
+    }
+
+    /// Silently discards notifications sent with [`notify`][Self::notify] while `drop` is true.
+    pub fn set_drop_notifications(&self, drop: bool) {
+        self.peripheral.lock().faults.drop_notifications = drop;
+    }
+
+    /// Fails reads, writes and subscriptions on the first characteristic identified by `uuid` with a protocol error.
+    ///
+    /// `None` restores normal operation.
+    pub fn set_characteristic_error(&self, uuid: Uuid, error: Option<AttError>) -> Result<()> {
+        let mut state = self.peripheral.lock();
+        state.characteristic_by_uuid_mut(uuid)?.error = error;
+        Ok(())
+    }
+
+    /// The current value of the first characteristic identified by `uuid`.
+    pub fn characteristic_value(&self, uuid: Uuid) -> Option<Vec<u8>> {
+        let state = self.peripheral.lock();
+        state.characteristic_by_uuid(uuid).map(|x| x.value.clone())
+    }
+
+    /// Changes the value of the first characteristic identified by `uuid` without notifying subscribers.
+    pub fn set_characteristic_value(&self, uuid: Uuid, value: &[u8]) -> Result<()> {
+        let mut state = self.peripheral.lock();
+        let characteristic = state.characteristic_by_uuid_mut(uuid)?;
+        characteristic.value = value.to_vec();
+        Ok(())
+    }
+
+    /// Changes the value of the first characteristic identified by `uuid` and sends it to all subscribers.
+    pub fn notify(&self, uuid: Uuid, value: &[u8]) -> Result<()> {
+        let mut state = self.peripheral.lock();
+        if !state.connected {
+            return Err(ErrorKind::NotConnected.into());
+        }
+        let drop_notifications = state.faults.drop_notifications;
+        let characteristic = state.characteristic_by_uuid_mut(uuid)?;
+        characteristic.value = value.to_vec();
+        if !drop_notifications {
+            let received_at = Instant::now();
+            characteristic.subscribers.retain(|subscriber| {
+                let notification = Notification {
+                    value: value.to_vec(),
+                    is_indication: subscriber.kind == SubscriptionKind::Indicate,
+                    received_at,
+                };
+                subscriber.sender.unbounded_send(Ok(notification)).is_ok()
+            });
+        }
+        Ok(())
+    }
+}
+
+/// State shared between a [`MockAdapter`] and every object created from it.
+#[derive(Debug)]
+pub(crate) struct Shared {
+    state: Mutex<SharedState>,
+}
+
+#[derive(Debug)]
+pub(crate) struct SharedState {
+    pub available: bool,
+    pub next_id: u64,
+    pub peripherals: Vec<Arc<Peripheral>>,
+    pub events: Vec<mpsc::UnboundedSender<AdapterEvent>>,
+    pub scanners: Vec<mpsc::UnboundedSender<AdvertisingDevice>>,
+    pub servers: Vec<(u64, Vec<LocalService>)>,
+    pub advertisements: Vec<(u64, Advertisement)>,
+}
+
+impl Default for Shared {
+    fn default() -> Self {
+        Shared {
+            state: Mutex::new(SharedState {
+                available: true,
+                next_id: 0,
+                peripherals: Vec::new(),
+                events: Vec::new(),
+                scanners: Vec::new(),
+                servers: Vec::new(),
+                advertisements: Vec::new(),
+            }),
+        }
+    }
+}
+
+impl Shared {
+    pub fn lock(&self) -> MutexGuard<'_, SharedState> {
+        self.state.lock().unwrap()
+    }
+
+    pub fn check_available(&self) -> Result<()> {
+        if self.lock().available {
+            Ok(())
+        } else {
+            Err(ErrorKind::AdapterUnavailable.into())
+        }
+    }
+
+    /// The current advertisement of `peripheral`, if it is advertising.
+    pub fn advertisement(self: &Arc<Self>, peripheral: &Arc<Peripheral>) -> Option<AdvertisingDevice> {
+        let state = peripheral.lock();
+        state.advertising.then(|| AdvertisingDevice {
+            device: device::DeviceImpl::new(self.clone(), peripheral.clone()).into_device(),
+            adv_data: state.adv_data.clone(),
+            rssi: state.rssi,
+        })
+    }
+
+    /// Sends the current advertisement of `peripheral` to all active scans, if it is advertising.
+    pub fn advertise(self: &Arc<Self>, peripheral: &Arc<Peripheral>) {
+        if let Some(advertisement) = self.advertisement(peripheral) {
+            self.lock()
+                .scanners
+                .retain(|sender| sender.unbounded_send(advertisement.clone()).is_ok());
+        }
+    }
+}
+
+/// A virtual peripheral and its GATT database.
+#[derive(Debug)]
+pub(crate) struct Peripheral {
+    pub id: u64,
+    state: Mutex<PeripheralState>,
+}
+
+#[derive(Debug)]
+pub(crate) struct PeripheralState {
+    pub name: Option<String>,
+    pub adv_data: AdvertisementData,
+    pub rssi: Option<i16>,
+    pub max_mtu: u16,
+    pub mtu: u16,
+    pub connection_priority: ConnectionPriority,
+    pub phy: ConnectionPhy,
+    pub advertising: bool,
+    pub connected: bool,
+    pub paired: bool,
+    pub services: Vec<ServiceEntry>,
+    pub connection_events: Vec<mpsc::UnboundedSender<ConnectionEvent>>,
+    pub services_changed: Vec<mpsc::UnboundedSender<Result<ServicesChanged>>>,
+    pub rssi_updates: Vec<mpsc::UnboundedSender<i16>>,
+    pub mtu_updates: Vec<mpsc::UnboundedSender<u16>>,
+    pub faults: Faults,
+}
+
+/// Faults injected with [`MockPeripheralHandle`].
+#[derive(Debug, Default)]
+pub(crate) struct Faults {
+    pub disconnect_after: Option<usize>,
+    pub response_delay: Option<Duration>,
+    pub drop_notifications: bool,
+}
+
+#[derive(Debug)]
+pub(crate) struct ServiceEntry {
+    pub handle: u16,
+    pub end_handle: u16,
+    pub uuid: Uuid,
+    pub primary: bool,
+    pub included_services: Vec<u16>,
+    pub characteristics: Vec<CharacteristicEntry>,
+}
+
+#[derive(Debug)]
+pub(crate) struct CharacteristicEntry {
+    pub handle: u16,
+    pub uuid: Uuid,
+    pub properties: CharacteristicProperties,
+    pub value: Vec<u8>,
+    pub descriptors: Vec<DescriptorEntry>,
+    pub error: Option<AttError>,
+    pub subscribers: Vec<Subscriber>,
+}
+
+#[derive(Debug)]
+pub(crate) struct Subscriber {
+    pub kind: SubscriptionKind,
+    pub sender: mpsc::UnboundedSender<Result<Notification>>,
+}
+
+#[derive(Debug)]
+pub(crate) struct DescriptorEntry {
+    pub handle: u16,
+    pub uuid: Uuid,
+    pub value: Vec<u8>,
+}
+
+impl Peripheral {
+    fn new(id: u64, peripheral: MockPeripheral, services: Vec<ServiceEntry>) -> Self {
+        Peripheral {
+            id,
+            state: Mutex::new(PeripheralState {
+                name: peripheral.name,
+                adv_data: peripheral.adv_data,
+                rssi: peripheral.rssi,
+                max_mtu: peripheral.mtu,
+                mtu: peripheral.mtu,
+                connection_priority: ConnectionPriority::default(),
+                phy: ConnectionPhy::default(),
+                advertising: true,
+                connected: false,
+                paired: false,
+                services,
+                connection_events: Vec::new(),
+                services_changed: Vec::new(),
+                rssi_updates: Vec::new(),
+                mtu_updates: Vec::new(),
+                faults: Faults::default(),
+            }),
+        }
+    }
+
+    pub fn device_id(&self) -> DeviceId {
+        // A static random address, whose two most significant bits are set
+        let id = self.id.to_be_bytes();
+        DeviceId::mock([0xc0 | (id[2] & 0x3f), id[3], id[4], id[5], id[6], id[7]])
+    }
+
+    pub fn lock(&self) -> MutexGuard<'_, PeripheralState> {
+        self.state.lock().unwrap()
+    }
+
+    /// Locks the peripheral state, failing if no central is connected.
+    pub fn lock_connected(&self) -> Result<MutexGuard<'_, PeripheralState>> {
+        let state = self.lock();
+        if state.connected {
+            Ok(state)
+        } else {
+            Err(ErrorKind::NotConnected.into())
+        }
+    }
+
+    /// Applies the injected faults to a GATT operation. Must be called before the operation accesses the database.
+    pub async fn operation(&self) -> Result<()> {
+        let delay = self.lock().faults.response_delay;
+        if let Some(delay) = delay {
+            sleep(delay).await;
+        }
+
+        let mut state = self.lock_connected()?;
+        match state.faults.disconnect_after {
+            Some(0) => {
+                state.faults.disconnect_after = None;
+                drop(state);
+                self.disconnect();
+                Err(ErrorKind::NotConnected.into())
+            }
+            Some(ref mut remaining) => {
+                *remaining -= 1;
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    pub fn connect(&self) -> Result<()> {
+        let mut state = self.lock();
+        if state.connected {
+            return Ok(());
+        }
+        if !state.adv_data.is_connectable {
+            return Err(Error::new(
+                ErrorKind::ConnectionFailed,
+                None,
+                "the peripheral is not connectable",
+            ));
+        }
+        state.connected = true;
+        state.mtu = state.max_mtu;
+        state.connection_priority = ConnectionPriority::default();
+        state.phy = ConnectionPhy::default();
+        state
+            .connection_events
+            .retain(|sender| sender.unbounded_send(ConnectionEvent::Connected).is_ok());
+        Ok(())
+    }
+
+    pub fn disconnect(&self) {
+        let mut state = self.lock();
+        if !state.connected {
+            return;
+        }
+        state.connected = false;
+        for service in &mut state.services {
+            for characteristic in &mut service.characteristics {
+                for subscriber in characteristic.subscribers.drain(..) {
+                    let _ = subscriber.sender.unbounded_send(Err(ErrorKind::NotConnected.into()));
+                }
+            }
+        }
+        for sender in state.services_changed.drain(..) {
+            let _ = sender.unbounded_send(Err(ErrorKind::NotConnected.into()));
+        }
+        state.mtu_updates.clear();
+        state
+            .connection_events
+            .retain(|sender| sender.unbounded_send(ConnectionEvent::Disconnected).is_ok());
+    }
+}
+
+impl SharedState {
+    /// Applies `f` to the first published characteristic identified by `uuid`.
+    fn local_characteristic<T>(&self, uuid: Uuid, f: impl FnOnce(&LocalCharacteristic) -> T) -> Result<T> {
+        self.servers
+            .iter()
+            .flat_map(|(_, x)| x)
+            .flat_map(|x| &x.characteristics)
+            .find(|x| x.uuid == uuid)
+            .map(f)
+            .ok_or_else(|| Error::new(ErrorKind::NotFound, None, format!("characteristic {uuid} not found")))
+    }
+
+    /// Applies `f` to the descriptor identified by `uuid` of the first published characteristic identified by
+    /// `characteristic`.
+    fn local_descriptor<T>(
+        &self,
+        characteristic: Uuid,
+        uuid: Uuid,
+        f: impl FnOnce(&LocalDescriptor) -> T,
+    ) -> Result<T> {
+        self.local_characteristic(characteristic, |x| x.descriptors.iter().find(|x| x.uuid == uuid).map(f))?
+            .ok_or_else(|| Error::new(ErrorKind::NotFound, None, format!("descriptor {uuid} not found")))
+    }
+}
+
+impl PeripheralState {
+    pub fn set_mtu(&mut self, mtu: u16) {
+        if self.mtu != mtu {
+            self.mtu = mtu;
+            self.mtu_updates.retain(|sender| sender.unbounded_send(mtu).is_ok());
+        }
+    }
+
+    pub fn send_services_changed(&mut self, range: RangeInclusive<u16>) {
+        let services_changed = ServicesChanged(backend::device::ServicesChangedImpl::Mock(
+            device::ServicesChangedImpl(range),
+        ));
+        self.services_changed
+            .retain(|sender| sender.unbounded_send(Ok(services_changed.clone())).is_ok());
+    }
+
+    pub fn service(&self, handle: u16) -> Result<&ServiceEntry> {
+        self.services
+            .iter()
+            .find(|x| x.handle == handle)
+            .ok_or_else(|| ErrorKind::ServiceChanged.into())
+    }
+
+    pub fn characteristic(&self, handle: u16) -> Result<&CharacteristicEntry> {
+        self.services
+            .iter()
+            .flat_map(|x| &x.characteristics)
+            .find(|x| x.handle == handle)
+            .ok_or_else(|| ErrorKind::ServiceChanged.into())
+    }
+
+    pub fn characteristic_mut(&mut self, handle: u16) -> Result<&mut CharacteristicEntry> {
+        self.services
+            .iter_mut()
+            .flat_map(|x| &mut x.characteristics)
+            .find(|x| x.handle == handle)
+            .ok_or_else(|| ErrorKind::ServiceChanged.into())
+    }
+
+    pub fn descriptor(&self, handle: u16) -> Result<&DescriptorEntry> {
+        self.services
+            .iter()
+            .flat_map(|x| &x.characteristics)
+            .flat_map(|x| &x.descriptors)
+            .find(|x| x.handle == handle)
+            .ok_or_else(|| ErrorKind::ServiceChanged.into())
+    }
+
+    pub fn descriptor_mut(&mut self, handle: u16) -> Result<&mut DescriptorEntry> {
+        self.services
+            .iter_mut()
+            .flat_map(|x| &mut x.characteristics)
+            .flat_map(|x| &mut x.descriptors)
+            .find(|x| x.handle == handle)
+            .ok_or_else(|| ErrorKind::ServiceChanged.into())
+    }
+
+    fn characteristic_by_uuid(&self, uuid: Uuid) -> Option<&CharacteristicEntry> {
+        self.services
+            .iter()
+            .flat_map(|x| &x.characteristics)
+            .find(|x| x.uuid == uuid)
+    }
+
+    fn characteristic_by_uuid_mut(&mut self, uuid: Uuid) -> Result<&mut CharacteristicEntry> {
+        self.services
+            .iter_mut()
+            .flat_map(|x| &mut x.characteristics)
+            .find(|x| x.uuid == uuid)
+            .ok_or_else(|| Error::new(ErrorKind::NotFound, None, format!("characteristic {uuid} not found")))
+    }
+}
+
+impl CharacteristicEntry {
+    /// Fails with the protocol error injected with [`MockPeripheralHandle::set_characteristic_error`], if any.
+    pub fn check_error(&self) -> Result<()> {
+        match self.error {
+            Some(err) => Err(ErrorKind::Protocol(err).into()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Checks that `value` may be stored in an attribute.
+pub(crate) fn check_value_len(value: &[u8]) -> Result<()> {
+    if value.len() > MAX_ATTRIBUTE_LEN {
+        Err(ErrorKind::Protocol(AttError::INVALID_ATTRIBUTE_VALUE_LENGTH).into())
+    } else {
+        Ok(())
+    }
+}
+
+/// The value resulting from writing `value` at `offset` of `current` with prepared writes.
+pub(crate) fn prepare_value(current: &[u8], offset: u16, value: &[u8]) -> Result<Vec<u8>> {
+    let offset = usize::from(offset);
+    if offset > current.len() {
+        return Err(ErrorKind::Protocol(AttError::INVALID_OFFSET).into());
+    }
+    let mut new = current[..offset].to_vec();
+    new.extend_from_slice(value);
+    check_value_len(&new)?;
+    Ok(new)
+}
+
+/// The part of `value` returned by a read starting at `offset`, as with read blob requests.
+pub(crate) fn read_value_from(value: &[u8], offset: u16, mtu: u16) -> Result<Vec<u8>> {
+    let offset = usize::from(offset);
+    if offset > value.len() {
+        return Err(ErrorKind::Protocol(AttError::INVALID_OFFSET).into());
+    }
+    // A value which fits in a single read response cannot be read at an offset.
+    if offset > 0 && value.len() < usize::from(mtu) {
+        return Err(ErrorKind::Protocol(AttError::ATTRIBUTE_NOT_LONG).into());
+    }
+    Ok(value[offset..].to_vec())
+}
+
+/// Assigns attribute handles to `services` in declaration order, starting from handle 1.
+fn build_database(services: Vec<MockService>) -> Result<Vec<ServiceEntry>> {
+    let mut entries = Vec::new();
+    let mut next_handle = 1;
+    for service in services {
+        add_service(&mut entries, &mut next_handle, service)?;
+    }
+    Ok(entries)
+}
+
+/// Appends `service` and its included services to `services`, assigning attribute handles in declaration order.
+///
+/// `next_handle` is wider than a handle so that the last handle, 0xffff, can be assigned.
+fn add_service(services: &mut Vec<ServiceEntry>, next_handle: &mut u32, service: MockService) -> Result<u16> {
+    let mut alloc = || {
+        let handle = u16::try_from(*next_handle).map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidParameter,
+                None,
+                "the GATT database has more attributes than there are attribute handles",
+            )
+        })?;
+        *next_handle += 1;
+        Ok::<_, Error>(handle)
+    };
+
+    let handle = alloc()?;
+    for _ in &service.included_services {
+        alloc()?;
+    }
+
+    let characteristics = service
+        .characteristics
+        .into_iter()
+        .map(|characteristic| {
+            // The declaration is immediately followed by the value attribute
+            let handle = alloc()?;
+            alloc()?;
+            Ok(CharacteristicEntry {
+                handle,
+                uuid: characteristic.uuid,
+                properties: characteristic.properties,
+                value: characteristic.value,
+                descriptors: characteristic
+                    .descriptors
+                    .into_iter()
+                    .map(|descriptor| {
+                        Ok(DescriptorEntry {
+                            handle: alloc()?,
+                            uuid: descriptor.uuid,
+                            value: descriptor.value,
+                        })
+                    })
+                    .collect::<Result<_>>()?,
+                error: None,
+                subscribers: Vec::new(),
+            })
+        })
+        .collect::<Result<_>>()?;
+
+    let index = services.len();
+    services.push(ServiceEntry {
+        handle,
+        // The last assigned handle, which fits in a handle as it was assigned
+        end_handle: (*next_handle - 1) as u16,
+        uuid: service.uuid,
+        primary: service.primary,
+        included_services: Vec::new(),
+        characteristics,
+    });
+
+    let included_services = service
+        .included_services
+        .into_iter()
+        .map(|included| add_service(services, next_handle, included))
+        .collect::<Result<_>>()?;
+    services[index].included_services = included_services;
+
+    Ok(handle)
+}
@@ -0,0 +1,204 @@
+use std::sync::Arc;
+
+use futures_channel::mpsc;
+use futures_core::Stream;
+use futures_lite::{stream, StreamExt};
+
+use super::advertiser::AdvertisingHandleImpl;
+use super::device::DeviceImpl;
+use super::server::GattServerImpl;
+use super::{MockAdapter, Shared};
+use crate::advertiser::{Advertisement, AdvertisingHandle};
+use crate::error::ErrorKind;
+use crate::server::{Application, GattServer};
+use crate::{
+    AdapterEvent, AdvertisingDevice, ConnectionEvent, Device, DeviceId, Error, Result, ScanOptions, ScanTransport, Uuid,
+};
+
+/// A simulated Bluetooth adapter interface.
+#[derive(Debug, Clone)]
+pub struct AdapterImpl {
+    shared: Arc<Shared>,
+}
+
+impl PartialEq for AdapterImpl {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.shared, &other.shared)
+    }
+}
+
+impl Eq for AdapterImpl {}
+
+impl std::hash::Hash for AdapterImpl {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.shared).hash(state);
+    }
+}
+
+impl AdapterImpl {
+    pub fn new(adapter: MockAdapter) -> Self {
+        AdapterImpl { shared: adapter.shared }
+    }
+
+    fn devices(&self) -> Vec<DeviceImpl> {
+        let peripherals = self.shared.lock().peripherals.clone();
+        peripherals
+            .into_iter()
+            .map(|peripheral| DeviceImpl::new(self.shared.clone(), peripheral))
+            .collect()
+    }
+
+    /// A stream of [`AdapterEvent`] which allows the application to identify when the adapter is enabled or disabled.
+    pub async fn events(&self) -> Result<impl Stream<Item = Result<AdapterEvent>> + Send + Unpin + '_> {
+        let (sender, receiver) = mpsc::unbounded();
+        self.shared.lock().events.push(sender);
+        Ok(receiver.map(Ok))
+    }
+
+    /// Check if the adapter is available
+    pub async fn is_available(&self) -> Result<bool> {
+        Ok(self.shared.lock().available)
+    }
+
+    /// Asynchronously blocks until the adapter is available
+    pub async fn wait_available(&self) -> Result<()> {
+        let events = self.events().await?;
+        if !self.is_available().await? {
+            events
+                .skip_while(|x| x.is_ok() && !matches!(x, Ok(AdapterEvent::Available)))
+                .next()
+                .await
+                .ok_or_else(|| Error::new(ErrorKind::Internal, None, "adapter event stream closed unexpectedly"))??;
+        }
+        Ok(())
+    }
+
+    /// Attempts to create the device identified by `id`
+    pub async fn open_device(&self, id: &DeviceId) -> Result<Device> {
+        self.devices()
+            .into_iter()
+            .find(|x| x.peripheral.device_id() == *id)
+            .map(DeviceImpl::into_device)
+            .ok_or_else(|| ErrorKind::NotFound.into())
+    }
+
+    /// Finds all connected Bluetooth LE devices
+    pub async fn connected_devices(&self) -> Result<Vec<Device>> {
+        self.shared.check_available()?;
+        Ok(self
+            .devices()
+            .into_iter()
+            .filter(|x| x.peripheral.lock().connected)
+            .map(DeviceImpl::into_device)
+            .collect())
+    }
+
+    /// Finds all connected devices providing any service in `services`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `services` is empty.
+    pub async fn connected_devices_with_services(&self, services: &[Uuid]) -> Result<Vec<Device>> {
+        assert!(!services.is_empty());
+
+        self.shared.check_available()?;
+        Ok(self
+            .devices()
+            .into_iter()
+            .filter(|x| {
+                let state = x.peripheral.lock();
+                state.connected && state.services.iter().any(|x| services.contains(&x.uuid))
+            })
+            .map(DeviceImpl::into_device)
+            .collect())
+    }
+
+    /// Starts scanning for Bluetooth advertising packets.
+    pub async fn scan<'a>(
+        &'a self,
+        services: &'a [Uuid],
+    ) -> Result<impl Stream<Item = AdvertisingDevice> + Send + Unpin + 'a> {
+        self.shared.check_available()?;
+
+        let (sender, receiver) = mpsc::unbounded();
+        {
+            let mut state = self.shared.lock();
+            for peripheral in &state.peripherals {
+                if let Some(advertisement) = self.shared.advertisement(peripheral) {
+                    let _ = sender.unbounded_send(advertisement);
+                }
+            }
+            state.scanners.push(sender);
+        }
+
+        Ok(receiver.filter(move |x: &AdvertisingDevice| {
+            services.is_empty() || x.adv_data.services.iter().any(|y| services.contains(y))
+        }))
+    }
+
+    /// Starts scanning for Bluetooth advertising packets using the provided options.
+    pub async fn scan_with_options(
+        &self,
+        options: ScanOptions,
+    ) -> Result<impl Stream<Item = AdvertisingDevice> + Send + Unpin + '_> {
+        if options.transport == ScanTransport::BrEdr {
+            return Err(ErrorKind::NotSupported.into());
+        }
+        Ok(options.filter(self.scan(&[]).await?))
+    }
+
+    /// Finds Bluetooth devices providing any service in `services`.
+    pub async fn discover_devices<'a>(
+        &'a self,
+        services: &'a [Uuid],
+    ) -> Result<impl Stream<Item = Result<Device>> + Send + Unpin + 'a> {
+        let connected = if services.is_empty() {
+            self.connected_devices().await?
+        } else {
+            self.connected_devices_with_services(services).await?
+        };
+        let scan = self.scan(services).await?;
+        Ok(stream::iter(connected).chain(scan.map(|x| x.device)).map(Ok))
+    }
+
+    /// Connects to the [`Device`]
+    pub async fn connect_device(&self, device: &DeviceImpl) -> Result<()> {
+        self.shared.check_available()?;
+        device.peripheral.connect()
+    }
+
+    /// Disconnects from the [`Device`]
+    pub async fn disconnect_device(&self, device: &DeviceImpl) -> Result<()> {
+        device.peripheral.disconnect();
+        Ok(())
+    }
+
+    /// Monitors a device for connection/disconnection events.
+    pub async fn device_connection_events<'a>(
+        &'a self,
+        device: &'a DeviceImpl,
+    ) -> Result<impl Stream<Item = ConnectionEvent> + Send + Unpin + 'a> {
+        let (sender, receiver) = mpsc::unbounded();
+        device.peripheral.lock().connection_events.push(sender);
+        Ok(receiver)
+    }
+
+    /// Publishes the local GATT services described by `application`.
+    pub async fn serve_gatt(&self, application: Application) -> Result<GattServer> {
+        GattServerImpl::serve(self.shared.clone(), application)
+    }
+
+    /// Starts sending `advertisement`.
+    pub async fn advertise(&self, advertisement: Advertisement) -> Result<AdvertisingHandle> {
+        AdvertisingHandleImpl::advertise(self.shared.clone(), advertisement)
+    }
+
+    #[cfg(feature = "l2cap")]
+    pub async fn listen_l2cap(
+        &self,
+        _psm: Option<u16>,
+        _secure: bool,
+    ) -> Result<crate::platform::l2cap_channel::L2capListener> {
+        Err(ErrorKind::NotSupported.into())
+    }
+}
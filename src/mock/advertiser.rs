@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use super::Shared;
+use crate::advertiser::{Advertisement, AdvertisingHandle};
+use crate::Result;
+
+/// An advertisement sent in a simulated environment
+#[derive(Debug)]
+pub struct AdvertisingHandleImpl {
+    shared: Arc<Shared>,
+    id: u64,
+}
+
+impl AdvertisingHandleImpl {
+    pub(super) fn advertise(shared: Arc<Shared>, advertisement: Advertisement) -> Result<AdvertisingHandle> {
+        shared.check_available()?;
+
+        let id = {
+            let mut state = shared.lock();
+            state.next_id += 1;
+            let id = state.next_id;
+            state.advertisements.push((id, advertisement));
+            id
+        };
+        Ok(AdvertisingHandle::from_mock(AdvertisingHandleImpl { shared, id }))
+    }
+}
+
+impl Drop for AdvertisingHandleImpl {
+    fn drop(&mut self) {
+        self.shared.lock().advertisements.retain(|(id, _)| *id != self.id);
+    }
+}
@@ -0,0 +1,240 @@
+use std::sync::Arc;
+
+use futures_channel::mpsc;
+use futures_core::Stream;
+
+use super::descriptor::DescriptorImpl;
+use super::service::ServiceImpl;
+use super::{check_value_len, prepare_value, read_value_from, CharacteristicEntry, Subscriber};
+use crate::error::{AttError, ErrorKind};
+use crate::{
+    backend, Characteristic, CharacteristicProperties, Descriptor, DeviceId, Error, Notification, Result,
+    SubscriptionKind, Uuid,
+};
+
+/// A simulated GATT characteristic
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CharacteristicImpl {
+    pub(super) service: ServiceImpl,
+    pub(super) handle: u16,
+    uuid: Uuid,
+}
+
+impl CharacteristicImpl {
+    pub(super) fn new(service: ServiceImpl, entry: &CharacteristicEntry) -> Self {
+        CharacteristicImpl {
+            service,
+            handle: entry.handle,
+            uuid: entry.uuid,
+        }
+    }
+
+    pub(super) fn into_characteristic(self) -> Characteristic {
+        Characteristic(backend::characteristic::CharacteristicImpl::Mock(self))
+    }
+
+    /// The [`Uuid`] identifying the type of this GATT characteristic
+    pub fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    /// The [`Uuid`] identifying the type of this GATT characteristic
+    pub async fn uuid_async(&self) -> Result<Uuid> {
+        Ok(self.uuid)
+    }
+
+    /// The attribute handle of this GATT characteristic.
+    pub fn handle(&self) -> Result<u16> {
+        Ok(self.handle)
+    }
+
+    /// The identifier of the device this GATT characteristic belongs to.
+    pub fn device_id(&self) -> Result<DeviceId> {
+        Ok(self.service.device.peripheral.device_id())
+    }
+
+    /// The properties of this this GATT characteristic.
+    pub async fn properties(&self) -> Result<CharacteristicProperties> {
+        let state = self.service.device.peripheral.lock();
+        Ok(state.characteristic(self.handle)?.properties)
+    }
+
+    /// The cached value of this characteristic
+    pub async fn value(&self) -> Result<Vec<u8>> {
+        let state = self.service.device.peripheral.lock();
+        Ok(state.characteristic(self.handle)?.value.clone())
+    }
+
+    /// Read the value of this characteristic from the device
+    pub async fn read(&self) -> Result<Vec<u8>> {
+        self.service.device.peripheral.operation().await?;
+        let state = self.service.device.peripheral.lock_connected()?;
+        let characteristic = state.characteristic(self.handle)?;
+        if !characteristic.properties.read {
+            return Err(ErrorKind::Protocol(AttError::READ_NOT_PERMITTED).into());
+        }
+        characteristic.check_error()?;
+        Ok(characteristic.value.clone())
+    }
+
+    /// Read the value of this characteristic from the device, starting at `offset`.
+    pub async fn read_with_offset(&self, offset: u16) -> Result<Vec<u8>> {
+        self.service.device.peripheral.operation().await?;
+        let state = self.service.device.peripheral.lock_connected()?;
+        let characteristic = state.characteristic(self.handle)?;
+        if !characteristic.properties.read {
+            return Err(ErrorKind::Protocol(AttError::READ_NOT_PERMITTED).into());
+        }
+        characteristic.check_error()?;
+        read_value_from(&characteristic.value, offset, state.mtu)
+    }
+
+    /// Write the value of this descriptor on the device to `value` and request the device return a response indicating
+    /// a successful write.
+    pub async fn write(&self, value: &[u8]) -> Result<()> {
+        self.service.device.peripheral.operation().await?;
+        let mut state = self.service.device.peripheral.lock_connected()?;
+        let characteristic = state.characteristic_mut(self.handle)?;
+        if !characteristic.properties.write {
+            return Err(ErrorKind::Protocol(AttError::WRITE_NOT_PERMITTED).into());
+        }
+        characteristic.check_error()?;
+        check_value_len(value)?;
+        characteristic.value = value.to_vec();
+        Ok(())
+    }
+
+    /// Write the value of this descriptor on the device to `value` without requesting a response.
+    pub async fn write_without_response(&self, value: &[u8]) -> Result<()> {
+        let max_write_len = self.max_write_len()?;
+        self.service.device.peripheral.operation().await?;
+        let mut state = self.service.device.peripheral.lock_connected()?;
+        let characteristic = state.characteristic_mut(self.handle)?;
+        if !characteristic.properties.write_without_response {
+            return Err(ErrorKind::Protocol(AttError::WRITE_NOT_PERMITTED).into());
+        }
+        if value.len() > max_write_len {
+            return Err(Error::new(
+                ErrorKind::InvalidParameter,
+                None,
+                format!("value exceeds the maximum write length of {max_write_len} bytes"),
+            ));
+        }
+        characteristic.value = value.to_vec();
+        Ok(())
+    }
+
+    /// Write `value` to the value of this characteristic starting at `offset`, as with prepared writes.
+    pub async fn write_long(&self, offset: u16, value: &[u8]) -> Result<()> {
+        self.service.device.peripheral.operation().await?;
+        let mut state = self.service.device.peripheral.lock_connected()?;
+        let characteristic = state.characteristic_mut(self.handle)?;
+        if !characteristic.properties.write {
+            return Err(ErrorKind::Protocol(AttError::WRITE_NOT_PERMITTED).into());
+        }
+        characteristic.check_error()?;
+        characteristic.value = prepare_value(&characteristic.value, offset, value)?;
+        Ok(())
+    }
+
+    /// Writes every value in `writes`, or none of them if any write fails.
+    pub async fn execute_reliable_write(writes: &[(&Self, &[u8])]) -> Result<()> {
+        let peripheral = match writes.first() {
+            Some((characteristic, _)) => &characteristic.service.device.peripheral,
+            None => return Ok(()),
+        };
+        if writes
+            .iter()
+            .any(|(characteristic, _)| !Arc::ptr_eq(&characteristic.service.device.peripheral, peripheral))
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidParameter,
+                None,
+                "every characteristic of a reliable write must belong to the same device",
+            ));
+        }
+
+        // One prepare write request for each value and an execute write request
+        for _ in 0..=writes.len() {
+            peripheral.operation().await?;
+        }
+
+        let mut state = peripheral.lock_connected()?;
+        let mut values = Vec::with_capacity(writes.len());
+        for (characteristic, value) in writes {
+            let entry = state.characteristic(characteristic.handle)?;
+            if !entry.properties.write {
+                return Err(ErrorKind::Protocol(AttError::WRITE_NOT_PERMITTED).into());
+            }
+            if !entry.properties.reliable_write {
+                return Err(ErrorKind::Protocol(AttError::REQUEST_NOT_SUPPORTED).into());
+            }
+            entry.check_error()?;
+            check_value_len(value)?;
+            values.push((characteristic.handle, value.to_vec()));
+        }
+        for (handle, value) in values {
+            state.characteristic_mut(handle)?.value = value;
+        }
+        Ok(())
+    }
+
+    /// Get the maximum amount of data that can be written in a single packet for this characteristic.
+    pub fn max_write_len(&self) -> Result<usize> {
+        let mtu = self.service.device.peripheral.lock().mtu;
+        // GATT characteristic writes have 3 bytes of overhead (opcode + handle id)
+        Ok(usize::from(mtu) - 3)
+    }
+
+    /// Get the maximum amount of data that can be written in a single packet for this characteristic.
+    pub async fn max_write_len_async(&self) -> Result<usize> {
+        self.max_write_len()
+    }
+
+    /// Subscribes to notifications or indications of value changes for this GATT characteristic.
+    pub async fn subscribe(
+        &self,
+        kind: SubscriptionKind,
+    ) -> Result<impl Stream<Item = Result<Notification>> + Send + Unpin + '_> {
+        self.service.device.peripheral.operation().await?;
+        let mut state = self.service.device.peripheral.lock_connected()?;
+        let characteristic = state.characteristic_mut(self.handle)?;
+        kind.check_supported(&characteristic.properties)?;
+        characteristic.check_error()?;
+        let (sender, receiver) = mpsc::unbounded();
+        characteristic.subscribers.push(Subscriber { kind, sender });
+        Ok(receiver)
+    }
+
+    /// Disables notifications and indications for this GATT characteristic.
+    pub async fn unsubscribe(&self) -> Result<()> {
+        self.service.device.peripheral.operation().await?;
+        let mut state = self.service.device.peripheral.lock_connected()?;
+        state.characteristic_mut(self.handle)?.subscribers.clear();
+        Ok(())
+    }
+
+    /// Is the device currently sending notifications for this characteristic?
+    pub async fn is_notifying(&self) -> Result<bool> {
+        let state = self.service.device.peripheral.lock();
+        let characteristic = state.characteristic(self.handle)?;
+        Ok(characteristic.subscribers.iter().any(|x| !x.sender.is_closed()))
+    }
+
+    /// Discover the descriptors associated with this characteristic.
+    pub async fn discover_descriptors(&self) -> Result<Vec<Descriptor>> {
+        self.descriptors().await
+    }
+
+    /// Get previously discovered descriptors.
+    pub async fn descriptors(&self) -> Result<Vec<Descriptor>> {
+        self.service.device.peripheral.operation().await?;
+        let state = self.service.device.peripheral.lock_connected()?;
+        Ok(state
+            .characteristic(self.handle)?
+            .descriptors
+            .iter()
+            .map(|x| DescriptorImpl::new(self.clone(), x).into_descriptor())
+            .collect())
+    }
+}
@@ -0,0 +1,69 @@
+use super::characteristic::CharacteristicImpl;
+use super::{check_value_len, read_value_from, DescriptorEntry};
+use crate::{backend, Descriptor, Result, Uuid};
+
+/// A simulated GATT descriptor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DescriptorImpl {
+    pub(super) characteristic: CharacteristicImpl,
+    pub(super) handle: u16,
+    uuid: Uuid,
+}
+
+impl DescriptorImpl {
+    pub(super) fn new(characteristic: CharacteristicImpl, entry: &DescriptorEntry) -> Self {
+        DescriptorImpl {
+            characteristic,
+            handle: entry.handle,
+            uuid: entry.uuid,
+        }
+    }
+
+    pub(super) fn into_descriptor(self) -> Descriptor {
+        Descriptor(backend::descriptor::DescriptorImpl::Mock(self))
+    }
+
+    /// The [`Uuid`] identifying the type of this GATT descriptor
+    pub fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    /// The [`Uuid`] identifying the type of this GATT descriptor
+    pub async fn uuid_async(&self) -> Result<Uuid> {
+        Ok(self.uuid)
+    }
+
+    /// The attribute handle of this GATT descriptor.
+    pub fn handle(&self) -> Result<u16> {
+        Ok(self.handle)
+    }
+
+    /// The cached value of this descriptor
+    pub async fn value(&self) -> Result<Vec<u8>> {
+        let state = self.characteristic.service.device.peripheral.lock();
+        Ok(state.descriptor(self.handle)?.value.clone())
+    }
+
+    /// Read the value of this descriptor from the device
+    pub async fn read(&self) -> Result<Vec<u8>> {
+        self.characteristic.service.device.peripheral.operation().await?;
+        let state = self.characteristic.service.device.peripheral.lock_connected()?;
+        Ok(state.descriptor(self.handle)?.value.clone())
+    }
+
+    /// Read the value of this descriptor from the device, starting at `offset`.
+    pub async fn read_with_offset(&self, offset: u16) -> Result<Vec<u8>> {
+        self.characteristic.service.device.peripheral.operation().await?;
+        let state = self.characteristic.service.device.peripheral.lock_connected()?;
+        read_value_from(&state.descriptor(self.handle)?.value, offset, state.mtu)
+    }
+
+    /// Write the value of this descriptor on the device to `value`
+    pub async fn write(&self, value: &[u8]) -> Result<()> {
+        check_value_len(value)?;
+        self.characteristic.service.device.peripheral.operation().await?;
+        let mut state = self.characteristic.service.device.peripheral.lock_connected()?;
+        state.descriptor_mut(self.handle)?.value = value.to_vec();
+        Ok(())
+    }
+}
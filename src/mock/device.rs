@@ -0,0 +1,202 @@
+use std::sync::Arc;
+
+use futures_channel::mpsc;
+use futures_core::Stream;
+
+use super::service::ServiceImpl;
+use super::{Peripheral, Shared, DEFAULT_MTU};
+use crate::error::ErrorKind;
+use crate::pairing::PairingAgent;
+use crate::{
+    backend, ConnectionPhy, ConnectionPriority, Device, DeviceId, Phy, Result, Service, ServicesChanged, Uuid,
+};
+
+/// A simulated Bluetooth LE device
+#[derive(Debug, Clone)]
+pub struct DeviceImpl {
+    pub(super) shared: Arc<Shared>,
+    pub(super) peripheral: Arc<Peripheral>,
+}
+
+impl PartialEq for DeviceImpl {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.shared, &other.shared) && self.peripheral.id == other.peripheral.id
+    }
+}
+
+impl Eq for DeviceImpl {}
+
+impl std::hash::Hash for DeviceImpl {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.shared).hash(state);
+        self.peripheral.id.hash(state);
+    }
+}
+
+impl std::fmt::Display for DeviceImpl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name().as_deref().unwrap_or("(Unknown)"))
+    }
+}
+
+impl DeviceImpl {
+    pub(super) fn new(shared: Arc<Shared>, peripheral: Arc<Peripheral>) -> Self {
+        DeviceImpl { shared, peripheral }
+    }
+
+    pub(super) fn into_device(self) -> Device {
+        Device(backend::device::DeviceImpl::Mock(self))
+    }
+
+    /// This device's unique identifier
+    pub fn id(&self) -> DeviceId {
+        self.peripheral.device_id()
+    }
+
+    /// The local name for this device, if available
+    pub fn name(&self) -> Result<String> {
+        self.peripheral
+            .lock()
+            .name
+            .clone()
+            .ok_or_else(|| ErrorKind::NotFound.into())
+    }
+
+    /// The local name for this device, if available
+    pub async fn name_async(&self) -> Result<String> {
+        self.name()
+    }
+
+    /// The connection status for this device
+    pub async fn is_connected(&self) -> bool {
+        self.peripheral.lock().connected
+    }
+
+    /// The pairing status for this device
+    pub async fn is_paired(&self) -> Result<bool> {
+        Ok(self.peripheral.lock().paired)
+    }
+
+    /// Attempt to pair this device using the system default pairing UI
+    pub async fn pair(&self) -> Result<()> {
+        self.peripheral.lock_connected()?.paired = true;
+        Ok(())
+    }
+
+    /// Attempt to pair this device using the provided agent
+    pub async fn pair_with_agent<T: PairingAgent + 'static>(&self, _agent: &T) -> Result<()> {
+        self.pair().await
+    }
+
+    /// Disconnect and unpair this device from the system
+    pub async fn unpair(&self) -> Result<()> {
+        self.peripheral.disconnect();
+        self.peripheral.lock().paired = false;
+        Ok(())
+    }
+
+    /// Discover the primary services of this device.
+    pub async fn discover_services(&self) -> Result<Vec<Service>> {
+        self.services().await
+    }
+
+    /// Discover the primary service(s) of this device with the given [`Uuid`].
+    pub async fn discover_services_with_uuid(&self, uuid: Uuid) -> Result<Vec<Service>> {
+        Ok(self
+            .services()
+            .await?
+            .into_iter()
+            .filter(|x| x.uuid() == uuid)
+            .collect())
+    }
+
+    /// Get previously discovered services.
+    pub async fn services(&self) -> Result<Vec<Service>> {
+        self.peripheral.operation().await?;
+        let state = self.peripheral.lock_connected()?;
+        Ok(state
+            .services
+            .iter()
+            .filter(|x| x.primary)
+            .map(|x| ServiceImpl::new(self.clone(), x).into_service())
+            .collect())
+    }
+
+    /// Monitors the device for services changed events.
+    pub async fn service_changed_indications(
+        &self,
+    ) -> Result<impl Stream<Item = Result<ServicesChanged>> + Send + Unpin + '_> {
+        let (sender, receiver) = mpsc::unbounded();
+        self.peripheral.lock_connected()?.services_changed.push(sender);
+        Ok(receiver)
+    }
+
+    /// Get the current signal strength from the device in dBm.
+    pub async fn rssi(&self) -> Result<i16> {
+        self.peripheral.lock().rssi.ok_or_else(|| ErrorKind::NotReady.into())
+    }
+
+    /// Monitors the signal strength of the device.
+    pub async fn rssi_updates(&self) -> Result<impl Stream<Item = i16> + Send + Unpin + '_> {
+        let (sender, receiver) = mpsc::unbounded();
+        self.peripheral.lock().rssi_updates.push(sender);
+        Ok(receiver)
+    }
+
+    /// The ATT MTU of the connection.
+    pub async fn mtu(&self) -> Result<u16> {
+        Ok(self.peripheral.lock_connected()?.mtu)
+    }
+
+    /// Exchanges the ATT MTU, limited to the MTU supported by the peripheral.
+    pub async fn request_mtu(&self, mtu: u16) -> Result<u16> {
+        let mut state = self.peripheral.lock_connected()?;
+        let mtu = mtu.clamp(DEFAULT_MTU, state.max_mtu.max(DEFAULT_MTU));
+        state.set_mtu(mtu);
+        Ok(mtu)
+    }
+
+    /// Monitors changes to the ATT MTU of the connection.
+    pub async fn mtu_updates(&self) -> Result<impl Stream<Item = u16> + Send + Unpin + '_> {
+        let (sender, receiver) = mpsc::unbounded();
+        self.peripheral.lock_connected()?.mtu_updates.push(sender);
+        Ok(receiver)
+    }
+
+    /// Records the requested connection priority, see [`MockPeripheralHandle::connection_priority`].
+    pub async fn set_connection_priority(&self, priority: ConnectionPriority) -> Result<()> {
+        self.peripheral.lock_connected()?.connection_priority = priority;
+        Ok(())
+    }
+
+    /// The PHYs of the connection.
+    pub async fn phy(&self) -> Result<ConnectionPhy> {
+        Ok(self.peripheral.lock_connected()?.phy)
+    }
+
+    /// Switches the connection to the preferred PHYs, which simulated peripherals always support.
+    pub async fn set_preferred_phy(&self, tx: Phy, rx: Phy) -> Result<()> {
+        self.peripheral.lock_connected()?.phy = ConnectionPhy { tx, rx };
+        Ok(())
+    }
+
+    #[cfg(feature = "l2cap")]
+    pub async fn open_l2cap_channel(
+        &self,
+        _psm: u16,
+        _options: &crate::L2capOptions,
+    ) -> Result<crate::platform::l2cap_channel::L2capChannel> {
+        Err(ErrorKind::NotSupported.into())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ServicesChangedImpl(pub(super) std::ops::RangeInclusive<u16>);
+
+impl ServicesChangedImpl {
+    pub fn was_invalidated(&self, service: &ServiceImpl) -> bool {
+        // A service which no longer exists only reports its start handle
+        let end_handle = service.end_handle().unwrap_or(service.handle);
+        *self.0.start() <= end_handle && service.handle <= *self.0.end()
+    }
+}
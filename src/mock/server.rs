@@ -0,0 +1,106 @@
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures_channel::{mpsc, oneshot};
+use futures_core::Stream;
+use futures_lite::FutureExt;
+
+use super::Shared;
+use crate::error::ErrorKind;
+use crate::server::{Application, BoxFuture, GattServer, Notifier, SubscribeFn};
+use crate::{backend, Error, Result, SubscriptionKind};
+
+/// A subscription of a simulated central to a local characteristic
+#[derive(Debug)]
+pub struct NotifierImpl {
+    kind: SubscriptionKind,
+    sender: mpsc::UnboundedSender<Vec<u8>>,
+    stop: oneshot::Sender<()>,
+}
+
+impl NotifierImpl {
+    /// Whether values are sent as notifications or indications.
+    pub fn kind(&self) -> SubscriptionKind {
+        self.kind
+    }
+
+    /// Returns `true` if the simulated central has unsubscribed.
+    pub fn is_stopped(&self) -> bool {
+        self.stop.is_canceled()
+    }
+
+    /// Waits until the simulated central unsubscribes.
+    pub async fn stopped(&mut self) {
+        self.stop.cancellation().await
+    }
+
+    /// Sends `value` to the simulated central.
+    pub async fn notify(&mut self, value: &[u8]) -> Result<()> {
+        if self.is_stopped() || self.sender.unbounded_send(value.to_vec()).is_err() {
+            return Err(Error::new(ErrorKind::Other, None, "the subscriber has unsubscribed"));
+        }
+        Ok(())
+    }
+}
+
+/// Local GATT services published to a simulated environment
+#[derive(Debug)]
+pub struct GattServerImpl {
+    shared: Arc<Shared>,
+    id: u64,
+}
+
+impl GattServerImpl {
+    pub(super) fn serve(shared: Arc<Shared>, application: Application) -> Result<GattServer> {
+        shared.check_available()?;
+        let id = {
+            let mut state = shared.lock();
+            state.next_id += 1;
+            let id = state.next_id;
+            state.servers.push((id, application.services));
+            id
+        };
+        Ok(GattServer::from_mock(GattServerImpl { shared, id }))
+    }
+}
+
+impl Drop for GattServerImpl {
+    fn drop(&mut self) {
+        self.shared.lock().servers.retain(|(id, _)| *id != self.id);
+    }
+}
+
+/// A stream of the values sent by a [`Notifier`], driving the subscribe handler which sends them.
+pub(super) struct Subscription {
+    handler: Option<BoxFuture<()>>,
+    receiver: mpsc::UnboundedReceiver<Vec<u8>>,
+    _stop: oneshot::Receiver<()>,
+}
+
+impl Subscription {
+    pub(super) fn new(kind: SubscriptionKind, handler: Option<SubscribeFn>) -> Self {
+        let (sender, receiver) = mpsc::unbounded();
+        let (stop, stop_receiver) = oneshot::channel();
+        let notifier = Notifier(backend::server::NotifierImpl::Mock(NotifierImpl { kind, sender, stop }));
+        Subscription {
+            // Without a handler the notifier is dropped, which ends the stream
+            handler: handler.map(|handler| handler(notifier)),
+            receiver,
+            _stop: stop_receiver,
+        }
+    }
+}
+
+impl Stream for Subscription {
+    type Item = Vec<u8>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Some(handler) = &mut self.handler {
+            if handler.poll(cx).is_ready() {
+                self.handler = None;
+            }
+        }
+        Pin::new(&mut self.receiver).poll_next(cx)
+    }
+}
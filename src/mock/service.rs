@@ -0,0 +1,108 @@
+use super::characteristic::CharacteristicImpl;
+use super::device::DeviceImpl;
+use super::ServiceEntry;
+use crate::{backend, Characteristic, Result, Service, Uuid};
+
+/// A simulated GATT service
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ServiceImpl {
+    pub(super) device: DeviceImpl,
+    pub(super) handle: u16,
+    uuid: Uuid,
+}
+
+impl ServiceImpl {
+    pub(super) fn new(device: DeviceImpl, entry: &ServiceEntry) -> Self {
+        ServiceImpl {
+            device,
+            handle: entry.handle,
+            uuid: entry.uuid,
+        }
+    }
+
+    pub(super) fn into_service(self) -> Service {
+        Service(backend::service::ServiceImpl::Mock(self))
+    }
+
+    /// The [`Uuid`] identifying the type of this GATT service
+    pub fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    /// The [`Uuid`] identifying the type of this GATT service
+    pub async fn uuid_async(&self) -> Result<Uuid> {
+        Ok(self.uuid)
+    }
+
+    /// The attribute handle of this GATT service.
+    pub fn handle(&self) -> Result<u16> {
+        Ok(self.handle)
+    }
+
+    /// The last attribute handle belonging to this GATT service.
+    pub fn end_handle(&self) -> Result<u16> {
+        Ok(self.device.peripheral.lock().service(self.handle)?.end_handle)
+    }
+
+    /// Whether this is a primary service of the device.
+    pub async fn is_primary(&self) -> Result<bool> {
+        Ok(self.device.peripheral.lock().service(self.handle)?.primary)
+    }
+
+    /// Discover all characteristics associated with this service.
+    pub async fn discover_characteristics(&self) -> Result<Vec<Characteristic>> {
+        self.characteristics().await
+    }
+
+    /// Discover the characteristic(s) with the given [`Uuid`].
+    pub async fn discover_characteristics_with_uuid(&self, uuid: Uuid) -> Result<Vec<Characteristic>> {
+        Ok(self
+            .characteristics()
+            .await?
+            .into_iter()
+            .filter(|x| x.uuid() == uuid)
+            .collect())
+    }
+
+    /// Get previously discovered characteristics.
+    pub async fn characteristics(&self) -> Result<Vec<Characteristic>> {
+        self.device.peripheral.operation().await?;
+        let state = self.device.peripheral.lock_connected()?;
+        Ok(state
+            .service(self.handle)?
+            .characteristics
+            .iter()
+            .map(|x| CharacteristicImpl::new(self.clone(), x).into_characteristic())
+            .collect())
+    }
+
+    /// Discover the included services of this service.
+    pub async fn discover_included_services(&self) -> Result<Vec<Service>> {
+        self.included_services().await
+    }
+
+    /// Discover the included service(s) with the given [`Uuid`].
+    pub async fn discover_included_services_with_uuid(&self, uuid: Uuid) -> Result<Vec<Service>> {
+        Ok(self
+            .included_services()
+            .await?
+            .into_iter()
+            .filter(|x| x.uuid() == uuid)
+            .collect())
+    }
+
+    /// Get previously discovered included services.
+    pub async fn included_services(&self) -> Result<Vec<Service>> {
+        self.device.peripheral.operation().await?;
+        let state = self.device.peripheral.lock_connected()?;
+        state
+            .service(self.handle)?
+            .included_services
+            .iter()
+            .map(|&handle| {
+                let entry = state.service(handle)?;
+                Ok(ServiceImpl::new(self.device.clone(), entry).into_service())
+            })
+            .collect()
+    }
+}
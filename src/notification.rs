@@ -0,0 +1,426 @@
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use std::task::{Context, Poll, Wake, Waker};
+use std::time::Instant;
+
+use futures_channel::mpsc;
+use futures_core::Stream;
+use futures_lite::future::poll_fn;
+use futures_lite::StreamExt;
+
+use crate::error::ErrorKind;
+use crate::{Characteristic, CharacteristicProperties, Error, Result};
+
+/// The kind of value updates requested with [`Characteristic::subscribe`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SubscriptionKind {
+    /// Unacknowledged notifications
+    Notify,
+    /// Indications, which the device must receive a confirmation for before sending another
+    Indicate,
+}
+
+impl SubscriptionKind {
+    /// Fails with [`ErrorKind::NotSupported`] unless a characteristic with `properties` supports this kind of
+    /// subscription.
+    pub(crate) fn check_supported(self, properties: &CharacteristicProperties) -> Result<()> {
+        match self {
+            SubscriptionKind::Notify if !properties.notify => Err(Error::new(
+                ErrorKind::NotSupported,
+                None,
+                "characteristic does not support notifications",
+            )),
+            SubscriptionKind::Indicate if !properties.indicate => Err(Error::new(
+                ErrorKind::NotSupported,
+                None,
+                "characteristic does not support indications",
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A value of a characteristic sent by the device
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    /// The value of the characteristic
+    pub value: Vec<u8>,
+    /// Whether the value was sent as an indication rather than a notification
+    pub is_indication: bool,
+    /// When the value was received
+    pub received_at: Instant,
+}
+
+/// What happens when a subscriber's buffer is full and another value is received
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LagPolicy {
+    /// The oldest buffered value is silently discarded.
+    #[default]
+    DropOldest,
+    /// The oldest buffered value is discarded and the subscriber's stream yields an error of kind
+    /// [`Lagged`][ErrorKind::Lagged] before the remaining buffered values.
+    Error,
+}
+
+/// Options for a subscriber to the values sent for a characteristic
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriberOptions {
+    /// The number of values buffered for the subscriber while it is not polled. Defaults to 32.
+    pub buffer_len: usize,
+    /// What happens when the buffer is full. Defaults to [`LagPolicy::DropOldest`].
+    pub lag_policy: LagPolicy,
+}
+
+impl Default for SubscriberOptions {
+    fn default() -> Self {
+        SubscriberOptions {
+            buffer_len: 32,
+            lag_policy: LagPolicy::DropOldest,
+        }
+    }
+}
+
+type Hubs = HashMap<Characteristic, (SubscriptionKind, Weak<Hub>)>;
+
+/// The subscription shared by every subscriber to a characteristic, and its kind.
+///
+/// A characteristic has a single Client Characteristic Configuration descriptor, so it can only have one kind of
+/// subscription at a time.
+fn hubs() -> &'static Mutex<Hubs> {
+    static HUBS: OnceLock<Mutex<Hubs>> = OnceLock::new();
+    HUBS.get_or_init(Default::default)
+}
+
+/// Subscribes to `characteristic`, sharing the platform subscription with any other subscribers.
+///
+/// Returns [`ErrorKind::InvalidParameter`] if the characteristic is already subscribed to with a different kind.
+pub(crate) async fn subscribe(
+    characteristic: &Characteristic,
+    kind: SubscriptionKind,
+    options: SubscriberOptions,
+) -> Result<Subscriber> {
+    // Declared before the lock so that a hub dropped here is dropped after the registry is unlocked
+    let existing;
+    let hub = {
+        let mut hubs = hubs().lock().unwrap();
+        existing = hubs
+            .get(characteristic)
+            .and_then(|(kind, hub)| Some((*kind, hub.upgrade()?)));
+        match &existing {
+            Some((existing_kind, hub)) if !hub.state.lock().unwrap().closed => {
+                if *existing_kind != kind {
+                    return Err(Error::new(
+                        ErrorKind::InvalidParameter,
+                        None,
+                        "characteristic is already subscribed to with a different kind",
+                    ));
+                }
+                hub.clone()
+            }
+            // A closed hub is replaced, it is only dropped with `existing` once the registry is unlocked
+            _ => {
+                let hub = Hub::new(characteristic.clone(), kind);
+                hubs.insert(characteristic.clone(), (kind, Arc::downgrade(&hub)));
+                hub
+            }
+        }
+    };
+
+    let subscriber = Subscriber::new(hub, options);
+    poll_fn(|cx| subscriber.poll_enabled(cx)).await?;
+    Ok(subscriber)
+}
+
+/// Ends the subscription to `characteristic`, ending the streams of all of its subscribers.
+///
+/// Returns the number of streams ended.
+pub(crate) fn unsubscribe(characteristic: &Characteristic) -> usize {
+    let hub = hubs()
+        .lock()
+        .unwrap()
+        .remove(characteristic)
+        .and_then(|(_, hub)| hub.upgrade());
+    let Some(hub) = hub else {
+        return 0;
+    };
+
+    let mut state = hub.state.lock().unwrap();
+    // Dropping the platform subscription disables notifications when the platform does so on drop
+    state.driver = None;
+    state.closed = true;
+    for subscriber in &mut state.subscribers {
+        subscriber.buffer.clear();
+        subscriber.lagged = 0;
+    }
+    let ended = state.subscribers.len();
+    drop(state);
+    hub.wakers.wake_by_ref();
+    ended
+}
+
+enum HubEvent {
+    Enabled(Result<()>),
+    Value(Result<Notification>),
+}
+
+/// A single platform subscription whose values are copied to every subscriber.
+struct Hub {
+    characteristic: Characteristic,
+    state: Mutex<HubState>,
+    wakers: Arc<Wakers>,
+}
+
+struct HubState {
+    /// Drives the platform subscription, forwarding its values to `events`.
+    driver: Option<Pin<Box<dyn Future<Output = ()> + Send>>>,
+    events: mpsc::UnboundedReceiver<HubEvent>,
+    enabled: Option<Result<()>>,
+    closed: bool,
+    subscribers: Vec<SubscriberState>,
+    next_id: usize,
+}
+
+struct SubscriberState {
+    id: usize,
+    options: SubscriberOptions,
+    buffer: VecDeque<Result<Notification>>,
+    lagged: usize,
+}
+
+impl Hub {
+    fn new(characteristic: Characteristic, kind: SubscriptionKind) -> Arc<Self> {
+        let (sender, events) = mpsc::unbounded();
+        let subscribed = characteristic.clone();
+        let driver = async move {
+            let mut values = match subscribed.0.subscribe(kind).await {
+                Ok(values) => values,
+                Err(err) => {
+                    let _ = sender.unbounded_send(HubEvent::Enabled(Err(err)));
+                    return;
+                }
+            };
+            let _ = sender.unbounded_send(HubEvent::Enabled(Ok(())));
+            while let Some(value) = values.next().await {
+                if sender.unbounded_send(HubEvent::Value(value)).is_err() {
+                    break;
+                }
+            }
+        };
+
+        Arc::new(Hub {
+            characteristic,
+            state: Mutex::new(HubState {
+                driver: Some(Box::pin(driver)),
+                events,
+                enabled: None,
+                closed: false,
+                subscribers: Vec::new(),
+                next_id: 0,
+            }),
+            wakers: Arc::new(Wakers::default()),
+        })
+    }
+}
+
+impl Drop for Hub {
+    fn drop(&mut self) {
+        let mut hubs = hubs().lock().unwrap();
+        // The entry may already have been replaced by a newer hub for the same characteristic
+        if let Some((_, hub)) = hubs.get(&self.characteristic) {
+            if std::ptr::eq(hub.as_ptr(), self) {
+                hubs.remove(&self.characteristic);
+            }
+        }
+    }
+}
+
+impl HubState {
+    /// Makes progress on the platform subscription and copies any values received to the subscribers' buffers.
+    fn poll_driver(&mut self, wakers: &Arc<Wakers>) {
+        if self.closed {
+            return;
+        }
+
+        let waker = Waker::from(wakers.clone());
+        let mut cx = Context::from_waker(&waker);
+        if let Some(driver) = &mut self.driver {
+            if driver.as_mut().poll(&mut cx).is_ready() {
+                self.driver = None;
+            }
+        }
+
+        loop {
+            match Pin::new(&mut self.events).poll_next(&mut cx) {
+                Poll::Ready(Some(HubEvent::Enabled(res))) => self.enabled = Some(res),
+                Poll::Ready(Some(HubEvent::Value(value))) => {
+                    for subscriber in &mut self.subscribers {
+                        subscriber.push(copy_value(&value));
+                    }
+                }
+                Poll::Ready(None) => {
+                    self.closed = true;
+                    break;
+                }
+                Poll::Pending => break,
+            }
+        }
+    }
+}
+
+impl SubscriberState {
+    fn push(&mut self, value: Result<Notification>) {
+        if self.buffer.len() >= self.options.buffer_len.max(1) {
+            self.buffer.pop_front();
+            self.lagged += 1;
+        }
+        self.buffer.push_back(value);
+    }
+
+    fn pop(&mut self) -> Option<Result<Notification>> {
+        if self.lagged > 0 && self.options.lag_policy == LagPolicy::Error {
+            let lagged = std::mem::take(&mut self.lagged);
+            return Some(Err(Error::new(
+                ErrorKind::Lagged,
+                None,
+                format!("{lagged} values were dropped"),
+            )));
+        }
+        self.buffer.pop_front()
+    }
+}
+
+/// Copies a value received from the platform subscription for one subscriber.
+///
+/// Errors cannot be cloned, so the copies keep the kind and message of the error but not its source.
+fn copy_value(value: &Result<Notification>) -> Result<Notification> {
+    match value {
+        Ok(notification) => Ok(notification.clone()),
+        Err(err) => Err(copy_error(err)),
+    }
+}
+
+fn copy_error(err: &Error) -> Error {
+    match std::error::Error::source(err) {
+        Some(source) if err.message().is_empty() => Error::new(err.kind(), None, source),
+        Some(source) => Error::new(err.kind(), None, format!("{} ({source})", err.message())),
+        None => Error::new(err.kind(), None, err.message()),
+    }
+}
+
+/// The wakers of every subscriber waiting for a hub.
+///
+/// The platform subscription is polled by whichever subscriber is polled, so it must wake all of them.
+#[derive(Default)]
+struct Wakers(Mutex<Vec<(usize, Waker)>>);
+
+impl Wakers {
+    fn register(&self, id: usize, waker: &Waker) {
+        let mut wakers = self.0.lock().unwrap();
+        match wakers.iter_mut().find(|(x, _)| *x == id) {
+            Some((_, existing)) => existing.clone_from(waker),
+            None => wakers.push((id, waker.clone())),
+        }
+    }
+
+    fn remove(&self, id: usize) {
+        self.0.lock().unwrap().retain(|(x, _)| *x != id);
+    }
+}
+
+impl Wake for Wakers {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        let wakers = std::mem::take(&mut *self.0.lock().unwrap());
+        for (_, waker) in wakers {
+            waker.wake();
+        }
+    }
+}
+
+/// One subscriber to a [`Hub`]
+pub(crate) struct Subscriber {
+    hub: Arc<Hub>,
+    id: usize,
+}
+
+impl Subscriber {
+    fn new(hub: Arc<Hub>, options: SubscriberOptions) -> Self {
+        let id = {
+            let mut state = hub.state.lock().unwrap();
+            let id = state.next_id;
+            state.next_id += 1;
+            state.subscribers.push(SubscriberState {
+                id,
+                options,
+                buffer: VecDeque::new(),
+                lagged: 0,
+            });
+            id
+        };
+        Subscriber { hub, id }
+    }
+
+    /// Waits until the platform subscription has been enabled.
+    fn poll_enabled(&self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let mut state = self.hub.state.lock().unwrap();
+        for poll_driver in [false, true] {
+            if poll_driver {
+                self.hub.wakers.register(self.id, cx.waker());
+                state.poll_driver(&self.hub.wakers);
+            }
+            match &state.enabled {
+                Some(Ok(())) => return Poll::Ready(Ok(())),
+                Some(Err(err)) => return Poll::Ready(Err(copy_error(err))),
+                // The subscription was ended by `unsubscribe`
+                None if state.closed => return Poll::Ready(Ok(())),
+                None => (),
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl Stream for Subscriber {
+    type Item = Result<Notification>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut state = self.hub.state.lock().unwrap();
+        for poll_driver in [false, true] {
+            if poll_driver {
+                self.hub.wakers.register(self.id, cx.waker());
+                state.poll_driver(&self.hub.wakers);
+            }
+            let closed = state.closed;
+            let subscriber = state.subscribers.iter_mut().find(|x| x.id == self.id).unwrap();
+            if let Some(value) = subscriber.pop() {
+                return Poll::Ready(Some(value));
+            }
+            if closed {
+                return Poll::Ready(None);
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for Subscriber {
+    fn drop(&mut self) {
+        self.hub.wakers.remove(self.id);
+        let mut state = self.hub.state.lock().unwrap();
+        state.subscribers.retain(|x| x.id != self.id);
+    }
+}
+
+impl std::fmt::Debug for Subscriber {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Subscriber")
+            .field("id", &self.id)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,307 @@
+//! Local GATT services published to remote devices (GATT Server role)
+//!
+//! An [`Application`] describes a set of local services, each containing characteristics and descriptors whose values
+//! are provided by asynchronous handlers. It is published with [`Adapter::serve_gatt`][crate::Adapter::serve_gatt],
+//! which returns a [`GattServer`]. The services stay published until the [`GattServer`] is dropped.
+//!
+//! ```rust,no_run
+//!# use bluest::btuuid::{characteristics, services};
+//!# use bluest::server::{Application, LocalCharacteristic, LocalService};
+//!# use bluest::{Adapter, CharacteristicProperties};
+//!# #[tokio::main]
+//!# async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!let adapter = Adapter::default().await?;
+//!
+//!let mut properties = CharacteristicProperties::default();
+//!properties.read = true;
+//!properties.notify = true;
+//!
+//!let mut service = LocalService::new(services::BATTERY);
+//!service.characteristics.push(
+//!    LocalCharacteristic::new(characteristics::BATTERY_LEVEL, properties)
+//!        .on_read(|_| async { Ok(vec![87]) })
+//!        .on_subscribe(|mut notifier| async move {
+//!            let _ = notifier.notify(&[86]).await;
+//!        }),
+//!);
+//!
+//!let _server = adapter.serve_gatt(Application { services: vec![service] }).await?;
+//!#    Ok(())
+//!# }
+//! ```
+//!
+//! # Platform specific
+//!
+//! Only supported on Linux and by the `mock` backend. Returns [`NotSupported`][crate::error::ErrorKind::NotSupported]
+//! on MacOS/iOS, Windows and Android.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use crate::error::AttError;
+use crate::{sys, CharacteristicProperties, Result, SubscriptionKind, Uuid};
+
+pub(crate) type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+pub(crate) type ReadFn = Arc<dyn Fn(ReadRequest) -> BoxFuture<Result<Vec<u8>, AttError>> + Send + Sync>;
+pub(crate) type WriteFn = Arc<dyn Fn(WriteRequest) -> BoxFuture<Result<(), AttError>> + Send + Sync>;
+pub(crate) type SubscribeFn = Arc<dyn Fn(Notifier) -> BoxFuture<()> + Send + Sync>;
+
+/// A set of local GATT services to publish with [`Adapter::serve_gatt`][crate::Adapter::serve_gatt]
+#[derive(Debug, Default)]
+pub struct Application {
+    /// The services to publish
+    pub services: Vec<LocalService>,
+}
+
+/// A local GATT service
+#[derive(Debug)]
+pub struct LocalService {
+    /// The [`Uuid`] identifying the type of this service
+    pub uuid: Uuid,
+    /// Whether this is a primary service
+    pub primary: bool,
+    /// The characteristics of this service
+    pub characteristics: Vec<LocalCharacteristic>,
+}
+
+impl LocalService {
+    /// Creates a primary service with no characteristics.
+    pub fn new(uuid: Uuid) -> Self {
+        LocalService {
+            uuid,
+            primary: true,
+            characteristics: Vec::new(),
+        }
+    }
+}
+
+/// A local GATT characteristic
+///
+/// The value of the characteristic is provided by the handlers set with [`on_read`][Self::on_read],
+/// [`on_write`][Self::on_write] and [`on_subscribe`][Self::on_subscribe]. Requests for which no handler is set are
+/// rejected.
+pub struct LocalCharacteristic {
+    /// The [`Uuid`] identifying the type of this characteristic
+    pub uuid: Uuid,
+    /// The properties of this characteristic
+    pub properties: CharacteristicProperties,
+    /// The descriptors of this characteristic
+    pub descriptors: Vec<LocalDescriptor>,
+    pub(crate) read: Option<ReadFn>,
+    pub(crate) write: Option<WriteFn>,
+    pub(crate) subscribe: Option<SubscribeFn>,
+}
+
+impl LocalCharacteristic {
+    /// Creates a characteristic with no handlers and no descriptors.
+    pub fn new(uuid: Uuid, properties: CharacteristicProperties) -> Self {
+        LocalCharacteristic {
+            uuid,
+            properties,
+            descriptors: Vec::new(),
+            read: None,
+            write: None,
+            subscribe: None,
+        }
+    }
+
+    /// Sets the handler called for each read of this characteristic by a remote device.
+    ///
+    /// The handler returns the value starting at [`ReadRequest::offset`], or the protocol error to respond with.
+    pub fn on_read<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(ReadRequest) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Vec<u8>, AttError>> + Send + 'static,
+    {
+        self.read = Some(Arc::new(move |request| Box::pin(handler(request))));
+        self
+    }
+
+    /// Sets the handler called for each write to this characteristic by a remote device.
+    ///
+    /// The handler returns the protocol error to respond with if the write is rejected.
+    pub fn on_write<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(WriteRequest) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), AttError>> + Send + 'static,
+    {
+        self.write = Some(Arc::new(move |request| Box::pin(handler(request))));
+        self
+    }
+
+    /// Sets the handler called when a remote device subscribes to notifications or indications of this characteristic.
+    ///
+    /// The handler is given a [`Notifier`] to send values to the subscriber with. The subscription lasts until the
+    /// subscriber unsubscribes, which can be awaited with [`Notifier::stopped`], or the handler returns.
+    pub fn on_subscribe<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(Notifier) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.subscribe = Some(Arc::new(move |notifier| Box::pin(handler(notifier))));
+        self
+    }
+}
+
+impl std::fmt::Debug for LocalCharacteristic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalCharacteristic")
+            .field("uuid", &self.uuid)
+            .field("properties", &self.properties)
+            .field("descriptors", &self.descriptors)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A local GATT descriptor
+///
+/// The descriptor can be read if an [`on_read`][Self::on_read] handler is set and written if an
+/// [`on_write`][Self::on_write] handler is set. The Client Characteristic Configuration descriptor is managed by the
+/// platform and must not be added.
+pub struct LocalDescriptor {
+    /// The [`Uuid`] identifying the type of this descriptor
+    pub uuid: Uuid,
+    pub(crate) read: Option<ReadFn>,
+    pub(crate) write: Option<WriteFn>,
+}
+
+impl LocalDescriptor {
+    /// Creates a descriptor with no handlers.
+    pub fn new(uuid: Uuid) -> Self {
+        LocalDescriptor {
+            uuid,
+            read: None,
+            write: None,
+        }
+    }
+
+    /// Sets the handler called for each read of this descriptor by a remote device.
+    ///
+    /// The handler returns the value starting at [`ReadRequest::offset`], or the protocol error to respond with.
+    pub fn on_read<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(ReadRequest) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Vec<u8>, AttError>> + Send + 'static,
+    {
+        self.read = Some(Arc::new(move |request| Box::pin(handler(request))));
+        self
+    }
+
+    /// Sets the handler called for each write to this descriptor by a remote device.
+    ///
+    /// The handler returns the protocol error to respond with if the write is rejected.
+    pub fn on_write<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(WriteRequest) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), AttError>> + Send + 'static,
+    {
+        self.write = Some(Arc::new(move |request| Box::pin(handler(request))));
+        self
+    }
+}
+
+impl std::fmt::Debug for LocalDescriptor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalDescriptor")
+            .field("uuid", &self.uuid)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A read of a local characteristic or descriptor by a remote device
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadRequest {
+    /// The offset into the value at which the read starts
+    pub offset: u16,
+    /// The ATT MTU of the connection to the remote device, or 23 if the platform does not report it
+    pub mtu: u16,
+}
+
+/// A write to a local characteristic or descriptor by a remote device
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WriteRequest {
+    /// The value written
+    pub value: Vec<u8>,
+    /// The offset into the value at which the write starts
+    pub offset: u16,
+    /// The ATT MTU of the connection to the remote device, or 23 if the platform does not report it
+    pub mtu: u16,
+    /// Whether the remote device expects a response to the write
+    pub with_response: bool,
+}
+
+/// A subscription of a remote device to a local characteristic
+///
+/// Passed to the handler set with [`LocalCharacteristic::on_subscribe`].
+pub struct Notifier(pub(crate) sys::server::NotifierImpl);
+
+impl Notifier {
+    #[cfg(target_os = "linux")]
+    pub(crate) fn from_platform(inner: crate::platform::server::NotifierImpl) -> Self {
+        #[cfg(feature = "mock")]
+        let inner = sys::server::NotifierImpl::Platform(inner);
+        Notifier(inner)
+    }
+
+    /// Whether values are sent as notifications or indications.
+    #[inline]
+    pub fn kind(&self) -> SubscriptionKind {
+        self.0.kind()
+    }
+
+    /// Returns `true` if the remote device has unsubscribed.
+    #[inline]
+    pub fn is_stopped(&self) -> bool {
+        self.0.is_stopped()
+    }
+
+    /// Waits until the remote device unsubscribes.
+    #[inline]
+    pub async fn stopped(&mut self) {
+        self.0.stopped().await
+    }
+
+    /// Sends `value` to the remote device.
+    ///
+    /// For indications, waits until the remote device has confirmed the value. Returns an error if the remote device
+    /// has unsubscribed.
+    #[inline]
+    pub async fn notify(&mut self, value: &[u8]) -> Result<()> {
+        self.0.notify(value).await
+    }
+}
+
+impl std::fmt::Debug for Notifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Notifier")
+            .field("kind", &self.kind())
+            .field("is_stopped", &self.is_stopped())
+            .finish()
+    }
+}
+
+/// Local GATT services published with [`Adapter::serve_gatt`][crate::Adapter::serve_gatt]
+///
+/// The services are unpublished when this is dropped.
+#[derive(Debug)]
+pub struct GattServer {
+    _inner: sys::server::GattServerImpl,
+}
+
+impl GattServer {
+    #[cfg(target_os = "linux")]
+    pub(crate) fn from_platform(inner: crate::platform::server::GattServerImpl) -> Self {
+        #[cfg(feature = "mock")]
+        let inner = sys::server::GattServerImpl::Platform(inner);
+        GattServer { _inner: inner }
+    }
+    #[cfg(feature = "mock")]
+    pub(crate) fn from_mock(inner: crate::mock::server::GattServerImpl) -> Self {
+        GattServer {
+            _inner: sys::server::GattServerImpl::Mock(inner),
+        }
+    }
+}
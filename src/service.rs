@@ -5,6 +5,12 @@ use crate::{sys, Characteristic, Result, Uuid};
 pub struct Service(pub(crate) sys::service::ServiceImpl);
 
 impl Service {
+    pub(crate) fn from_platform(inner: crate::platform::service::ServiceImpl) -> Self {
+        #[cfg(feature = "mock")]
+        let inner = sys::service::ServiceImpl::Platform(inner);
+        Service(inner)
+    }
+
     /// The [`Uuid`] identifying the type of this GATT service
     ///
     /// # Panics
@@ -23,6 +29,31 @@ impl Service {
         self.0.uuid_async().await
     }
 
+    /// The attribute handle of this GATT service.
+    ///
+    /// This is the handle of the service declaration, the first attribute of the service.
+    ///
+    /// # Platform specific
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS and Android.
+    #[inline]
+    pub fn handle(&self) -> Result<u16> {
+        self.0.handle()
+    }
+
+    /// The last attribute handle belonging to this GATT service.
+    ///
+    /// Together with [`handle`][Self::handle], this is the handle range covered by the service.
+    ///
+    /// # Platform specific
+    ///
+    /// Only supported by the mock backend. Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on all
+    /// platforms.
+    #[inline]
+    pub fn end_handle(&self) -> Result<u16> {
+        self.0.end_handle()
+    }
+
     /// Whether this is a primary service of the device.
     ///
     /// # Platform specific
@@ -0,0 +1,229 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use futures_core::Stream;
+
+use crate::util::{sleep_until, Sleep};
+use crate::{AdvertisementData, AdvertisingDevice, Device, DeviceId};
+
+/// Options for a [`DeviceTracker`]
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrackerOptions {
+    /// How long after its last advertisement a device is reported as [`Lost`][TrackerEvent::Lost]. Defaults to 10
+    /// seconds.
+    pub lost_timeout: Duration,
+    /// The weight, between 0 and 1, given to each new RSSI sample in [`TrackedDevice::smoothed_rssi`]. Smaller values
+    /// smooth more strongly. Defaults to 0.25.
+    pub rssi_smoothing: f64,
+    /// The number of RSSI samples kept in [`TrackedDevice::rssi_history`]. Defaults to 16.
+    pub rssi_history_len: usize,
+}
+
+impl Default for TrackerOptions {
+    fn default() -> Self {
+        TrackerOptions {
+            lost_timeout: Duration::from_secs(10),
+            rssi_smoothing: 0.25,
+            rssi_history_len: 16,
+        }
+    }
+}
+
+/// A device seen by a [`DeviceTracker`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackedDevice {
+    /// The device
+    pub device: Device,
+    /// When the first advertisement from the device was received
+    pub first_seen: Instant,
+    /// When the latest advertisement from the device was received
+    pub last_seen: Instant,
+    /// The signal strength in dBm of the latest advertisement which included it
+    pub rssi: Option<i16>,
+    /// An exponential moving average of the signal strength in dBm
+    pub smoothed_rssi: Option<f64>,
+    /// The most recent signal strength samples in dBm, oldest first
+    pub rssi_history: VecDeque<(Instant, i16)>,
+    /// The data of every advertisement and scan response received from the device, merged together
+    ///
+    /// Newer values replace older ones and lists of services are combined. [`raw`][AdvertisementData::raw] is the
+    /// latest raw payload received.
+    pub adv_data: AdvertisementData,
+}
+
+impl TrackedDevice {
+    fn new(adv: AdvertisingDevice, now: Instant, options: &TrackerOptions) -> Self {
+        let mut device = TrackedDevice {
+            device: adv.device,
+            first_seen: now,
+            last_seen: now,
+            rssi: None,
+            smoothed_rssi: None,
+            rssi_history: VecDeque::new(),
+            adv_data: AdvertisementData::default(),
+        };
+        device.record(adv.adv_data, adv.rssi, now, options);
+        device
+    }
+
+    fn record(&mut self, adv_data: AdvertisementData, rssi: Option<i16>, now: Instant, options: &TrackerOptions) {
+        self.last_seen = now;
+        self.adv_data.combine(adv_data);
+
+        if let Some(rssi) = rssi {
+            self.rssi = Some(rssi);
+            let sample = f64::from(rssi);
+            self.smoothed_rssi = Some(match self.smoothed_rssi {
+                Some(smoothed) => smoothed + options.rssi_smoothing * (sample - smoothed),
+                None => sample,
+            });
+            self.rssi_history.push_back((now, rssi));
+            while self.rssi_history.len() > options.rssi_history_len {
+                self.rssi_history.pop_front();
+            }
+        }
+    }
+}
+
+/// Events generated by a [`DeviceTracker`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum TrackerEvent {
+    /// An advertisement was received from a device which was not being tracked
+    Appeared(TrackedDevice),
+    /// Another advertisement was received from a tracked device
+    Updated(TrackedDevice),
+    /// No advertisement was received from a tracked device within [`TrackerOptions::lost_timeout`]. The device is no
+    /// longer tracked.
+    Lost(TrackedDevice),
+}
+
+/// Keeps track of the devices seen during a scan.
+///
+/// A `DeviceTracker` is a [`Stream`] of [`TrackerEvent`]s built on a stream of advertisements, usually created with
+/// [`Adapter::track_devices`][crate::Adapter::track_devices]. It keeps a table of the devices seen, merging every
+/// advertisement and scan response received from each device regardless of whether the platform reports duplicate
+/// advertisements. The table is only updated while the stream is polled.
+///
+/// The tracker ends when the underlying stream of advertisements ends.
+pub struct DeviceTracker<'a> {
+    scan: Pin<Box<dyn Stream<Item = AdvertisingDevice> + Send + 'a>>,
+    options: TrackerOptions,
+    devices: HashMap<DeviceId, TrackedDevice>,
+    timer: Option<Sleep>,
+}
+
+impl<'a> DeviceTracker<'a> {
+    /// Creates a tracker for the advertisements in `scan`.
+    ///
+    /// `scan` should report duplicate advertisements, for example a stream returned by
+    /// [`Adapter::scan_with_options`][crate::Adapter::scan_with_options] with
+    /// [`allow_duplicates`][crate::ScanOptions::allow_duplicates] set. Otherwise devices which keep advertising may be
+    /// reported as lost.
+    pub fn new<S>(scan: S, options: TrackerOptions) -> Self
+    where
+        S: Stream<Item = AdvertisingDevice> + Send + 'a,
+    {
+        DeviceTracker {
+            scan: Box::pin(scan),
+            options,
+            devices: HashMap::new(),
+            timer: None,
+        }
+    }
+
+    /// The options of this tracker.
+    pub fn options(&self) -> &TrackerOptions {
+        &self.options
+    }
+
+    /// The devices currently being tracked.
+    pub fn devices(&self) -> impl Iterator<Item = &TrackedDevice> + '_ {
+        self.devices.values()
+    }
+
+    /// The tracked device with the given id, if any.
+    pub fn device(&self, id: &DeviceId) -> Option<&TrackedDevice> {
+        self.devices.get(id)
+    }
+
+    fn update(&mut self, adv: AdvertisingDevice, now: Instant) -> TrackerEvent {
+        match self.devices.entry(adv.device.id()) {
+            Entry::Occupied(entry) => {
+                let device = entry.into_mut();
+                device.record(adv.adv_data, adv.rssi, now, &self.options);
+                TrackerEvent::Updated(device.clone())
+            }
+            Entry::Vacant(entry) => {
+                TrackerEvent::Appeared(entry.insert(TrackedDevice::new(adv, now, &self.options)).clone())
+            }
+        }
+    }
+
+    fn remove_lost(&mut self, now: Instant) -> Option<TrackedDevice> {
+        let timeout = self.options.lost_timeout;
+        let id = self
+            .devices
+            .iter()
+            .find(|(_, device)| now.saturating_duration_since(device.last_seen) >= timeout)
+            .map(|(id, _)| id.clone())?;
+        self.devices.remove(&id)
+    }
+
+    fn next_deadline(&self) -> Option<Instant> {
+        self.devices
+            .values()
+            .map(|device| device.last_seen + self.options.lost_timeout)
+            .min()
+    }
+}
+
+impl Stream for DeviceTracker<'_> {
+    type Item = TrackerEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        loop {
+            if let Some(device) = this.remove_lost(Instant::now()) {
+                return Poll::Ready(Some(TrackerEvent::Lost(device)));
+            }
+
+            match this.scan.as_mut().poll_next(cx) {
+                Poll::Ready(Some(adv)) => return Poll::Ready(Some(this.update(adv, Instant::now()))),
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => (),
+            }
+
+            let deadline = match this.next_deadline() {
+                Some(deadline) => deadline,
+                None => {
+                    this.timer = None;
+                    return Poll::Pending;
+                }
+            };
+
+            // An earlier timer is kept, the deadline is checked again when it fires.
+            let timer = match &mut this.timer {
+                Some(timer) if timer.deadline() <= deadline => timer,
+                timer => timer.insert(sleep_until(deadline)),
+            };
+            match Pin::new(timer).poll(cx) {
+                Poll::Ready(()) => this.timer = None,
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for DeviceTracker<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DeviceTracker")
+            .field("options", &self.options)
+            .field("devices", &self.devices)
+            .finish_non_exhaustive()
+    }
+}
@@ -1,6 +1,12 @@
 #![allow(unused)] // used depending on the target.
 
+use std::collections::BTreeMap;
+use std::future::Future;
 use std::mem::ManuallyDrop;
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
 
 pub struct ScopeGuard<F: FnOnce()> {
     dropfn: ManuallyDrop<F>,
@@ -26,3 +32,114 @@ pub fn defer<F: FnOnce()>(dropfn: F) -> ScopeGuard<F> {
         dropfn: ManuallyDrop::new(dropfn),
     }
 }
+
+/// A future which completes at a given instant, independently of the async runtime in use.
+///
+/// Dropping the future cancels the wait.
+pub struct Sleep {
+    key: (Instant, u64),
+    state: Arc<Mutex<SleepState>>,
+}
+
+#[derive(Default)]
+struct SleepState {
+    done: bool,
+    waker: Option<Waker>,
+}
+
+impl Sleep {
+    pub fn deadline(&self) -> Instant {
+        self.key.0
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.lock().unwrap();
+        if state.done {
+            Poll::Ready(())
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        timer().lock().unwrap().sleeps.remove(&self.key);
+    }
+}
+
+/// The pending sleeps of the timer thread, ordered by deadline
+#[derive(Default)]
+struct Timer {
+    sleeps: BTreeMap<(Instant, u64), Arc<Mutex<SleepState>>>,
+    next_id: u64,
+}
+
+static TIMER_CONDVAR: Condvar = Condvar::new();
+
+/// Returns the state of the timer thread, starting the thread on first use.
+fn timer() -> &'static Mutex<Timer> {
+    static TIMER: OnceLock<Mutex<Timer>> = OnceLock::new();
+    TIMER.get_or_init(|| {
+        std::thread::Builder::new()
+            .name("bluest-timer".to_string())
+            .spawn(run_timer)
+            .expect("failed to spawn the timer thread");
+        Mutex::new(Timer::default())
+    })
+}
+
+fn run_timer() {
+    let mut wakers = Vec::new();
+    loop {
+        let mut timer = timer().lock().unwrap();
+        let now = Instant::now();
+        while let Some(entry) = timer.sleeps.first_entry() {
+            if entry.key().0 > now {
+                break;
+            }
+            let state = entry.remove();
+            let mut state = state.lock().unwrap();
+            state.done = true;
+            wakers.extend(state.waker.take());
+        }
+
+        if wakers.is_empty() {
+            drop(match timer.sleeps.first_key_value() {
+                Some(((deadline, _), _)) => {
+                    let timeout = deadline.saturating_duration_since(now);
+                    TIMER_CONDVAR.wait_timeout(timer, timeout).unwrap().0
+                }
+                None => TIMER_CONDVAR.wait(timer).unwrap(),
+            });
+        } else {
+            // Wake the tasks without holding the lock, as they may drop or create sleeps
+            drop(timer);
+            wakers.drain(..).for_each(Waker::wake);
+        }
+    }
+}
+
+/// Completes at `deadline`. The wait happens on a shared background thread.
+pub fn sleep_until(deadline: Instant) -> Sleep {
+    let state = Arc::new(Mutex::new(SleepState::default()));
+    let mut timer = timer().lock().unwrap();
+    let key = (deadline, timer.next_id);
+    timer.next_id += 1;
+    timer.sleeps.insert(key, state.clone());
+    // Wake the timer thread if this is now the earliest deadline
+    if timer.sleeps.first_key_value().is_some_and(|(first, _)| *first == key) {
+        TIMER_CONDVAR.notify_one();
+    }
+    Sleep { key, state }
+}
+
+/// Completes after `duration` has elapsed. The wait happens on a shared background thread.
+pub fn sleep(duration: Duration) -> Sleep {
+    sleep_until(Instant::now() + duration)
+}
@@ -1,8 +1,10 @@
 pub mod adapter;
+pub mod advertiser;
 pub mod characteristic;
 pub mod descriptor;
 pub mod device;
 pub mod error;
+pub mod server;
 pub mod service;
 mod types;
 mod winver;
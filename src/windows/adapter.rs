@@ -18,13 +18,17 @@ use windows::Foundation::Collections::{IIterable, IVector};
 use windows::Foundation::TypedEventHandler;
 use windows::Storage::Streams::DataReader;
 
+use super::device::DeviceImpl;
 use super::types::StringVec;
 use super::winver::windows_version_above;
+use super::DeviceId;
+use crate::advertiser::{Advertisement, AdvertisingHandle};
 use crate::error::{Error, ErrorKind};
+use crate::server::{Application, GattServer};
 use crate::util::defer;
 use crate::{
-    AdapterEvent, AdvertisementData, AdvertisingDevice, BluetoothUuidExt, ConnectionEvent, Device, DeviceId,
-    ManufacturerData, Result, Uuid,
+    AdapterEvent, AdvertisementData, AdvertisingDevice, BluetoothUuidExt, ConnectionEvent, Device, ManufacturerData,
+    Result, ScanMode, ScanOptions, ScanTransport, Uuid,
 };
 
 #[derive(Default)]
@@ -261,6 +265,33 @@ impl AdapterImpl {
         &'a self,
         services: &'a [Uuid],
     ) -> Result<impl Stream<Item = AdvertisingDevice> + Send + Unpin + 'a> {
+        self.start_watchers(services, BluetoothLEScanningMode::Active)
+    }
+
+    /// Starts scanning for Bluetooth advertising packets using the provided options.
+    pub async fn scan_with_options(
+        &self,
+        options: ScanOptions,
+    ) -> Result<impl Stream<Item = AdvertisingDevice> + Send + Unpin + '_> {
+        let mode = match options.mode {
+            ScanMode::Active => BluetoothLEScanningMode::Active,
+            ScanMode::Passive => BluetoothLEScanningMode::Passive,
+        };
+        if options.transport == ScanTransport::BrEdr {
+            return Err(Error::new(
+                ErrorKind::NotSupported,
+                None,
+                "scanning for BR/EDR devices is not supported",
+            ));
+        }
+        Ok(options.filter(self.start_watchers(&options.services, mode)?))
+    }
+
+    fn start_watchers(
+        &self,
+        services: &[Uuid],
+        mode: BluetoothLEScanningMode,
+    ) -> Result<impl Stream<Item = AdvertisingDevice> + Send + Unpin> {
         let ext_api_available = windows_version_above(10, 0, 19041);
 
         let (sender, receiver) = futures_channel::mpsc::channel(16);
@@ -298,7 +329,7 @@ impl AdapterImpl {
 
         let build_watcher = |uuid: Option<Uuid>| {
             let watcher = BluetoothLEAdvertisementWatcher::new()?;
-            watcher.SetScanningMode(BluetoothLEScanningMode::Active)?;
+            watcher.SetScanningMode(mode)?;
             if ext_api_available {
                 watcher.SetAllowExtendedAdvertisements(true)?;
             }
@@ -399,7 +430,7 @@ impl AdapterImpl {
     ///
     /// Device connections are automatically managed by the OS. This method has no effect. Instead, a connection will
     /// automatically be established, if necessary, when methods on the device requiring a connection are called.
-    pub async fn connect_device(&self, _device: &Device) -> Result<()> {
+    pub async fn connect_device(&self, _device: &DeviceImpl) -> Result<()> {
         // Windows manages the device connection automatically
         Ok(())
     }
@@ -408,7 +439,7 @@ impl AdapterImpl {
     ///
     /// Device connections are automatically managed by the OS. This method has no effect. Instead, the connection will
     /// be closed only when the [`Device`] and all its child objects are dropped.
-    pub async fn disconnect_device(&self, _device: &Device) -> Result<()> {
+    pub async fn disconnect_device(&self, _device: &DeviceImpl) -> Result<()> {
         // Windows manages the device connection automatically
         Ok(())
     }
@@ -416,7 +447,7 @@ impl AdapterImpl {
     /// Monitors a device for connection/disconnection events.
     pub async fn device_connection_events<'a>(
         &'a self,
-        device: &'a Device,
+        device: &'a DeviceImpl,
     ) -> Result<impl Stream<Item = ConnectionEvent> + Send + Unpin + 'a> {
         let (mut sender, receiver) = futures_channel::mpsc::channel::<BluetoothConnectionStatus>(16);
 
@@ -433,11 +464,11 @@ impl AdapterImpl {
                 Ok(())
             });
 
-            device.0.inner.ConnectionStatusChanged(&handler)?
+            device.inner.ConnectionStatusChanged(&handler)?
         };
 
         let guard = defer(move || {
-            let _ = device.0.inner.RemoveConnectionStatusChanged(token);
+            let _ = device.inner.RemoveConnectionStatusChanged(token);
         });
 
         Ok(receiver.map(move |x| {
@@ -445,6 +476,20 @@ impl AdapterImpl {
             ConnectionEvent::from(x)
         }))
     }
+
+    /// Publishes the local GATT services described by `application`.
+    ///
+    /// Returns [ErrorKind::NotSupported].
+    pub async fn serve_gatt(&self, _application: Application) -> Result<GattServer> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// Starts sending `advertisement`.
+    ///
+    /// Returns [ErrorKind::NotSupported].
+    pub async fn advertise(&self, _advertisement: Advertisement) -> Result<AdvertisingHandle> {
+        Err(ErrorKind::NotSupported.into())
+    }
 }
 
 impl From<BluetoothConnectionStatus> for ConnectionEvent {
@@ -473,40 +518,52 @@ impl From<BluetoothLEAdvertisementReceivedEventArgs> for AdvertisementData {
     fn from(event_args: BluetoothLEAdvertisementReceivedEventArgs) -> Self {
         let is_connectable = event_args.IsConnectable().unwrap_or(false);
         let tx_power_level = event_args.TransmitPowerLevelInDBm().ok().and_then(|x| x.Value().ok());
-        let (local_name, manufacturer_data, services, service_data) = if let Ok(adv) = event_args.Advertisement() {
-            let local_name = adv
-                .LocalName()
-                .ok()
-                .and_then(|x| (!x.is_empty()).then(|| x.to_string_lossy()));
-            let manufacturer_data = adv
-                .ManufacturerData()
-                .and_then(|x| x.GetAt(0))
-                .and_then(|x| x.try_into())
-                .ok();
-
-            let services = adv
-                .ServiceUuids()
-                .map(|x| x.into_iter().map(|x| Uuid::from_u128(x.to_u128())).collect())
-                .unwrap_or_default();
-
-            let service_data = if let Ok(data_sections) = adv.DataSections() {
-                to_service_data(&data_sections).unwrap_or_default()
+        let (local_name, manufacturer_data_list, services, service_data, parsed) =
+            if let Ok(adv) = event_args.Advertisement() {
+                let local_name = adv
+                    .LocalName()
+                    .ok()
+                    .and_then(|x| (!x.is_empty()).then(|| x.to_string_lossy()));
+                let manufacturer_data_list = adv
+                    .ManufacturerData()
+                    .map(|x| x.into_iter().filter_map(|x| x.try_into().ok()).collect())
+                    .unwrap_or_default();
+
+                let services = adv
+                    .ServiceUuids()
+                    .map(|x| x.into_iter().map(|x| Uuid::from_u128(x.to_u128())).collect())
+                    .unwrap_or_default();
+
+                let (service_data, parsed) = if let Ok(data_sections) = adv.DataSections() {
+                    let parsed = to_raw(&data_sections)
+                        .ok()
+                        .and_then(|raw| AdvertisementData::from_raw(&raw).ok())
+                        .unwrap_or_default();
+                    (to_service_data(&data_sections).unwrap_or_default(), parsed)
+                } else {
+                    Default::default()
+                };
+
+                (local_name, manufacturer_data_list, services, service_data, parsed)
             } else {
-                Default::default()
+                (
+                    None,
+                    Vec::new(),
+                    Vec::new(),
+                    HashMap::new(),
+                    AdvertisementData::default(),
+                )
             };
 
-            (local_name, manufacturer_data, services, service_data)
-        } else {
-            (None, None, Vec::new(), HashMap::new())
-        };
-
         AdvertisementData {
             local_name,
-            manufacturer_data,
+            manufacturer_data: manufacturer_data_list.first().cloned(),
+            manufacturer_data_list,
             services,
             tx_power_level,
             is_connectable,
             service_data,
+            ..parsed
         }
     }
 }
@@ -557,3 +614,22 @@ fn to_service_data(
 
     Ok(service_data)
 }
+
+fn to_raw(data_sections: &IVector<BluetoothLEAdvertisementDataSection>) -> windows::core::Result<Vec<u8>> {
+    let mut raw = Vec::new();
+
+    for data in data_sections {
+        let buf = data.Data()?;
+        let len = buf.Length()? as usize;
+        let mut value = vec![0; len];
+        DataReader::FromBuffer(&buf)?.ReadBytes(value.as_mut_slice())?;
+
+        if let Ok(section_len) = u8::try_from(len + 1) {
+            raw.push(section_len);
+            raw.push(data.DataType()?);
+            raw.extend_from_slice(&value);
+        }
+    }
+
+    Ok(raw)
+}
@@ -1,18 +1,23 @@
+use std::time::Instant;
+
 use futures_core::Stream;
 use futures_lite::StreamExt;
 use tracing::{error, warn};
 use windows::Devices::Bluetooth::BluetoothCacheMode;
 use windows::Devices::Bluetooth::GenericAttributeProfile::{
-    GattCharacteristic, GattClientCharacteristicConfigurationDescriptorValue, GattValueChangedEventArgs,
-    GattWriteOption, GattWriteResult,
+    GattCharacteristic, GattClientCharacteristicConfigurationDescriptorValue, GattReliableWriteTransaction,
+    GattValueChangedEventArgs, GattWriteOption, GattWriteResult,
 };
 use windows::Foundation::{AsyncOperationCompletedHandler, TypedEventHandler};
 use windows::Storage::Streams::{DataReader, DataWriter};
 
 use super::error::check_communication_status;
+use super::DeviceId;
 use crate::error::ErrorKind;
 use crate::util::defer;
-use crate::{Characteristic, CharacteristicProperties, Descriptor, Error, Result, Uuid};
+use crate::{
+    Characteristic, CharacteristicProperties, Descriptor, Error, Notification, Result, SubscriptionKind, Uuid,
+};
 
 /// A Bluetooth GATT characteristic
 #[derive(Clone)]
@@ -63,7 +68,7 @@ impl std::fmt::Debug for CharacteristicImpl {
 
 impl Characteristic {
     pub(super) fn new(characteristic: GattCharacteristic) -> Self {
-        Characteristic(CharacteristicImpl { inner: characteristic })
+        Characteristic::from_platform(CharacteristicImpl { inner: characteristic })
     }
 }
 
@@ -78,6 +83,18 @@ impl CharacteristicImpl {
         Ok(Uuid::from_u128(self.inner.Uuid()?.to_u128()))
     }
 
+    /// The attribute handle of this GATT characteristic.
+    pub fn handle(&self) -> Result<u16> {
+        self.inner.AttributeHandle().map_err(Into::into)
+    }
+
+    /// The identifier of the device this GATT characteristic belongs to.
+    pub fn device_id(&self) -> Result<DeviceId> {
+        Ok(DeviceId(
+            self.inner.Service()?.Session()?.DeviceId()?.Id()?.to_os_string(),
+        ))
+    }
+
     /// The properties of this this GATT characteristic.
     ///
     /// Characteristic properties indicate which operations (e.g. read, write, notify, etc) may be performed on this
@@ -99,6 +116,20 @@ impl CharacteristicImpl {
         self.read_value(BluetoothCacheMode::Uncached).await
     }
 
+    /// Read the value of this characteristic from the device, starting at `offset`.
+    ///
+    /// An `offset` other than 0 returns [ErrorKind::NotSupported].
+    pub async fn read_with_offset(&self, offset: u16) -> Result<Vec<u8>> {
+        if offset != 0 {
+            return Err(Error::new(
+                ErrorKind::NotSupported,
+                None,
+                "reading at an offset is not supported on Windows",
+            ));
+        }
+        self.read().await
+    }
+
     async fn read_value(&self, cachemode: BluetoothCacheMode) -> Result<Vec<u8>> {
         let res = self.inner.ReadValueWithCacheModeAsync(cachemode)?.await?;
 
@@ -133,6 +164,35 @@ impl CharacteristicImpl {
         check_communication_status(res.Status()?, res.ProtocolError(), "writing characteristic")
     }
 
+    /// Write `value` to the value of this characteristic on the device, starting at `offset`.
+    ///
+    /// Windows uses prepared writes automatically for long values, but an `offset` other than 0 returns
+    /// [ErrorKind::NotSupported].
+    pub async fn write_long(&self, offset: u16, value: &[u8]) -> Result<()> {
+        if offset != 0 {
+            return Err(Error::new(
+                ErrorKind::NotSupported,
+                None,
+                "writing at an offset is not supported on Windows",
+            ));
+        }
+        self.write(value).await
+    }
+
+    /// Writes every value in `writes` in a single reliable write transaction.
+    pub async fn execute_reliable_write(writes: &[(&Self, &[u8])]) -> Result<()> {
+        let transaction = GattReliableWriteTransaction::new()?;
+        for (characteristic, value) in writes {
+            let writer = DataWriter::new()?;
+            writer.WriteBytes(value)?;
+            let buf = writer.DetachBuffer()?;
+            transaction.WriteValue(&characteristic.inner, &buf)?;
+        }
+        let res = transaction.CommitWithResultAsync()?.await?;
+
+        check_communication_status(res.Status()?, res.ProtocolError(), "committing reliable write")
+    }
+
     /// Get the maximum amount of data that can be written in a single packet for this characteristic.
     pub fn max_write_len(&self) -> Result<usize> {
         let mtu = self.inner.Service()?.Session()?.MaxPduSize()?;
@@ -145,21 +205,15 @@ impl CharacteristicImpl {
         self.max_write_len()
     }
 
-    /// Enables notification of value changes for this GATT characteristic.
-    ///
-    /// Returns a stream of values for the characteristic sent from the device.
-    pub async fn notify(&self) -> Result<impl Stream<Item = Result<Vec<u8>>> + Send + Unpin + '_> {
-        let props = self.properties().await?;
-        let value = if props.notify {
-            GattClientCharacteristicConfigurationDescriptorValue::Notify
-        } else if props.indicate {
-            GattClientCharacteristicConfigurationDescriptorValue::Indicate
-        } else {
-            return Err(Error::new(
-                ErrorKind::NotSupported,
-                None,
-                "characteristic does not support indications or notifications",
-            ));
+    /// Subscribes to notifications or indications of value changes for this GATT characteristic.
+    pub async fn subscribe(
+        &self,
+        kind: SubscriptionKind,
+    ) -> Result<impl Stream<Item = Result<Notification>> + Send + Unpin + '_> {
+        kind.check_supported(&self.properties().await?)?;
+        let (value, is_indication) = match kind {
+            SubscriptionKind::Notify => (GattClientCharacteristicConfigurationDescriptorValue::Notify, false),
+            SubscriptionKind::Indicate => (GattClientCharacteristicConfigurationDescriptorValue::Indicate, true),
         };
 
         let (mut sender, receiver) = futures_channel::mpsc::channel(16);
@@ -178,7 +232,12 @@ impl CharacteristicImpl {
                     Ok(data)
                 }
 
-                if let Err(err) = sender.try_send(get_value(event_args)) {
+                let notification = get_value(event_args).map(|value| Notification {
+                    value,
+                    is_indication,
+                    received_at: Instant::now(),
+                });
+                if let Err(err) = sender.try_send(notification) {
                     error!("Error sending characteristic value changed notification: {:?}", err);
                 }
 
@@ -236,6 +295,18 @@ impl CharacteristicImpl {
         }))
     }
 
+    /// Disables notifications and indications for this GATT characteristic.
+    pub async fn unsubscribe(&self) -> Result<()> {
+        let res = self
+            .inner
+            .WriteClientCharacteristicConfigurationDescriptorWithResultAsync(
+                GattClientCharacteristicConfigurationDescriptorValue::None,
+            )?
+            .await?;
+
+        check_communication_status(res.Status()?, res.ProtocolError(), "disabling notifications")
+    }
+
     /// Is the device currently sending notifications for this characteristic?
     pub async fn is_notifying(&self) -> Result<bool> {
         let res = self
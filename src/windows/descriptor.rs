@@ -3,7 +3,8 @@ use windows::Devices::Bluetooth::GenericAttributeProfile::GattDescriptor;
 use windows::Storage::Streams::{DataReader, DataWriter};
 
 use super::error::check_communication_status;
-use crate::{Descriptor, Result, Uuid};
+use crate::error::ErrorKind;
+use crate::{Descriptor, Error, Result, Uuid};
 
 /// A Bluetooth GATT descriptor
 #[derive(Clone, PartialEq, Eq)]
@@ -22,7 +23,7 @@ impl std::fmt::Debug for DescriptorImpl {
 
 impl Descriptor {
     pub(super) fn new(descriptor: GattDescriptor) -> Self {
-        Descriptor(DescriptorImpl { inner: descriptor })
+        Descriptor::from_platform(DescriptorImpl { inner: descriptor })
     }
 }
 
@@ -37,6 +38,11 @@ impl DescriptorImpl {
         Ok(Uuid::from_u128(self.inner.Uuid()?.to_u128()))
     }
 
+    /// The attribute handle of this GATT descriptor.
+    pub fn handle(&self) -> Result<u16> {
+        self.inner.AttributeHandle().map_err(Into::into)
+    }
+
     /// The cached value of this descriptor
     ///
     /// If the value has not yet been read, this method may either return an error or perform a read of the value.
@@ -49,6 +55,20 @@ impl DescriptorImpl {
         self.read_value(BluetoothCacheMode::Uncached).await
     }
 
+    /// Read the value of this descriptor from the device, starting at `offset`.
+    ///
+    /// An `offset` other than 0 returns [ErrorKind::NotSupported].
+    pub async fn read_with_offset(&self, offset: u16) -> Result<Vec<u8>> {
+        if offset != 0 {
+            return Err(Error::new(
+                ErrorKind::NotSupported,
+                None,
+                "reading at an offset is not supported on Windows",
+            ));
+        }
+        self.read().await
+    }
+
     async fn read_value(&self, cachemode: BluetoothCacheMode) -> Result<Vec<u8>> {
         let res = self.inner.ReadValueWithCacheModeAsync(cachemode)?.await?;
 
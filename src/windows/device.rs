@@ -1,27 +1,34 @@
 use std::pin::pin;
+use std::sync::{Arc, Mutex};
 
 use futures_channel::mpsc;
 use futures_core::Stream;
 use futures_lite::{future, StreamExt};
 use tracing::error;
 use windows::core::{GUID, HSTRING};
+use windows::Devices::Bluetooth::GenericAttributeProfile::GattSession;
 use windows::Devices::Bluetooth::{
-    BluetoothAddressType, BluetoothCacheMode, BluetoothConnectionStatus, BluetoothLEDevice,
+    BluetoothAddressType, BluetoothCacheMode, BluetoothConnectionStatus, BluetoothLEConnectionPhyInfo,
+    BluetoothLEDevice, BluetoothLEPreferredConnectionParameters, BluetoothLEPreferredConnectionParametersRequest,
+    BluetoothLEPreferredConnectionParametersRequestStatus,
 };
 use windows::Devices::Enumeration::{DeviceInformation, DevicePairingKinds, DevicePairingRequestedEventArgs};
 use windows::Foundation::TypedEventHandler;
 
 use super::error::{check_communication_status, check_pairing_status, check_unpairing_status};
+use super::service::ServiceImpl;
+use super::DeviceId;
 use crate::device::ServicesChanged;
 use crate::error::ErrorKind;
 use crate::pairing::{IoCapability, PairingAgent, Passkey};
 use crate::util::defer;
-use crate::{Device, DeviceId, Error, Result, Service, Uuid};
+use crate::{ConnectionPhy, ConnectionPriority, Device, Error, Phy, Result, Service, Uuid};
 
 /// A Bluetooth LE device
 #[derive(Clone)]
 pub struct DeviceImpl {
     pub(super) inner: BluetoothLEDevice,
+    connection_parameters: Arc<Mutex<Option<BluetoothLEPreferredConnectionParametersRequest>>>,
 }
 
 impl PartialEq for DeviceImpl {
@@ -62,16 +69,23 @@ impl Device {
         } else {
             BluetoothLEDevice::FromBluetoothAddressAsync(addr)?.await?
         };
-        Ok(Device(DeviceImpl { inner }))
+        Ok(Device::from_platform(DeviceImpl::new(inner)))
     }
 
     pub(super) async fn from_id(id: &HSTRING) -> windows::core::Result<Self> {
         let inner = BluetoothLEDevice::FromIdAsync(id)?.await?;
-        Ok(Device(DeviceImpl { inner }))
+        Ok(Device::from_platform(DeviceImpl::new(inner)))
     }
 }
 
 impl DeviceImpl {
+    fn new(inner: BluetoothLEDevice) -> Self {
+        DeviceImpl {
+            inner,
+            connection_parameters: Default::default(),
+        }
+    }
+
     /// This device's unique identifier
     pub fn id(&self) -> DeviceId {
         super::DeviceId(
@@ -160,7 +174,7 @@ impl DeviceImpl {
 
         let op = custom.PairAsync(pairing_kinds_supported)?;
 
-        let device = Device(self.clone());
+        let device = Device::from_platform(self.clone());
         let pairing_fut = pin!(async move {
             while let Some((event_args, deferral)) = rx.next().await {
                 match event_args.PairingKind()? {
@@ -261,7 +275,7 @@ impl DeviceImpl {
     ) -> Result<impl Stream<Item = Result<ServicesChanged>> + Send + Unpin + '_> {
         let (mut sender, receiver) = futures_channel::mpsc::channel(16);
         let token = self.inner.GattServicesChanged(&TypedEventHandler::new(move |_, _| {
-            if let Err(err) = sender.try_send(Ok(ServicesChanged(ServicesChangedImpl))) {
+            if let Err(err) = sender.try_send(Ok(ServicesChanged::from_platform(ServicesChangedImpl))) {
                 error!("Error sending service changed indication: {:?}", err);
             }
             Ok(())
@@ -285,13 +299,115 @@ impl DeviceImpl {
     pub async fn rssi(&self) -> Result<i16> {
         Err(ErrorKind::NotSupported.into())
     }
+
+    /// Monitors the signal strength of the device.
+    ///
+    /// Returns [ErrorKind::NotSupported].
+    pub async fn rssi_updates(&self) -> Result<impl Stream<Item = i16> + Send + Unpin + '_> {
+        Err::<futures_lite::stream::Empty<_>, _>(ErrorKind::NotSupported.into())
+    }
+
+    async fn session(&self) -> Result<GattSession> {
+        let session = GattSession::FromDeviceIdAsync(&self.inner.BluetoothDeviceId()?)?.await?;
+        Ok(session)
+    }
+
+    /// The ATT MTU of the connection.
+    pub async fn mtu(&self) -> Result<u16> {
+        let mtu = self.session().await?.MaxPduSize()?;
+        Ok(mtu)
+    }
+
+    /// Windows negotiates the largest supported MTU when connecting, so this returns the current MTU.
+    pub async fn request_mtu(&self, _mtu: u16) -> Result<u16> {
+        self.mtu().await
+    }
+
+    /// Monitors changes to the ATT MTU of the connection.
+    pub async fn mtu_updates(&self) -> Result<impl Stream<Item = u16> + Send + Unpin + '_> {
+        let session = self.session().await?;
+        let (mut sender, receiver) = futures_channel::mpsc::channel(16);
+        let token = session.MaxPduSizeChanged(&TypedEventHandler::new(move |session: &Option<GattSession>, _| {
+            if let Some(mtu) = session.as_ref().and_then(|x| x.MaxPduSize().ok()) {
+                if let Err(err) = sender.try_send(mtu) {
+                    error!("Error sending MTU update: {:?}", err);
+                }
+            }
+            Ok(())
+        }))?;
+
+        let guard = defer(move || {
+            if let Err(err) = session.RemoveMaxPduSizeChanged(token) {
+                error!("Error removing MTU changed handler: {:?}", err);
+            }
+        });
+
+        Ok(receiver.map(move |x| {
+            let _guard = &guard;
+            x
+        }))
+    }
+
+    /// Requests connection parameters matching `priority`.
+    ///
+    /// The request is kept until another priority is requested or the last clone of this device is dropped.
+    pub async fn set_connection_priority(&self, priority: ConnectionPriority) -> Result<()> {
+        let parameters = match priority {
+            ConnectionPriority::Balanced => BluetoothLEPreferredConnectionParameters::Balanced()?,
+            ConnectionPriority::High => BluetoothLEPreferredConnectionParameters::ThroughputOptimized()?,
+            ConnectionPriority::LowPower => BluetoothLEPreferredConnectionParameters::PowerOptimized()?,
+        };
+        let request = self.inner.RequestPreferredConnectionParameters(&parameters)?;
+        let status = request.Status()?;
+        if status != BluetoothLEPreferredConnectionParametersRequestStatus::Success {
+            return Err(Error::new(
+                ErrorKind::Other,
+                None,
+                format!("requesting connection parameters failed: {status:?}"),
+            ));
+        }
+
+        let previous = self.connection_parameters.lock().unwrap().replace(request);
+        if let Some(previous) = previous {
+            if let Err(err) = previous.Close() {
+                error!("Error closing connection parameters request: {:?}", err);
+            }
+        }
+        Ok(())
+    }
+
+    /// The PHYs used by the connection.
+    pub async fn phy(&self) -> Result<ConnectionPhy> {
+        fn convert(info: BluetoothLEConnectionPhyInfo) -> Result<Phy> {
+            if info.IsCodedPhy()? {
+                Ok(Phy::LeCoded)
+            } else if info.IsUncoded2MPhy()? {
+                Ok(Phy::Le2M)
+            } else {
+                Ok(Phy::Le1M)
+            }
+        }
+
+        let phy = self.inner.GetConnectionPhy()?;
+        Ok(ConnectionPhy {
+            tx: convert(phy.TransmitInfo()?)?,
+            rx: convert(phy.ReceiveInfo()?)?,
+        })
+    }
+
+    /// Sets the preferred PHYs for the connection.
+    ///
+    /// Returns [ErrorKind::NotSupported].
+    pub async fn set_preferred_phy(&self, _tx: Phy, _rx: Phy) -> Result<()> {
+        Err(ErrorKind::NotSupported.into())
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ServicesChangedImpl;
 
 impl ServicesChangedImpl {
-    pub fn was_invalidated(&self, _service: &Service) -> bool {
+    pub fn was_invalidated(&self, _service: &ServiceImpl) -> bool {
         true
     }
 }
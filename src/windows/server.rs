@@ -0,0 +1,34 @@
+use crate::{Result, SubscriptionKind};
+
+/// A subscription of a remote device to a local characteristic
+///
+/// Local GATT services are not supported on this platform, so this type has no values.
+pub enum NotifierImpl {}
+
+impl NotifierImpl {
+    /// Whether values are sent as notifications or indications.
+    pub fn kind(&self) -> SubscriptionKind {
+        match *self {}
+    }
+
+    /// Returns `true` if the remote device has unsubscribed.
+    pub fn is_stopped(&self) -> bool {
+        match *self {}
+    }
+
+    /// Waits until the remote device unsubscribes.
+    pub async fn stopped(&mut self) {
+        match *self {}
+    }
+
+    /// Sends `value` to the remote device.
+    pub async fn notify(&mut self, _value: &[u8]) -> Result<()> {
+        match *self {}
+    }
+}
+
+/// Local GATT services published on this platform
+///
+/// Local GATT services are not supported on this platform, so this type has no values.
+#[derive(Debug)]
+pub enum GattServerImpl {}
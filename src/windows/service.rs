@@ -65,7 +65,7 @@ impl std::fmt::Debug for ServiceImpl {
 
 impl Service {
     pub(super) fn new(service: GattDeviceService) -> Self {
-        Service(ServiceImpl { inner: service })
+        Service::from_platform(ServiceImpl { inner: service })
     }
 }
 
@@ -80,6 +80,18 @@ impl ServiceImpl {
         Ok(Uuid::from_u128(self.inner.Uuid()?.to_u128()))
     }
 
+    /// The attribute handle of this GATT service.
+    pub fn handle(&self) -> Result<u16> {
+        self.inner.AttributeHandle().map_err(Into::into)
+    }
+
+    /// The last attribute handle belonging to this GATT service.
+    ///
+    /// Returns [ErrorKind::NotSupported].
+    pub fn end_handle(&self) -> Result<u16> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
     /// Whether this is a primary service of the device.
     ///
     /// Returns [ErrorKind::NotSupported].
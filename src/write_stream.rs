@@ -0,0 +1,120 @@
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use std::time::{Duration, Instant};
+
+use futures_io::AsyncWrite;
+
+use crate::{Characteristic, Result};
+
+type WriteFuture<'a> = Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+
+/// A stream of bytes written to a characteristic with write without response operations
+///
+/// Created by [`Characteristic::write_stream`]. The data written to the [`AsyncWrite`] implementation is split into
+/// chunks of at most [`chunk_len`][Self::chunk_len] bytes which are each sent with
+/// [`Characteristic::write_without_response`]. Only one chunk is outstanding at a time, so writes are paced by the
+/// platform: on MacOS/iOS a chunk is only sent once the peripheral is ready to send another write without response,
+/// and on other platforms once the OS has accepted the previous chunk.
+///
+/// A chunk is accepted as soon as it has been started, so an error sending it is returned by the following call to
+/// [`poll_write`][AsyncWrite::poll_write], [`poll_flush`][AsyncWrite::poll_flush] or
+/// [`poll_close`][AsyncWrite::poll_close]. Flush the stream to make sure every chunk has been sent.
+pub struct WriteStream<'a> {
+    characteristic: &'a Characteristic,
+    chunk_len: usize,
+    pending: Option<(WriteFuture<'a>, usize)>,
+    bytes_written: u64,
+    started: Option<Instant>,
+}
+
+impl<'a> WriteStream<'a> {
+    pub(crate) fn new(characteristic: &'a Characteristic, chunk_len: usize) -> Self {
+        WriteStream {
+            characteristic,
+            chunk_len,
+            pending: None,
+            bytes_written: 0,
+            started: None,
+        }
+    }
+
+    /// The maximum number of bytes sent in a single write without response.
+    pub fn chunk_len(&self) -> usize {
+        self.chunk_len
+    }
+
+    /// The number of bytes sent to the device so far.
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+
+    /// The time elapsed since the first chunk was started.
+    pub fn elapsed(&self) -> Duration {
+        self.started.map(|started| started.elapsed()).unwrap_or_default()
+    }
+
+    /// The average throughput in bytes per second since the first chunk was started.
+    pub fn throughput(&self) -> f64 {
+        let elapsed = self.elapsed().as_secs_f64();
+        if elapsed > 0.0 {
+            self.bytes_written as f64 / elapsed
+        } else {
+            0.0
+        }
+    }
+
+    /// Waits for the outstanding chunk, if any, to be sent.
+    fn poll_pending(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        if let Some((write, len)) = &mut self.pending {
+            let len = *len;
+            let res = ready!(write.as_mut().poll(cx));
+            self.pending = None;
+            res.map_err(io::Error::other)?;
+            self.bytes_written += len as u64;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncWrite for WriteStream<'_> {
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        ready!(self.poll_pending(cx))?;
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        let len = buf.len().min(self.chunk_len);
+        let chunk = buf[..len].to_vec();
+        let characteristic = self.characteristic;
+        self.started.get_or_insert_with(Instant::now);
+        self.pending = Some((
+            Box::pin(async move { characteristic.write_without_response(&chunk).await }),
+            len,
+        ));
+        // Start sending the chunk now rather than on the next call
+        if let Poll::Ready(Err(err)) = self.poll_pending(cx) {
+            return Poll::Ready(Err(err));
+        }
+        Poll::Ready(Ok(len))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_pending(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_pending(cx)
+    }
+}
+
+impl std::fmt::Debug for WriteStream<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WriteStream")
+            .field("characteristic", &self.characteristic)
+            .field("chunk_len", &self.chunk_len)
+            .field("bytes_written", &self.bytes_written)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,134 @@
+use std::time::Duration;
+
+use bluest::advertisement::{AdFlags, AdStructure, AdStructures, AdType, ConnectionIntervalRange, LeRole};
+use bluest::btuuid::services;
+use bluest::error::ErrorKind;
+use bluest::{AdvertisementData, ManufacturerData, Uuid};
+
+#[test]
+fn parse_all_ad_types() {
+    #[rustfmt::skip]
+    let raw = [
+        0x02, 0x01, 0x06,
+        0x05, 0x03, 0x0f, 0x18, 0x0d, 0x18,
+        0x05, 0x08, b'T', b'e', b's', b't',
+        0x02, 0x0a, 0xf4,
+        0x05, 0x12, 0x06, 0x00, 0xff, 0xff,
+        0x03, 0x14, 0x12, 0x18,
+        0x05, 0x16, 0x0f, 0x18, 0x64, 0x01,
+        0x03, 0x19, 0xc1, 0x03,
+        0x03, 0x1a, 0x40, 0x00,
+        0x02, 0x1c, 0x02,
+        0x0d, 0x24, 0x17, b'/', b'/', b'e', b'x', b'a', b'm', b'p', b'l', b'e', b'.', b'c',
+        0x05, 0xff, 0x4c, 0x00, 0x01, 0x02,
+        0x04, 0xff, 0x59, 0x00, 0x03,
+        0x03, 0x3d, 0xaa, 0xbb,
+    ];
+
+    let structures = AdStructure::parse_all(&raw).unwrap();
+    assert_eq!(
+        structures,
+        vec![
+            AdStructure::Flags(AdFlags::LE_GENERAL_DISCOVERABLE | AdFlags::BR_EDR_NOT_SUPPORTED),
+            AdStructure::CompleteServiceUuids(vec![services::BATTERY, services::HEART_RATE]),
+            AdStructure::ShortenedLocalName("Test".to_string()),
+            AdStructure::TxPowerLevel(-12),
+            AdStructure::ConnectionIntervalRange(ConnectionIntervalRange {
+                min: Some(Duration::from_micros(7500)),
+                max: None,
+            }),
+            AdStructure::ServiceSolicitation(vec![services::HUMAN_INTERFACE_DEVICE]),
+            AdStructure::ServiceData(services::BATTERY, vec![0x64, 0x01]),
+            AdStructure::Appearance(0x03c1),
+            AdStructure::AdvertisingInterval(Duration::from_millis(40)),
+            AdStructure::LeRole(LeRole::PeripheralPreferred),
+            AdStructure::Uri("https://example.c".to_string()),
+            AdStructure::ManufacturerData(ManufacturerData {
+                company_id: 0x004c,
+                data: vec![0x01, 0x02],
+            }),
+            AdStructure::ManufacturerData(ManufacturerData {
+                company_id: 0x0059,
+                data: vec![0x03],
+            }),
+            AdStructure::Other(AdType::THREE_D_INFORMATION_DATA, vec![0xaa, 0xbb]),
+        ]
+    );
+
+    let adv_data = AdvertisementData::from_raw(&raw).unwrap();
+    assert_eq!(adv_data.raw.as_deref(), Some(&raw[..]));
+    assert_eq!(adv_data.local_name.as_deref(), Some("Test"));
+    assert!(adv_data.is_local_name_shortened);
+    assert_eq!(adv_data.services, vec![services::BATTERY, services::HEART_RATE]);
+    assert_eq!(adv_data.solicited_services, vec![services::HUMAN_INTERFACE_DEVICE]);
+    assert_eq!(adv_data.service_data[&services::BATTERY], vec![0x64, 0x01]);
+    assert_eq!(adv_data.tx_power_level, Some(-12));
+    assert_eq!(adv_data.appearance, Some(0x03c1));
+    assert_eq!(adv_data.le_role, Some(LeRole::PeripheralPreferred));
+    assert_eq!(adv_data.advertising_interval, Some(Duration::from_millis(40)));
+    assert_eq!(adv_data.uri.as_deref(), Some("https://example.c"));
+    assert_eq!(adv_data.manufacturer_data.map(|x| x.company_id), Some(0x004c));
+    assert_eq!(
+        adv_data.manufacturer_data_list,
+        vec![
+            ManufacturerData {
+                company_id: 0x004c,
+                data: vec![0x01, 0x02],
+            },
+            ManufacturerData {
+                company_id: 0x0059,
+                data: vec![0x03],
+            },
+        ]
+    );
+    assert!(adv_data.flags.unwrap().contains(AdFlags::LE_GENERAL_DISCOVERABLE));
+}
+
+#[test]
+fn parse_uuid_widths() {
+    #[rustfmt::skip]
+    let raw = [
+        0x05, 0x05, 0x78, 0x56, 0x34, 0x12,
+        0x11, 0x07,
+        0xfb, 0x34, 0x9b, 0x5f, 0x80, 0x00, 0x00, 0x80, 0x00, 0x10, 0x00, 0x00, 0x0d, 0x18, 0x00, 0x00,
+    ];
+
+    let structures = AdStructure::parse_all(&raw).unwrap();
+    assert_eq!(
+        structures,
+        vec![
+            AdStructure::CompleteServiceUuids(vec![Uuid::from_u128(0x12345678_0000_1000_8000_00805f9b34fb)]),
+            AdStructure::CompleteServiceUuids(vec![services::HEART_RATE]),
+        ]
+    );
+}
+
+#[test]
+fn complete_name_takes_precedence() {
+    let raw = [0x03, 0x08, b'A', b'B', 0x04, 0x09, b'A', b'B', b'C'];
+    let adv_data = AdvertisementData::from_raw(&raw).unwrap();
+    assert_eq!(adv_data.local_name.as_deref(), Some("ABC"));
+    assert!(!adv_data.is_local_name_shortened);
+}
+
+#[test]
+fn stops_at_zero_length() {
+    let raw = [0x02, 0x0a, 0x00, 0x00, 0x00, 0xff];
+    let structures: Vec<_> = AdStructures::new(&raw).collect::<Result<_, _>>().unwrap();
+    assert_eq!(structures, vec![(AdType::TX_POWER_LEVEL, &[0x00][..])]);
+}
+
+#[test]
+fn malformed_payloads() {
+    let err = AdStructure::parse_all(&[0x05, 0x09, b'A']).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidParameter);
+    assert!(AdvertisementData::from_raw(&[0x05, 0x09, b'A']).is_err());
+
+    let err = AdStructure::parse(AdType::COMPLETE_SERVICE_UUIDS_16, &[0x0f]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidParameter);
+
+    // Invalid contents of a single AD structure are skipped
+    let adv_data = AdvertisementData::from_raw(&[0x02, 0x03, 0x0f, 0x02, 0x0a, 0x04]).unwrap();
+    assert!(adv_data.services.is_empty());
+    assert_eq!(adv_data.tx_power_level, Some(4));
+}
@@ -19,6 +19,13 @@ async fn check_adapter_apis(adapter: Adapter) -> Result<Device> {
     let scan: Result<_> = assert_send(adapter.scan(&[btuuid::services::GENERIC_ACCESS])).await;
     let _adv: Option<AdvertisingDevice> = assert_send(scan?.next()).await;
 
+    let scan: Result<_> = assert_send(adapter.scan_with_options(ScanOptions::default())).await;
+    let _adv: Option<AdvertisingDevice> = assert_send(scan?.next()).await;
+
+    let tracker: Result<DeviceTracker<'_>> =
+        assert_send(adapter.track_devices(&[btuuid::services::GENERIC_ACCESS], TrackerOptions::default())).await;
+    let _event: Option<TrackerEvent> = assert_send(tracker?.next()).await;
+
     let discovery: Result<_> = assert_send(adapter.discover_devices(&[btuuid::services::GENERIC_ACCESS])).await;
     let _device: Option<Result<Device>> = assert_send(discovery?.next()).await;
 
@@ -31,6 +38,39 @@ async fn check_adapter_apis(adapter: Adapter) -> Result<Device> {
     let events: Result<_> = assert_send(adapter.device_connection_events(&device)).await;
     let _event: Option<ConnectionEvent> = assert_send(events?.next()).await;
 
+    let characteristic = server::LocalCharacteristic::new(btuuid::characteristics::DEVICE_NAME, Default::default())
+        .on_read(|_request: server::ReadRequest| async { Ok(Vec::new()) })
+        .on_write(|_request: server::WriteRequest| async { Ok(()) })
+        .on_subscribe(|mut notifier: server::Notifier| async move {
+            let _kind: SubscriptionKind = notifier.kind();
+            let _is_stopped: bool = notifier.is_stopped();
+            let _res: Result<()> = assert_send(notifier.notify(&[0u8])).await;
+            assert_send(notifier.stopped()).await;
+        });
+    let mut service = server::LocalService::new(btuuid::services::GENERIC_ACCESS);
+    service.characteristics.push(characteristic);
+    let application = server::Application {
+        services: vec![service],
+    };
+    let _server: Result<server::GattServer> = assert_send(adapter.serve_gatt(application)).await;
+
+    let mut advertisement = advertiser::Advertisement::default();
+    advertisement.local_name = Some("Bluest".to_string());
+    advertisement.services.push(btuuid::services::BATTERY);
+    let _handle: Result<advertiser::AdvertisingHandle> = assert_send(adapter.advertise(advertisement)).await;
+
+    #[cfg(feature = "l2cap")]
+    if let Ok(listener) = assert_send(adapter.listen_l2cap(None, true)).await {
+        let _psm: u16 = listener.psm();
+        if let Ok((_device, channel)) = assert_send(listener.accept()).await {
+            let _device: &Device = channel.device();
+            let _psm: u16 = channel.psm();
+            let _send_mtu: Result<u16> = channel.send_mtu();
+            let _recv_mtu: Result<u16> = channel.recv_mtu();
+            let _encrypted: Result<bool> = channel.is_encrypted();
+        }
+    }
+
     Ok(device)
 }
 
@@ -49,10 +89,31 @@ async fn check_device_apis(device: Device) -> Result<Service> {
     let _discovery: Result<Vec<Service>> =
         assert_send(device.discover_services_with_uuid(btuuid::services::GENERIC_ACCESS)).await;
     let services: Result<Vec<Service>> = assert_send(device.services()).await;
+    let _snapshot: Result<GattDatabase> = assert_send(device.gatt_snapshot(true)).await;
+    let _info: Result<DeviceInformation> = assert_send(device.device_information()).await;
 
     let _services_changed: Result<()> = assert_send(device.services_changed()).await;
 
     let _rssi: Result<i16> = assert_send(device.rssi()).await;
+    let rssi_updates: Result<_> = assert_send(device.rssi_updates()).await;
+    let _rssi: Option<i16> = assert_send(rssi_updates?.next()).await;
+
+    let _mtu: Result<u16> = assert_send(device.mtu()).await;
+    let _mtu: Result<u16> = assert_send(device.request_mtu(517)).await;
+    let mtu_updates: Result<_> = assert_send(device.mtu_updates()).await;
+    let _mtu: Option<u16> = assert_send(mtu_updates?.next()).await;
+    let _res: Result<()> = assert_send(device.set_connection_priority(ConnectionPriority::High)).await;
+    let _phy: Result<ConnectionPhy> = assert_send(device.phy()).await;
+    let _res: Result<()> = assert_send(device.set_preferred_phy(Phy::Le2M, Phy::Le2M)).await;
+
+    #[cfg(feature = "l2cap")]
+    {
+        let _channel: Result<L2capChannel> = assert_send(device.open_l2cap_channel(0x80, true)).await;
+        let mut options = L2capOptions::default();
+        options.mtu = Some(512);
+        options.mode = L2capMode::EnhancedCreditBased;
+        let _channel: Result<L2capChannel> = assert_send(device.open_l2cap_channel_with_options(0x80, &options)).await;
+    }
 
     Ok(services?.into_iter().next().unwrap())
 }
@@ -61,6 +122,8 @@ async fn check_service_apis(service: Service) -> Result<Characteristic> {
     let _uuid: Uuid = service.uuid();
     let _uuid: Result<Uuid> = assert_send(service.uuid_async()).await;
     let _is_primary: Result<bool> = assert_send(service.is_primary()).await;
+    let _handle: Result<u16> = service.handle();
+    let _end_handle: Result<u16> = service.end_handle();
 
     let _discovery: Result<Vec<Characteristic>> = assert_send(service.discover_characteristics()).await;
     let _discovery: Result<Vec<Characteristic>> =
@@ -77,32 +140,57 @@ async fn check_service_apis(service: Service) -> Result<Characteristic> {
 
 async fn check_characteristic_apis(characteristic: Characteristic) -> Result<Descriptor> {
     let _uuid: Uuid = characteristic.uuid();
+    let _handle: Result<u16> = characteristic.handle();
     let _uuid: Result<Uuid> = assert_send(characteristic.uuid_async()).await;
     let _props: Result<CharacteristicProperties> = assert_send(characteristic.properties()).await;
 
     let _value: Result<Vec<u8>> = assert_send(characteristic.value()).await;
     let _value: Result<Vec<u8>> = assert_send(characteristic.read()).await;
+    let _value: Result<Vec<u8>> = assert_send(characteristic.read_with_offset(0)).await;
+    let _value: Result<codec::BatteryLevel> = assert_send(characteristic.read_as()).await;
     let _res: Result<()> = assert_send(characteristic.write(&[0u8])).await;
     let _res: Result<()> = assert_send(characteristic.write_without_response(&[0u8])).await;
+    let _res: Result<()> = assert_send(characteristic.write_long(0, &[0u8])).await;
+    let _res: Result<()> = assert_send(characteristic.write_as(&codec::BatteryLevel { percent: 100 })).await;
+    let mut transaction = ReliableWriteTransaction::new();
+    let _res: Result<()> = transaction.write(&characteristic, &[0u8]);
+    let _res: Result<()> = assert_send(transaction.execute()).await;
+    ReliableWriteTransaction::new().abort();
     let _len: Result<usize> = assert_send(characteristic.max_write_len_async()).await;
+    let _stream: Result<WriteStream<'_>> = assert_send(characteristic.write_stream()).await;
 
     let notifications: Result<_> = assert_send(characteristic.notify()).await;
     let _notification: Option<Result<Vec<u8>>> = assert_send(notifications?.next()).await;
+    let notifications: Result<_> = assert_send(characteristic.notify_as::<codec::HeartRateMeasurement>()).await;
+    let _notification: Option<Result<codec::HeartRateMeasurement>> = assert_send(notifications?.next()).await;
+    let subscription: Result<_> = assert_send(characteristic.subscribe(SubscriptionKind::Indicate)).await;
+    let _notification: Option<Result<Notification>> = assert_send(subscription?.next()).await;
+    let subscription: Result<_> =
+        assert_send(characteristic.subscribe_with_options(SubscriptionKind::Notify, SubscriberOptions::default()))
+            .await;
+    let _notification: Option<Result<Notification>> = assert_send(subscription?.next()).await;
+    let _ended: Result<usize> = assert_send(characteristic.unsubscribe()).await;
     let _is_notifying: Result<bool> = assert_send(characteristic.is_notifying()).await;
 
     let _discovery: Result<Vec<Descriptor>> = assert_send(characteristic.discover_descriptors()).await;
     let descriptors: Result<Vec<Descriptor>> = assert_send(characteristic.descriptors()).await;
+    let _format: Result<Option<codec::PresentationFormat>> = assert_send(characteristic.presentation_format()).await;
+    let _description: Result<Option<String>> = assert_send(characteristic.user_description()).await;
 
     Ok(descriptors?.into_iter().next().unwrap())
 }
 
 async fn check_descriptor_apis(descriptor: Descriptor) -> Result<()> {
     let _uuid: Uuid = descriptor.uuid();
+    let _handle: Result<u16> = descriptor.handle();
     let _uuid: Result<Uuid> = assert_send(descriptor.uuid_async()).await;
 
     let _value: Result<Vec<u8>> = assert_send(descriptor.value()).await;
     let _value: Result<Vec<u8>> = assert_send(descriptor.read()).await;
+    let _value: Result<Vec<u8>> = assert_send(descriptor.read_with_offset(0)).await;
+    let _value: Result<codec::ExtendedProperties> = assert_send(descriptor.read_as()).await;
     let _res: Result<()> = assert_send(descriptor.write(&[0u8])).await;
+    let _res: Result<()> = assert_send(descriptor.write_as(&codec::ExtendedProperties::default())).await;
 
     Ok(())
 }
@@ -110,6 +198,8 @@ async fn check_descriptor_apis(descriptor: Descriptor) -> Result<()> {
 #[allow(unused)]
 async fn check_apis() -> Result<()> {
     let adapter: Result<Adapter> = assert_send(Adapter::default()).await;
+    #[cfg(target_os = "linux")]
+    let adapter = adapter.map(|adapter| adapter.with_acquire_io(true));
     let device = check_adapter_apis(adapter.unwrap()).await?;
     let service = check_device_apis(device).await?;
     let characteristic = check_service_apis(service).await?;
@@ -119,4 +209,15 @@ async fn check_apis() -> Result<()> {
     Ok(())
 }
 
+#[cfg(feature = "mock")]
+#[allow(unused)]
+async fn check_mock_apis() -> Result<()> {
+    let mock = mock::MockAdapter::new();
+    let handle = mock.add_peripheral(mock::MockPeripheral::new("Mock"))?;
+    let adapter = Adapter::with_mock(mock);
+    let _device: Result<Device> = assert_send(adapter.open_device(&handle.id())).await;
+
+    Ok(())
+}
+
 fn main() {}
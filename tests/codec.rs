@@ -0,0 +1,319 @@
+use bluest::codec::{
+    BatteryLevel, BloodPressureMeasurement, CrankRevolutions, CscMeasurement, DateTime,
+    EnvironmentalSensingMeasurement, EnvironmentalSensingTriggerSetting, ExtendedProperties, Float, FormatType,
+    GattCodec, HeartRateMeasurement, PnpId, PresentationFormat, PressureUnit, RscMeasurement, SFloat, SystemId,
+    TemperatureMeasurement, TemperatureUnit, ValidRange, WheelRevolutions,
+};
+use bluest::error::ErrorKind;
+
+fn round_trip<T: GattCodec + PartialEq + std::fmt::Debug>(raw: &[u8], expected: T) {
+    let value = T::decode(raw).unwrap();
+    assert_eq!(value, expected);
+    assert_eq!(value.encode().unwrap(), raw);
+}
+
+#[test]
+fn primitives() {
+    round_trip(&[0x34, 0x12], 0x1234u16);
+    round_trip(&[0xfe, 0xff, 0xff, 0xff], -2i32);
+    round_trip(b"Bluest", "Bluest".to_string());
+    round_trip(&[1, 2, 3], vec![1u8, 2, 3]);
+
+    assert_eq!(u16::decode(&[1]).unwrap_err().kind(), ErrorKind::InvalidParameter);
+    assert_eq!(String::decode(&[0xff]).unwrap_err().kind(), ErrorKind::InvalidParameter);
+}
+
+#[test]
+fn floats() {
+    assert_eq!(SFloat::new(365, -1).to_f64(), 36.5);
+    assert_eq!(SFloat::new(0x07fe, 0).to_f64(), f64::INFINITY);
+    assert_eq!(SFloat::new(-0x07fe, 0).to_f64(), f64::NEG_INFINITY);
+    assert!(SFloat::NAN.to_f64().is_nan());
+    assert!(Float::NAN.to_f64().is_nan());
+    assert_eq!(Float::new(-1234, -2).to_f64(), -12.34);
+
+    let mut measurement = BloodPressureMeasurement::decode(&[0, 0x78, 0xf0, 0x50, 0, 0x5d, 0]).unwrap();
+    assert_eq!(measurement.systolic, SFloat::new(120, -1));
+    measurement.systolic = SFloat::new(4096, 0);
+    assert_eq!(measurement.encode().unwrap_err().kind(), ErrorKind::InvalidParameter);
+}
+
+#[test]
+fn battery_level() {
+    round_trip(&[87], BatteryLevel { percent: 87 });
+    assert_eq!(
+        BatteryLevel::decode(&[101]).unwrap_err().kind(),
+        ErrorKind::InvalidParameter
+    );
+    assert_eq!(
+        BatteryLevel::decode(&[]).unwrap_err().kind(),
+        ErrorKind::InvalidParameter
+    );
+}
+
+#[test]
+fn heart_rate_measurement() {
+    round_trip(
+        &[0x00, 0x48],
+        HeartRateMeasurement {
+            heart_rate: 72,
+            ..Default::default()
+        },
+    );
+    round_trip(
+        &[0x1d, 0x2c, 0x01, 0x10, 0x00, 0x00, 0x02, 0x00, 0x01],
+        HeartRateMeasurement {
+            heart_rate: 300,
+            sensor_contact: Some(false),
+            energy_expended: Some(16),
+            rr_intervals: vec![512, 256],
+        },
+    );
+    assert_eq!(
+        HeartRateMeasurement::decode(&[0x01, 0x48]).unwrap_err().kind(),
+        ErrorKind::InvalidParameter
+    );
+}
+
+#[test]
+fn temperature_measurement() {
+    round_trip(
+        &[
+            0x06, 0x6d, 0x01, 0x00, 0xff, 0xea, 0x07, 0x0a, 0x12, 0x08, 0x1e, 0x00, 0x02,
+        ],
+        TemperatureMeasurement {
+            temperature: Float::new(365, -1),
+            unit: TemperatureUnit::Celsius,
+            timestamp: Some(DateTime {
+                year: 2026,
+                month: 10,
+                day: 18,
+                hours: 8,
+                minutes: 30,
+                seconds: 0,
+            }),
+            temperature_type: Some(2),
+        },
+    );
+    let fahrenheit = TemperatureMeasurement::decode(&[0x01, 0xb1, 0x03, 0x00, 0xff]).unwrap();
+    assert_eq!(fahrenheit.unit, TemperatureUnit::Fahrenheit);
+    assert_eq!(fahrenheit.temperature.to_f64(), 94.5);
+}
+
+#[test]
+fn csc_measurement() {
+    round_trip(
+        &[0x03, 0x10, 0x00, 0x00, 0x00, 0x00, 0x04, 0x05, 0x00, 0x00, 0x08],
+        CscMeasurement {
+            wheel: Some(WheelRevolutions {
+                cumulative_revolutions: 16,
+                last_event_time: 1024,
+            }),
+            crank: Some(CrankRevolutions {
+                cumulative_revolutions: 5,
+                last_event_time: 2048,
+            }),
+        },
+    );
+    round_trip(
+        &[0x02, 0x05, 0x00, 0x00, 0x08],
+        CscMeasurement {
+            wheel: None,
+            crank: Some(CrankRevolutions {
+                cumulative_revolutions: 5,
+                last_event_time: 2048,
+            }),
+        },
+    );
+}
+
+#[test]
+fn rsc_measurement() {
+    round_trip(
+        &[0x07, 0x00, 0x03, 0xaa, 0x78, 0x00, 0xe8, 0x03, 0x00, 0x00],
+        RscMeasurement {
+            speed: 768,
+            cadence: 170,
+            stride_length: Some(120),
+            total_distance: Some(1000),
+            is_running: true,
+        },
+    );
+}
+
+#[test]
+fn pnp_id() {
+    round_trip(
+        &[0x01, 0x4c, 0x00, 0x34, 0x12, 0x01, 0x00],
+        PnpId {
+            vendor_id_source: 1,
+            vendor_id: 0x004c,
+            product_id: 0x1234,
+            product_version: 1,
+        },
+    );
+}
+
+#[test]
+fn system_id() {
+    round_trip(
+        &[0x05, 0x04, 0x03, 0x02, 0x01, 0xcc, 0xbb, 0xaa],
+        SystemId {
+            manufacturer_identifier: 0x01_0203_0405,
+            organizationally_unique_identifier: 0xaa_bbcc,
+        },
+    );
+}
+
+#[test]
+fn blood_pressure_measurement() {
+    round_trip(
+        &[
+            0x1f, 0x10, 0xf0, 0x0b, 0xf0, 0x0d, 0xf0, 0xea, 0x07, 0x0a, 0x12, 0x08, 0x1e, 0x00, 0x48, 0x00, 0x01, 0x04,
+            0x00,
+        ],
+        BloodPressureMeasurement {
+            systolic: SFloat::new(16, -1),
+            diastolic: SFloat::new(11, -1),
+            mean_arterial_pressure: SFloat::new(13, -1),
+            unit: PressureUnit::Kilopascals,
+            timestamp: Some(DateTime {
+                year: 2026,
+                month: 10,
+                day: 18,
+                hours: 8,
+                minutes: 30,
+                seconds: 0,
+            }),
+            pulse_rate: Some(SFloat::new(72, 0)),
+            user_id: Some(1),
+            measurement_status: Some(0x0004),
+        },
+    );
+}
+
+#[test]
+fn presentation_format() {
+    let format = PresentationFormat {
+        format: FormatType::UINT16,
+        exponent: -2,
+        unit: 0x2728,
+        namespace: 1,
+        description: 0x0106,
+    };
+    round_trip(&[0x06, 0xfe, 0x28, 0x27, 0x01, 0x06, 0x01], format);
+    assert_eq!(format.format_value(&[0x39, 0x30]).unwrap(), "123.45 V");
+    assert_eq!(format.format_value(&[0x05, 0x00]).unwrap(), "0.05 V");
+    assert_eq!(
+        format.format_value(&[0x05]).unwrap_err().kind(),
+        ErrorKind::InvalidParameter
+    );
+
+    let format = |format, exponent, unit| PresentationFormat {
+        format,
+        exponent,
+        unit,
+        namespace: 0,
+        description: 0,
+    };
+    assert_eq!(
+        format(FormatType::SINT8, 0, 0x2700).format_value(&[0xfb]).unwrap(),
+        "-5"
+    );
+    assert_eq!(
+        format(FormatType::SINT8, -1, 0x272f).format_value(&[0xfb]).unwrap(),
+        "-0.5 °C"
+    );
+    assert_eq!(
+        format(FormatType::SINT24, 0, 0x2700)
+            .format_value(&[0xff, 0xff, 0xff])
+            .unwrap(),
+        "-1"
+    );
+    assert_eq!(
+        format(FormatType::SINT12, 0, 0x2700)
+            .format_value(&[0xff, 0x0f])
+            .unwrap(),
+        "-1"
+    );
+    assert_eq!(
+        format(FormatType::UINT8, 3, 0x2701).format_value(&[0x02]).unwrap(),
+        "2000 m"
+    );
+    assert_eq!(
+        format(FormatType::UINT8, 0, 0x27ad).format_value(&[0x64]).unwrap(),
+        "100 %"
+    );
+    assert_eq!(
+        format(FormatType::UINT8, 0, 0x2722).format_value(&[0x32]).unwrap(),
+        "50 Hz"
+    );
+    assert_eq!(
+        format(FormatType::UINT8, 0, 0x2724).format_value(&[0x0a]).unwrap(),
+        "10 Pa"
+    );
+    assert_eq!(
+        format(FormatType::UINT8, 0, 0x272a).format_value(&[0x64]).unwrap(),
+        "100 Ω"
+    );
+    assert_eq!(
+        format(FormatType::BOOLEAN, 0, 0x2700).format_value(&[0x01]).unwrap(),
+        "true"
+    );
+    assert_eq!(
+        format(FormatType::SFLOAT, 0, 0x2700)
+            .format_value(&[0x6d, 0xf1])
+            .unwrap(),
+        "36.5"
+    );
+    assert_eq!(
+        format(FormatType::UTF8S, 0, 0x2700).format_value(b"abc").unwrap(),
+        "abc"
+    );
+    assert_eq!(
+        format(FormatType::STRUCT, 0, 0xffff)
+            .format_value(&[0x01, 0xab])
+            .unwrap(),
+        "01 ab"
+    );
+}
+
+#[test]
+fn descriptors() {
+    round_trip(
+        &[0x03, 0x00],
+        ExtendedProperties {
+            reliable_write: true,
+            writable_auxiliaries: true,
+        },
+    );
+    round_trip(
+        &[0x00, 0x00, 0x10, 0x00],
+        ValidRange {
+            lower: vec![0x00, 0x00],
+            upper: vec![0x10, 0x00],
+        },
+    );
+    assert_eq!(
+        ValidRange::decode(&[0x00, 0x00, 0x10]).unwrap_err().kind(),
+        ErrorKind::InvalidParameter
+    );
+    round_trip(
+        &[0x00, 0x00, 0x02, 0x3c, 0x00, 0x00, 0x0a, 0x00, 0x00, 0x01, 0x04],
+        EnvironmentalSensingMeasurement {
+            flags: 0,
+            sampling_function: 2,
+            measurement_period: 60,
+            update_interval: 10,
+            application: 1,
+            measurement_uncertainty: 4,
+        },
+    );
+    round_trip(
+        &[0x04, 0x10, 0x00],
+        EnvironmentalSensingTriggerSetting {
+            condition: 4,
+            operand: vec![0x10, 0x00],
+        },
+    );
+}
@@ -0,0 +1,989 @@
+#![cfg(feature = "mock")]
+
+use std::time::{Duration, Instant};
+
+use bluest::advertiser::Advertisement;
+use bluest::btuuid::{characteristics, descriptors, services};
+use bluest::codec::{BatteryLevel, FormatType, PresentationFormat};
+use bluest::error::{AttError, ErrorKind};
+use bluest::mock::{
+    MockAdapter, MockCharacteristic, MockDescriptor, MockPeripheral, MockPeripheralHandle, MockService,
+};
+use bluest::server::{Application, LocalCharacteristic, LocalDescriptor, LocalService};
+use bluest::{
+    Adapter, AdapterEvent, AdvertisementData, Characteristic, CharacteristicProperties, ConnectionEvent, ConnectionPhy,
+    ConnectionPriority, Device, GattDifference, LagPolicy, ManufacturerData, Phy, ReliableWriteTransaction,
+    ScanOptions, ScanTransport, SubscriberOptions, SubscriptionKind, TrackerEvent, TrackerOptions,
+};
+use futures_lite::{AsyncWriteExt, StreamExt};
+
+fn battery_peripheral() -> MockPeripheral {
+    let mut properties = CharacteristicProperties::default();
+    properties.read = true;
+    properties.notify = true;
+
+    let mut level = MockCharacteristic::new(characteristics::BATTERY_LEVEL, properties, vec![100]);
+    level.descriptors.push(MockDescriptor::new(
+        descriptors::CHARACTERISTIC_USER_DESCRIPTION,
+        b"Battery level".to_vec(),
+    ));
+
+    let mut service = MockService::new(services::BATTERY);
+    service.characteristics.push(level);
+
+    let mut peripheral = MockPeripheral::new("Battery");
+    peripheral.adv_data.services.push(services::BATTERY);
+    peripheral.services.push(service);
+    peripheral
+}
+
+async fn connect(mock: &MockAdapter) -> (Adapter, Device, MockPeripheralHandle) {
+    let handle = mock.add_peripheral(battery_peripheral()).unwrap();
+    let adapter = Adapter::with_mock(mock.clone());
+    let device = adapter.open_device(&handle.id()).await.unwrap();
+    adapter.connect_device(&device).await.unwrap();
+    (adapter, device, handle)
+}
+
+async fn battery_level(device: &Device) -> Characteristic {
+    let service = device.discover_services_with_uuid(services::BATTERY).await.unwrap();
+    let characteristics = service[0]
+        .discover_characteristics_with_uuid(characteristics::BATTERY_LEVEL)
+        .await
+        .unwrap();
+    characteristics[0].clone()
+}
+
+#[tokio::test]
+async fn scan_filters_by_service() {
+    let mock = MockAdapter::new();
+    mock.add_peripheral(MockPeripheral::new("Other")).unwrap();
+    let handle = mock.add_peripheral(battery_peripheral()).unwrap();
+
+    let adapter = Adapter::with_mock(mock);
+    let mut scan = adapter.scan(&[services::BATTERY]).await.unwrap();
+    let adv = scan.next().await.unwrap();
+    assert_eq!(adv.device.id(), handle.id());
+    assert_eq!(adv.adv_data.local_name.as_deref(), Some("Battery"));
+    assert_eq!(adv.rssi, Some(-60));
+
+    handle.set_rssi(Some(-40));
+    let adv = scan.next().await.unwrap();
+    assert_eq!(adv.rssi, Some(-40));
+}
+
+#[tokio::test]
+async fn scan_with_options() {
+    let mock = MockAdapter::new();
+    let mut weak = MockPeripheral::new("Sensor weak");
+    weak.rssi = Some(-90);
+    mock.add_peripheral(weak).unwrap();
+    mock.add_peripheral(MockPeripheral::new("Other")).unwrap();
+    let mut vendor = MockPeripheral::new("Sensor vendor");
+    vendor.adv_data.manufacturer_data_list.push(ManufacturerData {
+        company_id: 0x1234,
+        data: vec![1],
+    });
+    mock.add_peripheral(vendor).unwrap();
+    let strong = mock.add_peripheral(MockPeripheral::new("Sensor strong")).unwrap();
+
+    let adapter = Adapter::with_mock(mock.clone());
+    let mut options = ScanOptions::default();
+    options.name_prefix = Some("Sensor".to_string());
+    options.rssi_threshold = Some(-70);
+    let mut scan = adapter.scan_with_options(options).await.unwrap();
+    assert_eq!(
+        scan.next().await.unwrap().adv_data.local_name.as_deref(),
+        Some("Sensor vendor")
+    );
+    assert_eq!(scan.next().await.unwrap().device.id(), strong.id());
+
+    // Duplicates are suppressed
+    strong.set_rssi(Some(-50));
+    let late = mock.add_peripheral(MockPeripheral::new("Sensor late")).unwrap();
+    assert_eq!(scan.next().await.unwrap().device.id(), late.id());
+    drop(scan);
+
+    let mut options = ScanOptions::default();
+    options.company_id = Some(0x1234);
+    let mut scan = adapter.scan_with_options(options).await.unwrap();
+    assert_eq!(
+        scan.next().await.unwrap().adv_data.local_name.as_deref(),
+        Some("Sensor vendor")
+    );
+
+    let mut options = ScanOptions::default();
+    options.transport = ScanTransport::BrEdr;
+    let err = adapter.scan_with_options(options).await.err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::NotSupported);
+}
+
+#[tokio::test]
+async fn device_tracker() {
+    let mock = MockAdapter::new();
+    let mut peripheral = MockPeripheral::new("Sens");
+    peripheral.adv_data.is_local_name_shortened = true;
+    let handle = mock.add_peripheral(peripheral).unwrap();
+
+    let adapter = Adapter::with_mock(mock);
+    let mut options = TrackerOptions::default();
+    options.lost_timeout = Duration::from_millis(100);
+    options.rssi_smoothing = 0.5;
+    let mut tracker = adapter.track_devices(&[], options).await.unwrap();
+
+    let device = match tracker.next().await.unwrap() {
+        TrackerEvent::Appeared(device) => device,
+        event => panic!("unexpected event {event:?}"),
+    };
+    assert_eq!(device.device.id(), handle.id());
+    assert_eq!(device.first_seen, device.last_seen);
+    assert_eq!(device.smoothed_rssi, Some(-60.0));
+
+    // A scan response is merged with the advertisement
+    let scan_response = AdvertisementData {
+        local_name: Some("Sensor".to_string()),
+        manufacturer_data_list: vec![ManufacturerData {
+            company_id: 0x1234,
+            data: vec![1],
+        }],
+        ..Default::default()
+    };
+    handle.set_adv_data(scan_response);
+    handle.set_rssi(Some(-40));
+    tracker.next().await.unwrap();
+    let device = match tracker.next().await.unwrap() {
+        TrackerEvent::Updated(device) => device,
+        event => panic!("unexpected event {event:?}"),
+    };
+    assert_eq!(device.adv_data.local_name.as_deref(), Some("Sensor"));
+    assert!(!device.adv_data.is_local_name_shortened);
+    assert_eq!(device.adv_data.manufacturer_data.map(|x| x.company_id), Some(0x1234));
+    assert_eq!(device.rssi, Some(-40));
+    assert_eq!(device.smoothed_rssi, Some(-50.0));
+    assert_eq!(
+        device.rssi_history.iter().map(|x| x.1).collect::<Vec<_>>(),
+        vec![-60, -60, -40]
+    );
+    assert_eq!(tracker.devices().count(), 1);
+
+    let start = Instant::now();
+    match tracker.next().await.unwrap() {
+        TrackerEvent::Lost(device) => assert_eq!(device.device.id(), handle.id()),
+        event => panic!("unexpected event {event:?}"),
+    }
+    assert!(start.elapsed() >= Duration::from_millis(50));
+    assert!(tracker.device(&handle.id()).is_none());
+}
+
+#[tokio::test]
+async fn rssi_updates() {
+    let mock = MockAdapter::new();
+    let (_adapter, device, handle) = connect(&mock).await;
+    let mut updates = device.rssi_updates().await.unwrap();
+
+    handle.set_rssi(Some(-60));
+    handle.set_rssi(Some(-55));
+    assert_eq!(updates.next().await, Some(-60));
+    assert_eq!(updates.next().await, Some(-55));
+    assert_eq!(device.rssi().await.unwrap(), -55);
+}
+
+#[tokio::test]
+async fn connection_parameters() {
+    let mock = MockAdapter::new();
+    let mut peripheral = battery_peripheral();
+    peripheral.mtu = 247;
+    let handle = mock.add_peripheral(peripheral).unwrap();
+    let adapter = Adapter::with_mock(mock);
+    let device = adapter.open_device(&handle.id()).await.unwrap();
+    assert_eq!(device.mtu().await.unwrap_err().kind(), ErrorKind::NotConnected);
+
+    adapter.connect_device(&device).await.unwrap();
+    assert_eq!(device.mtu().await.unwrap(), 247);
+    let mut mtu_updates = device.mtu_updates().await.unwrap();
+    assert_eq!(device.request_mtu(100).await.unwrap(), 100);
+    assert_eq!(device.request_mtu(517).await.unwrap(), 247);
+    handle.exchange_mtu(185).unwrap();
+    assert_eq!(mtu_updates.next().await, Some(100));
+    assert_eq!(mtu_updates.next().await, Some(247));
+    assert_eq!(mtu_updates.next().await, Some(185));
+    let level = battery_level(&device).await;
+    assert_eq!(level.max_write_len_async().await.unwrap(), 182);
+
+    device.set_connection_priority(ConnectionPriority::High).await.unwrap();
+    assert_eq!(handle.connection_priority(), ConnectionPriority::High);
+
+    assert_eq!(device.phy().await.unwrap(), ConnectionPhy::default());
+    device.set_preferred_phy(Phy::Le2M, Phy::LeCoded).await.unwrap();
+    let phy = device.phy().await.unwrap();
+    assert_eq!((phy.tx, phy.rx), (Phy::Le2M, Phy::LeCoded));
+
+    // Reconnecting renegotiates every parameter
+    handle.disconnect();
+    assert_eq!(mtu_updates.next().await, None);
+    adapter.connect_device(&device).await.unwrap();
+    assert_eq!(device.mtu().await.unwrap(), 247);
+    assert_eq!(handle.connection_priority(), ConnectionPriority::Balanced);
+}
+
+#[tokio::test]
+async fn read_write_and_descriptors() {
+    let mock = MockAdapter::new();
+    let (_adapter, device, handle) = connect(&mock).await;
+    assert!(handle.is_connected());
+    assert_eq!(device.name().unwrap(), "Battery");
+
+    let level = battery_level(&device).await;
+    assert_eq!(level.read().await.unwrap(), vec![100]);
+    let err = level.write(&[1]).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Protocol(AttError::WRITE_NOT_PERMITTED));
+    assert_eq!(level.max_write_len().unwrap(), 20);
+
+    handle
+        .set_characteristic_value(characteristics::BATTERY_LEVEL, &[42])
+        .unwrap();
+    assert_eq!(level.read().await.unwrap(), vec![42]);
+
+    let descriptors = level.discover_descriptors().await.unwrap();
+    assert_eq!(descriptors.len(), 1);
+    assert_eq!(descriptors[0].read().await.unwrap(), b"Battery level");
+}
+
+async fn writable_characteristics(mock: &MockAdapter) -> (Device, MockPeripheralHandle, Vec<Characteristic>) {
+    let mut properties = CharacteristicProperties::default();
+    properties.read = true;
+    properties.write = true;
+    properties.extended_properties = true;
+    properties.reliable_write = true;
+
+    let mut service = MockService::new(services::DEVICE_INFORMATION);
+    for uuid in [
+        characteristics::MODEL_NUMBER_STRING,
+        characteristics::SERIAL_NUMBER_STRING,
+    ] {
+        service
+            .characteristics
+            .push(MockCharacteristic::new(uuid, properties, b"hello".to_vec()));
+    }
+    properties.reliable_write = false;
+    service.characteristics.push(MockCharacteristic::new(
+        characteristics::FIRMWARE_REVISION_STRING,
+        properties,
+        b"1.0".to_vec(),
+    ));
+    let mut peripheral = MockPeripheral::new("Writable");
+    peripheral.services.push(service);
+
+    let handle = mock.add_peripheral(peripheral).unwrap();
+    let adapter = Adapter::with_mock(mock.clone());
+    let device = adapter.open_device(&handle.id()).await.unwrap();
+    adapter.connect_device(&device).await.unwrap();
+    let characteristics = device.discover_services().await.unwrap()[0]
+        .discover_characteristics()
+        .await
+        .unwrap();
+    (device, handle, characteristics)
+}
+
+#[tokio::test]
+async fn write_long() {
+    let mock = MockAdapter::new();
+    let (_device, _handle, characteristics) = writable_characteristics(&mock).await;
+    let model = &characteristics[0];
+
+    model.write_long(5, b" world").await.unwrap();
+    assert_eq!(model.read().await.unwrap(), b"hello world");
+    model.write_long(0, &[b'x'; 100]).await.unwrap();
+    assert_eq!(model.read().await.unwrap(), vec![b'x'; 100]);
+
+    let err = model.write_long(101, b"!").await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Protocol(AttError::INVALID_OFFSET));
+    let err = model.write_long(100, &[0; 413]).await.unwrap_err();
+    assert_eq!(
+        err.kind(),
+        ErrorKind::Protocol(AttError::INVALID_ATTRIBUTE_VALUE_LENGTH)
+    );
+}
+
+#[tokio::test]
+async fn typed_values() {
+    let mock = MockAdapter::new();
+    let (_adapter, device, handle) = connect(&mock).await;
+    let level = battery_level(&device).await;
+    assert_eq!(
+        level.read_as::<BatteryLevel>().await.unwrap(),
+        BatteryLevel { percent: 100 }
+    );
+
+    let mut levels = level.notify_as::<BatteryLevel>().await.unwrap();
+    handle.notify(characteristics::BATTERY_LEVEL, &[101]).unwrap();
+    handle.notify(characteristics::BATTERY_LEVEL, &[99]).unwrap();
+    let err = levels.next().await.unwrap().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidParameter);
+    assert_eq!(levels.next().await.unwrap().unwrap(), BatteryLevel { percent: 99 });
+
+    let (_device, _handle, characteristics) = writable_characteristics(&mock).await;
+    let model = &characteristics[0];
+    model.write_as(&"Bluest".to_string()).await.unwrap();
+    assert_eq!(model.read_as::<String>().await.unwrap(), "Bluest");
+}
+
+#[tokio::test]
+async fn device_information() {
+    let mock = MockAdapter::new();
+    let (_adapter, device, handle) = connect(&mock).await;
+
+    let mut properties = CharacteristicProperties::default();
+    properties.read = true;
+    let mut services = vec![MockService::new(services::DEVICE_INFORMATION)];
+    for (uuid, value) in [
+        (characteristics::MANUFACTURER_NAME_STRING, b"Bluest\0\0".to_vec()),
+        (characteristics::MODEL_NUMBER_STRING, b"M1".to_vec()),
+        (characteristics::PNP_ID, vec![0x01, 0x4c, 0x00, 0x34, 0x12, 0x01, 0x00]),
+        (
+            characteristics::SYSTEM_ID,
+            vec![0x05, 0x04, 0x03, 0x02, 0x01, 0xcc, 0xbb, 0xaa],
+        ),
+    ] {
+        services[0]
+            .characteristics
+            .push(MockCharacteristic::new(uuid, properties, value));
+    }
+    handle.set_services(services).unwrap();
+
+    let info = device.device_information().await.unwrap();
+    assert_eq!(info.manufacturer_name.as_deref(), Some("Bluest"));
+    assert_eq!(info.model_number.as_deref(), Some("M1"));
+    assert_eq!(info.serial_number, None);
+    assert_eq!(info.firmware_revision, None);
+    let pnp_id = info.pnp_id.unwrap();
+    assert_eq!((pnp_id.vendor_id, pnp_id.product_id), (0x004c, 0x1234));
+    let system_id = info.system_id.unwrap();
+    assert_eq!(system_id.manufacturer_identifier, 0x01_0203_0405);
+    assert_eq!(system_id.organizationally_unique_identifier, 0xaabbcc);
+
+    handle.set_services(Vec::new()).unwrap();
+    let err = device.device_information().await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+}
+
+#[tokio::test]
+async fn descriptor_values() {
+    let mock = MockAdapter::new();
+    let (_adapter, device, handle) = connect(&mock).await;
+    let level = battery_level(&device).await;
+    assert_eq!(
+        level.user_description().await.unwrap().as_deref(),
+        Some("Battery level")
+    );
+    assert_eq!(level.presentation_format().await.unwrap(), None);
+
+    let mut properties = CharacteristicProperties::default();
+    properties.read = true;
+    let mut temperature = MockCharacteristic::new(characteristics::TEMPERATURE, properties, vec![0x6d, 0x08]);
+    temperature.descriptors.push(MockDescriptor::new(
+        descriptors::CHARACTERISTIC_PRESENTATION_FORMAT,
+        vec![0x0e, 0xfe, 0x2f, 0x27, 0x01, 0x00, 0x00],
+    ));
+    let mut service = MockService::new(services::ENVIRONMENTAL_SENSING);
+    service.characteristics.push(temperature);
+    handle.set_services(vec![service]).unwrap();
+
+    let service = device
+        .discover_services_with_uuid(services::ENVIRONMENTAL_SENSING)
+        .await
+        .unwrap();
+    let temperature = &service[0].discover_characteristics().await.unwrap()[0];
+    assert_eq!(temperature.user_description().await.unwrap(), None);
+    let format = temperature.presentation_format().await.unwrap().unwrap();
+    assert_eq!(format.format, FormatType::SINT16);
+    let value = temperature.read().await.unwrap();
+    assert_eq!(format.format_value(&value).unwrap(), "21.57 °C");
+
+    let descriptor = &temperature.descriptors().await.unwrap()[0];
+    assert_eq!(descriptor.read_as::<PresentationFormat>().await.unwrap(), format);
+}
+
+#[tokio::test]
+async fn read_with_offset() {
+    let mock = MockAdapter::new();
+    let (_device, _handle, characteristics) = writable_characteristics(&mock).await;
+    let model = &characteristics[0];
+
+    // Values shorter than the MTU must be read in a single request
+    assert_eq!(model.read_with_offset(0).await.unwrap(), b"hello");
+    let err = model.read_with_offset(1).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Protocol(AttError::ATTRIBUTE_NOT_LONG));
+
+    let value: Vec<u8> = (0..100).collect();
+    model.write(&value).await.unwrap();
+    assert_eq!(model.read_with_offset(22).await.unwrap(), &value[22..]);
+    assert!(model.read_with_offset(100).await.unwrap().is_empty());
+    let err = model.read_with_offset(101).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Protocol(AttError::INVALID_OFFSET));
+}
+
+#[tokio::test]
+async fn write_stream() {
+    let mock = MockAdapter::new();
+    let mut peripheral = battery_peripheral();
+    peripheral.services[0].characteristics[0]
+        .properties
+        .write_without_response = true;
+    let handle = mock.add_peripheral(peripheral).unwrap();
+    let adapter = Adapter::with_mock(mock.clone());
+    let device = adapter.open_device(&handle.id()).await.unwrap();
+    adapter.connect_device(&device).await.unwrap();
+    let level = battery_level(&device).await;
+
+    let data: Vec<u8> = (0..100).collect();
+    let mut stream = level.write_stream().await.unwrap();
+    assert_eq!(stream.chunk_len(), 20);
+    stream.write_all(&data).await.unwrap();
+    stream.flush().await.unwrap();
+    assert_eq!(stream.bytes_written(), 100);
+    assert_eq!(
+        handle.characteristic_value(characteristics::BATTERY_LEVEL).unwrap(),
+        &data[80..]
+    );
+
+    let (_adapter, device, _handle) = connect(&mock).await;
+    let level = battery_level(&device).await;
+    let err = level.write_stream().await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotSupported);
+}
+
+#[tokio::test]
+async fn reliable_write() {
+    let mock = MockAdapter::new();
+    let (_device, handle, characteristics) = writable_characteristics(&mock).await;
+    let (model, serial, firmware) = (&characteristics[0], &characteristics[1], &characteristics[2]);
+
+    let mut transaction = ReliableWriteTransaction::new();
+    transaction.write(model, b"model").unwrap();
+    transaction.write(serial, b"serial").unwrap();
+    transaction.execute().await.unwrap();
+    assert_eq!(model.read().await.unwrap(), b"model");
+    assert_eq!(serial.read().await.unwrap(), b"serial");
+
+    // A failing write prevents every write in the transaction
+    let mut transaction = ReliableWriteTransaction::new();
+    transaction.write(model, b"other").unwrap();
+    transaction.write(firmware, b"2.0").unwrap();
+    let err = transaction.execute().await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Protocol(AttError::REQUEST_NOT_SUPPORTED));
+    assert_eq!(model.read().await.unwrap(), b"model");
+
+    let mut transaction = ReliableWriteTransaction::new();
+    transaction.write(serial, b"aborted").unwrap();
+    transaction.abort();
+    assert_eq!(serial.read().await.unwrap(), b"serial");
+
+    // Characteristics of other devices cannot be written in the same transaction
+    let (_other, _other_handle, other_characteristics) = writable_characteristics(&mock).await;
+    let mut transaction = ReliableWriteTransaction::new();
+    transaction.write(model, b"model").unwrap();
+    let err = transaction.write(&other_characteristics[0], b"model").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidParameter);
+
+    handle.disconnect();
+    let mut transaction = ReliableWriteTransaction::new();
+    transaction.write(model, b"model").unwrap();
+    let err = transaction.execute().await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotConnected);
+}
+
+#[tokio::test]
+async fn notifications_end_on_disconnect() {
+    let mock = MockAdapter::new();
+    let (adapter, device, handle) = connect(&mock).await;
+    let level = battery_level(&device).await;
+
+    let mut connection_events = adapter.device_connection_events(&device).await.unwrap();
+    let mut notifications = level.notify().await.unwrap();
+    assert!(level.is_notifying().await.unwrap());
+
+    handle.notify(characteristics::BATTERY_LEVEL, &[99]).unwrap();
+    assert_eq!(notifications.next().await.unwrap().unwrap(), vec![99]);
+
+    handle.disconnect();
+    let err = notifications.next().await.unwrap().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotConnected);
+    assert!(notifications.next().await.is_none());
+    assert_eq!(connection_events.next().await, Some(ConnectionEvent::Disconnected));
+
+    let err = level.read().await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotConnected);
+}
+
+#[tokio::test]
+async fn subscribe_and_unsubscribe() {
+    let mock = MockAdapter::new();
+    let mut peripheral = battery_peripheral();
+    peripheral.services[0].characteristics[0].properties.indicate = true;
+    let handle = mock.add_peripheral(peripheral).unwrap();
+    let adapter = Adapter::with_mock(mock.clone());
+    let device = adapter.open_device(&handle.id()).await.unwrap();
+    adapter.connect_device(&device).await.unwrap();
+    let level = battery_level(&device).await;
+
+    let mut notifications = level.subscribe(SubscriptionKind::Notify).await.unwrap();
+    let mut other_notifications = level.subscribe(SubscriptionKind::Notify).await.unwrap();
+    let before = Instant::now();
+    handle.notify(characteristics::BATTERY_LEVEL, &[42]).unwrap();
+
+    let notification = notifications.next().await.unwrap().unwrap();
+    assert_eq!(notification.value, vec![42]);
+    assert!(!notification.is_indication);
+    assert!(notification.received_at >= before);
+
+    // Notifications and indications share the Client Characteristic Configuration descriptor
+    let err = level.subscribe(SubscriptionKind::Indicate).await.err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::InvalidParameter);
+
+    assert_eq!(level.unsubscribe().await.unwrap(), 2);
+    assert!(notifications.next().await.is_none());
+    assert!(other_notifications.next().await.is_none());
+    assert!(!level.is_notifying().await.unwrap());
+    assert_eq!(level.unsubscribe().await.unwrap(), 0);
+
+    let mut indications = level.subscribe(SubscriptionKind::Indicate).await.unwrap();
+    handle.notify(characteristics::BATTERY_LEVEL, &[43]).unwrap();
+    let indication = indications.next().await.unwrap().unwrap();
+    assert_eq!(indication.value, vec![43]);
+    assert!(indication.is_indication);
+    drop(indications);
+
+    // Battery levels only support notifications in the default peripheral
+    let (_adapter, device, _handle) = connect(&mock).await;
+    let err = battery_level(&device)
+        .await
+        .subscribe(SubscriptionKind::Indicate)
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(err.kind(), ErrorKind::NotSupported);
+}
+
+#[tokio::test]
+async fn shared_notifications() {
+    let mock = MockAdapter::new();
+    let (_adapter, device, handle) = connect(&mock).await;
+    let level = battery_level(&device).await;
+    let other = battery_level(&device).await;
+
+    let mut first = level.notify().await.unwrap();
+    let mut second = other.notify().await.unwrap();
+    handle.notify(characteristics::BATTERY_LEVEL, &[1]).unwrap();
+    assert_eq!(first.next().await.unwrap().unwrap(), vec![1]);
+    assert_eq!(second.next().await.unwrap().unwrap(), vec![1]);
+
+    // Notifications stay enabled until the last subscriber is dropped
+    drop(first);
+    assert!(level.is_notifying().await.unwrap());
+    handle.notify(characteristics::BATTERY_LEVEL, &[2]).unwrap();
+    assert_eq!(second.next().await.unwrap().unwrap(), vec![2]);
+    drop(second);
+    assert!(!level.is_notifying().await.unwrap());
+}
+
+#[tokio::test]
+async fn notification_lag_policies() {
+    let mock = MockAdapter::new();
+    let (_adapter, device, handle) = connect(&mock).await;
+    let level = battery_level(&device).await;
+
+    let mut options = SubscriberOptions::default();
+    options.buffer_len = 2;
+    let mut dropping = level
+        .subscribe_with_options(SubscriptionKind::Notify, options)
+        .await
+        .unwrap();
+    options.lag_policy = LagPolicy::Error;
+    let mut erroring = level
+        .subscribe_with_options(SubscriptionKind::Notify, options)
+        .await
+        .unwrap();
+
+    for value in 1..=3 {
+        handle.notify(characteristics::BATTERY_LEVEL, &[value]).unwrap();
+    }
+    assert_eq!(dropping.next().await.unwrap().unwrap().value, vec![2]);
+    assert_eq!(dropping.next().await.unwrap().unwrap().value, vec![3]);
+
+    let err = erroring.next().await.unwrap().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Lagged);
+    assert_eq!(erroring.next().await.unwrap().unwrap().value, vec![2]);
+    assert_eq!(erroring.next().await.unwrap().unwrap().value, vec![3]);
+}
+
+#[tokio::test]
+async fn services_changed() {
+    let mock = MockAdapter::new();
+    let (_adapter, device, handle) = connect(&mock).await;
+    let battery = device.discover_services().await.unwrap().remove(0);
+
+    let mut indications = device.service_changed_indications().await.unwrap();
+    handle
+        .set_services(vec![MockService::new(services::HEART_RATE)])
+        .unwrap();
+    let changed = indications.next().await.unwrap().unwrap();
+    assert!(changed.was_invalidated(&battery));
+
+    let services = device.discover_services().await.unwrap();
+    assert_eq!(services.len(), 1);
+    assert_eq!(services[0].uuid(), services::HEART_RATE);
+}
+
+#[tokio::test]
+async fn adapter_availability() {
+    let mock = MockAdapter::new();
+    let (adapter, device, _handle) = connect(&mock).await;
+    let mut events = adapter.events().await.unwrap();
+
+    mock.set_available(false);
+    assert_eq!(events.next().await.unwrap().unwrap(), AdapterEvent::Unavailable);
+    assert!(!device.is_connected().await);
+    let err = adapter.connect_device(&device).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::AdapterUnavailable);
+
+    mock.set_available(true);
+    adapter.wait_available().await.unwrap();
+    adapter.connect_device(&device).await.unwrap();
+    assert_eq!(adapter.connected_devices().await.unwrap(), vec![device]);
+}
+
+#[tokio::test]
+async fn disconnect_after_operations() {
+    let mock = MockAdapter::new();
+    let (_adapter, device, handle) = connect(&mock).await;
+    let level = battery_level(&device).await;
+
+    handle.disconnect_after(Some(1));
+    assert_eq!(level.read().await.unwrap(), vec![100]);
+    let err = level.read().await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotConnected);
+    assert!(!handle.is_connected());
+}
+
+#[tokio::test]
+async fn injected_characteristic_error() {
+    let mock = MockAdapter::new();
+    let (_adapter, device, handle) = connect(&mock).await;
+    let level = battery_level(&device).await;
+
+    handle
+        .set_characteristic_error(
+            characteristics::BATTERY_LEVEL,
+            Some(AttError::INSUFFICIENT_AUTHENTICATION),
+        )
+        .unwrap();
+    let err = level.read().await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Protocol(AttError::INSUFFICIENT_AUTHENTICATION));
+    let err = level.notify().await.err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::Protocol(AttError::INSUFFICIENT_AUTHENTICATION));
+
+    handle
+        .set_characteristic_error(characteristics::BATTERY_LEVEL, None)
+        .unwrap();
+    assert_eq!(level.read().await.unwrap(), vec![100]);
+}
+
+#[tokio::test]
+async fn delayed_responses() {
+    let mock = MockAdapter::new();
+    let (_adapter, device, handle) = connect(&mock).await;
+    let level = battery_level(&device).await;
+
+    handle.set_response_delay(Some(Duration::from_millis(50)));
+    let start = Instant::now();
+    assert_eq!(level.read().await.unwrap(), vec![100]);
+    assert!(start.elapsed() >= Duration::from_millis(50));
+}
+
+#[tokio::test]
+async fn dropped_notifications() {
+    let mock = MockAdapter::new();
+    let (_adapter, device, handle) = connect(&mock).await;
+    let level = battery_level(&device).await;
+    let mut notifications = level.notify().await.unwrap();
+
+    handle.set_drop_notifications(true);
+    handle.notify(characteristics::BATTERY_LEVEL, &[1]).unwrap();
+    handle.set_drop_notifications(false);
+    handle.notify(characteristics::BATTERY_LEVEL, &[2]).unwrap();
+    assert_eq!(notifications.next().await.unwrap().unwrap(), vec![2]);
+}
+
+#[tokio::test]
+async fn attribute_handles() {
+    let mock = MockAdapter::new();
+    let (_adapter, device, _handle) = connect(&mock).await;
+    let battery = device.discover_services().await.unwrap().remove(0);
+    assert_eq!(battery.handle().unwrap(), 1);
+    assert_eq!(battery.end_handle().unwrap(), 4);
+
+    // The declaration handle, followed by the value handle 3
+    let level = battery_level(&device).await;
+    assert_eq!(level.handle().unwrap(), 2);
+    let descriptors = level.discover_descriptors().await.unwrap();
+    assert_eq!(descriptors[0].handle().unwrap(), 4);
+}
+
+#[test]
+fn attribute_handle_exhaustion() {
+    let mock = MockAdapter::new();
+    let properties = CharacteristicProperties::default();
+
+    // The service declaration and 0x7fff characteristics use every handle up to 0xffff
+    let mut service = MockService::new(services::BATTERY);
+    service.characteristics = (0..0x7fff)
+        .map(|_| MockCharacteristic::new(characteristics::BATTERY_LEVEL, properties, Vec::new()))
+        .collect();
+    let mut peripheral = MockPeripheral::new("Full");
+    peripheral.services.push(service.clone());
+    let handle = mock.add_peripheral(peripheral).unwrap();
+
+    service.characteristics.push(MockCharacteristic::new(
+        characteristics::BATTERY_LEVEL,
+        properties,
+        Vec::new(),
+    ));
+    let err = handle.set_services(vec![service.clone()]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidParameter);
+    let mut peripheral = MockPeripheral::new("Overflow");
+    peripheral.services.push(service);
+    let err = mock.add_peripheral(peripheral).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidParameter);
+}
+
+#[tokio::test]
+async fn services_changed_range() {
+    let mock = MockAdapter::new();
+    let (_adapter, device, handle) = connect(&mock).await;
+    let battery = device.discover_services().await.unwrap().remove(0);
+    let mut indications = device.service_changed_indications().await.unwrap();
+
+    handle.send_services_changed(0x0100..=0x01ff);
+    let changed = indications.next().await.unwrap().unwrap();
+    assert!(!changed.was_invalidated(&battery));
+
+    handle.send_services_changed(0x0001..=0x0004);
+    let changed = indications.next().await.unwrap().unwrap();
+    assert!(changed.was_invalidated(&battery));
+
+    // A range overlapping the end of the service invalidates it
+    handle.send_services_changed(0x0003..=0x0010);
+    let changed = indications.next().await.unwrap().unwrap();
+    assert!(changed.was_invalidated(&battery));
+}
+
+#[tokio::test]
+async fn gatt_snapshot() {
+    let mock = MockAdapter::new();
+    let (_adapter, device, handle) = connect(&mock).await;
+
+    let structure = device.gatt_snapshot(false).await.unwrap();
+    assert_eq!(structure.services.len(), 1);
+    let service = &structure.services[0];
+    assert_eq!(service.uuid, services::BATTERY);
+    let level = &service.characteristics[0];
+    assert_eq!(level.uuid, characteristics::BATTERY_LEVEL);
+    assert!(level.properties.read && level.properties.notify);
+    assert_eq!(level.value, None);
+    assert_eq!(level.descriptors[0].uuid, descriptors::CHARACTERISTIC_USER_DESCRIPTION);
+    assert_eq!(level.descriptors[0].value, None);
+
+    let before = device.gatt_snapshot(true).await.unwrap();
+    assert_eq!(before.services[0].characteristics[0].value, Some(vec![100]));
+    assert_eq!(
+        before.services[0].characteristics[0].descriptors[0].value.as_deref(),
+        Some(&b"Battery level"[..])
+    );
+    assert_eq!(before.without_values(), structure);
+    assert!(before.diff(&before).is_empty());
+
+    handle
+        .set_characteristic_value(characteristics::BATTERY_LEVEL, &[42])
+        .unwrap();
+    let after = device.gatt_snapshot(true).await.unwrap();
+    assert_eq!(
+        before.diff(&after),
+        vec![GattDifference::CharacteristicValueChanged {
+            service: services::BATTERY,
+            characteristic: characteristics::BATTERY_LEVEL,
+            old: Some(vec![100]),
+            new: Some(vec![42]),
+        }]
+    );
+
+    let mut service = MockService::new(services::DEVICE_INFORMATION);
+    service.characteristics.push(MockCharacteristic::new(
+        characteristics::MANUFACTURER_NAME_STRING,
+        CharacteristicProperties::default(),
+        Vec::new(),
+    ));
+    handle.set_services(vec![service]).unwrap();
+    let changed = device.gatt_snapshot(false).await.unwrap();
+    assert_eq!(
+        structure.diff(&changed),
+        vec![
+            GattDifference::ServiceRemoved {
+                service: services::BATTERY
+            },
+            GattDifference::ServiceAdded {
+                service: services::DEVICE_INFORMATION
+            },
+        ]
+    );
+
+    handle.disconnect();
+    let err = device.gatt_snapshot(true).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotConnected);
+}
+
+#[tokio::test]
+async fn gatt_server() {
+    let mock = MockAdapter::new();
+    let adapter = Adapter::with_mock(mock.clone());
+
+    let mut properties = CharacteristicProperties::default();
+    properties.read = true;
+    properties.write = true;
+    properties.notify = true;
+
+    let value = std::sync::Arc::new(std::sync::Mutex::new(vec![50u8]));
+    let (read_value, write_value) = (value.clone(), value.clone());
+    let level = LocalCharacteristic::new(characteristics::BATTERY_LEVEL, properties)
+        .on_read(move |_| {
+            let value = read_value.lock().unwrap().clone();
+            async move { Ok(value) }
+        })
+        .on_write(move |request| {
+            let value = write_value.clone();
+            async move {
+                if request.value.len() != 1 {
+                    return Err(AttError::INVALID_ATTRIBUTE_VALUE_LENGTH);
+                }
+                *value.lock().unwrap() = request.value;
+                Ok(())
+            }
+        })
+        .on_subscribe(|mut notifier| async move {
+            assert_eq!(notifier.kind(), SubscriptionKind::Notify);
+            for level in [49, 48] {
+                notifier.notify(&[level]).await.unwrap();
+            }
+        });
+    let mut service = LocalService::new(services::BATTERY);
+    service.characteristics.push(level);
+    service.characteristics[0].descriptors.push(
+        LocalDescriptor::new(descriptors::CHARACTERISTIC_USER_DESCRIPTION)
+            .on_read(|_| async { Ok(b"Battery level".to_vec()) }),
+    );
+    let server = adapter
+        .serve_gatt(Application {
+            services: vec![service],
+        })
+        .await
+        .unwrap();
+
+    let central = mock.central();
+    assert_eq!(central.services(), vec![services::BATTERY]);
+    assert_eq!(central.read(characteristics::BATTERY_LEVEL).await.unwrap(), vec![50]);
+    central.write(characteristics::BATTERY_LEVEL, &[75]).await.unwrap();
+    assert_eq!(central.read(characteristics::BATTERY_LEVEL).await.unwrap(), vec![75]);
+    let err = central
+        .write(characteristics::BATTERY_LEVEL, &[1, 2])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.kind(),
+        ErrorKind::Protocol(AttError::INVALID_ATTRIBUTE_VALUE_LENGTH)
+    );
+    let err = central
+        .write_without_response(characteristics::BATTERY_LEVEL, &[1])
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Protocol(AttError::WRITE_NOT_PERMITTED));
+
+    assert_eq!(
+        central
+            .read_descriptor(
+                characteristics::BATTERY_LEVEL,
+                descriptors::CHARACTERISTIC_USER_DESCRIPTION
+            )
+            .await
+            .unwrap(),
+        b"Battery level"
+    );
+    let err = central
+        .write_descriptor(
+            characteristics::BATTERY_LEVEL,
+            descriptors::CHARACTERISTIC_USER_DESCRIPTION,
+            b"Level",
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Protocol(AttError::WRITE_NOT_PERMITTED));
+
+    let err = central
+        .subscribe(characteristics::BATTERY_LEVEL, SubscriptionKind::Indicate)
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(err.kind(), ErrorKind::NotSupported);
+    let mut notifications = central
+        .subscribe(characteristics::BATTERY_LEVEL, SubscriptionKind::Notify)
+        .await
+        .unwrap();
+    assert_eq!(notifications.next().await.unwrap(), vec![49]);
+    assert_eq!(notifications.next().await.unwrap(), vec![48]);
+    // The stream ends once the handler returns
+    assert_eq!(notifications.next().await, None);
+
+    drop(server);
+    assert!(central.services().is_empty());
+    let err = central.read(characteristics::BATTERY_LEVEL).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+}
+
+#[tokio::test]
+async fn advertising() {
+    let mock = MockAdapter::new();
+    let central = mock.central();
+    let adapter = Adapter::with_mock(mock.clone());
+
+    let mut advertisement = Advertisement::default();
+    advertisement.local_name = Some("Battery".to_string());
+    advertisement.services.push(services::BATTERY);
+    advertisement.service_data.insert(services::BATTERY, vec![87]);
+    advertisement.manufacturer_data.push(ManufacturerData {
+        company_id: 0x1234,
+        data: vec![1, 2, 3],
+    });
+    advertisement.tx_power = Some(4);
+    let handle = adapter.advertise(advertisement.clone()).await.unwrap();
+
+    let mut beacon = Advertisement::default();
+    beacon.is_connectable = false;
+    beacon.min_interval = Some(Duration::from_millis(100));
+    beacon.max_interval = Some(Duration::from_millis(200));
+    let beacon_handle = adapter.advertise(beacon.clone()).await.unwrap();
+    assert_eq!(central.advertisements(), vec![advertisement, beacon.clone()]);
+
+    drop(handle);
+    assert_eq!(central.advertisements(), vec![beacon]);
+    drop(beacon_handle);
+    assert!(central.advertisements().is_empty());
+
+    let mut invalid = Advertisement::default();
+    invalid.min_interval = Some(Duration::from_millis(200));
+    invalid.max_interval = Some(Duration::from_millis(100));
+    let err = adapter.advertise(invalid).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidParameter);
+
+    mock.set_available(false);
+    let err = adapter.advertise(Advertisement::default()).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::AdapterUnavailable);
+}